@@ -0,0 +1,166 @@
+//! Distant-observer image plane (Bardeen screen coordinates).
+//!
+//! An observer at infinity and inclination theta_obs labels each arriving
+//! photon by its apparent position (alpha, beta) on the sky, in units of M:
+//!
+//!   alpha = -lambda / sin(theta_obs)
+//!   beta  = +/- sqrt(eta + a^2 cos^2(theta_obs) - lambda^2 cot^2(theta_obs))
+//!
+//! where lambda = L_z / E and eta = Q / E^2 are the photon's conserved
+//! ratios, and the sign of beta is the sign of the arriving photon's p_theta.
+//! alpha runs along e_phi and beta along -e_theta (towards the projected
+//! spin axis), matching the +X / +Y axes of [`Camera`](super::Camera).
+//!
+//! # References
+//!
+//! - Bardeen, J. M. (1973). "Timelike and null geodesics in the Kerr metric"
+//! - Cunningham, C. T. & Bardeen, J. M. (1973). "The optical appearance of a
+//!   star orbiting an extreme Kerr black hole"
+
+use crate::geodesic::GeodesicState;
+use crate::metric::kerr::CoordinateSystem;
+use crate::metric::{Kerr, Metric};
+
+/// Conserved ratios (lambda, eta) of the photon seen at screen position
+/// (alpha, beta) by an observer at inclination `theta_obs`.
+pub fn screen_to_constants(alpha: f64, beta: f64, spin_a: f64, theta_obs: f64) -> (f64, f64) {
+    let cos2 = theta_obs.cos().powi(2);
+    let lambda = -alpha * theta_obs.sin();
+    let eta = beta * beta + (alpha * alpha - spin_a * spin_a) * cos2;
+    (lambda, eta)
+}
+
+/// beta^2 = Theta(theta_obs) = eta + a^2 cos^2 - lambda^2 cot^2.
+///
+/// Negative where no photon with these constants reaches theta_obs.
+pub fn beta_squared(lambda: f64, eta: f64, spin_a: f64, theta_obs: f64) -> f64 {
+    let cos2 = theta_obs.cos().powi(2);
+    let sin2 = theta_obs.sin().powi(2).max(1e-300);
+    eta + spin_a * spin_a * cos2 - lambda * lambda * cos2 / sin2
+}
+
+/// Screen position (alpha, beta) of a photon with conserved ratios
+/// (lambda, eta), taking the sign of beta from `beta_sign`.
+///
+/// Returns `None` if the photon cannot reach the observer's inclination.
+pub fn constants_to_screen(
+    lambda: f64,
+    eta: f64,
+    spin_a: f64,
+    theta_obs: f64,
+    beta_sign: f64,
+) -> Option<(f64, f64)> {
+    let beta_sq = beta_squared(lambda, eta, spin_a, theta_obs);
+    if beta_sq < 0.0 {
+        return None;
+    }
+    let alpha = -lambda / theta_obs.sin();
+    Some((alpha, beta_sq.sqrt().copysign(beta_sign)))
+}
+
+/// Backward null ray for screen position (alpha, beta), placed at radius
+/// `r_obs` and inclination `theta_obs` on the phi = 0 meridian.
+///
+/// The ray carries exactly the (lambda, eta) of the photon that reaches
+/// infinity at (alpha, beta), normalized to |p_t| = 1. Like
+/// [`Camera`](super::Camera) rays it is the time reverse of that photon,
+/// so p_t = +1 and the ray initially moves inward. Uses the metric's own
+/// coordinate system.
+pub fn distant_ray(bh: &Kerr, alpha: f64, beta: f64, theta_obs: f64, r_obs: f64) -> GeodesicState {
+    let a = bh.a();
+    let (lambda, eta) = screen_to_constants(alpha, beta, a, theta_obs);
+
+    // Radial potential R(r) for E = 1; the arriving photon has
+    // p_r = +sqrt(R)/Delta and p_theta = beta; the traced ray is its negative.
+    let delta = bh.delta(r_obs);
+    let k = r_obs * r_obs + a * a - a * lambda;
+    let radial = (k * k - delta * (eta + (lambda - a).powi(2))).max(0.0);
+    let p_bl = [1.0, -radial.sqrt() / delta, -beta, -lambda];
+    let p = match bh.coordinate_system() {
+        CoordinateSystem::BoyerLindquist => p_bl,
+        CoordinateSystem::KerrSchild => bh.covector_bl_to_ks(r_obs, p_bl),
+    };
+
+    GeodesicState {
+        x: [0.0, r_obs, theta_obs, 0.0],
+        p,
+    }
+}
+
+/// Screen position (alpha, beta) at which a ray at inclination
+/// theta = state.theta() would be seen by a distant observer there.
+///
+/// Works for both future-directed photons and backward-traced rays.
+/// Returns `None` for rays with zero energy.
+pub fn screen_position<M: Metric>(metric: &M, state: &GeodesicState) -> Option<(f64, f64)> {
+    let energy = -state.p[0];
+    if energy.abs() < 1e-300 {
+        return None;
+    }
+    let a = metric.spin() * metric.mass();
+    let theta = state.theta();
+    let lambda = state.p[3] / energy;
+    // Arriving photon's p_theta, per unit energy.
+    let p_theta = state.p[2] / energy;
+    let cos2 = theta.cos().powi(2);
+    let sin2 = theta.sin().powi(2).max(1e-300);
+    let eta = p_theta * p_theta + cos2 * (lambda * lambda / sin2 - a * a);
+    constants_to_screen(lambda, eta, a, theta, p_theta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Camera;
+    use crate::geodesic::{integrate, IntegrationOptions, TerminationReason};
+    use crate::invariants::hamiltonian;
+
+    #[test]
+    fn test_screen_constants_round_trip() {
+        let a = 0.8;
+        let theta_obs = 1.1;
+        for &(alpha, beta) in &[(3.0, -2.0), (-5.5, 0.5), (0.2, 7.0)] {
+            let (lambda, eta) = screen_to_constants(alpha, beta, a, theta_obs);
+            let (al, be) = constants_to_screen(lambda, eta, a, theta_obs, beta).unwrap();
+            assert!((al - alpha).abs() < 1e-12 && (be - beta).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_distant_ray_is_null_and_maps_back() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            let ray = distant_ray(&bh, 4.0, -3.0, 1.2, 500.0);
+            assert!(hamiltonian(&ray, &bh).abs() < 1e-10);
+            let (alpha, beta) = screen_position(&bh, &ray).unwrap();
+            assert!((alpha - 4.0).abs() < 1e-10 && (beta + 3.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_far_camera_axes_match_screen_axes() {
+        // A camera far away sees (alpha, beta) ~ r * (X, Y) in its image plane.
+        let bh = Kerr::new(1.0, 0.6);
+        let r = 1.0e5;
+        let cam = Camera::static_observer(&bh, [0.0, r, 1.2, 0.0], 2e-4, 101, 101).unwrap();
+        let frame = cam.tetrad(&bh);
+        let ray = cam.ray(&frame, 80.0, 30.0).unwrap();
+        let (alpha, beta) = screen_position(&bh, &ray).unwrap();
+
+        let n = cam.direction(80.0, 30.0).unwrap();
+        let expected = (r * n[2] / -n[0], r * -n[1] / -n[0]);
+        assert!((alpha - expected.0).abs() < 1e-2 * expected.0.abs());
+        assert!((beta - expected.1).abs() < 1e-2 * expected.1.abs());
+    }
+
+    #[test]
+    fn test_shadow_interior_captured_exterior_escapes() {
+        let bh = Kerr::kerr_schild(1.0, 0.9);
+        let options = IntegrationOptions::default();
+
+        let inside = integrate(&distant_ray(&bh, 1.0, 0.5, 1.4, 200.0), &bh, &options);
+        assert_eq!(inside.termination, TerminationReason::Horizon);
+
+        let outside = integrate(&distant_ray(&bh, 9.0, 0.5, 1.4, 200.0), &bh, &options);
+        assert_eq!(outside.termination, TerminationReason::Escape);
+    }
+}
@@ -0,0 +1,373 @@
+//! Local-observer camera.
+//!
+//! A [`Camera`] sits at a spacetime event, moves with a 4-velocity u^mu and
+//! looks through its own orthonormal frame ([`Tetrad::adapted`]). Each pixel
+//! maps, through the camera's [`Projection`], to a unit direction in that frame,
+//! which is turned into an exactly null covariant momentum ready for
+//! [`integrate`](crate::geodesic::integrate).
+//!
+//! # Ray direction
+//!
+//! The emitted rays are traced backwards: the momentum is the negative of
+//! the photon that arrives at the camera from the pixel's direction. Forward
+//! integration in the affine parameter then retraces the photon's history
+//! into the scene, with frame dragging acting in the physical sense. These
+//! rays have a positive p_t; conserved ratios such as L/E and Q/E^2 are
+//! unaffected by the overall sign.
+//!
+//! # Orientation
+//!
+//! Camera space follows the usual graphics convention: +X right, +Y up,
+//! looking down -Z. With the identity orientation the camera looks towards
+//! the centre (-e_r), +Y points towards the north pole (-e_theta) and +X
+//! points along +e_phi. The `orientation` quaternion rotates camera space
+//! before this mapping.
+//!
+//! # Moving cameras
+//!
+//! Because rays are built in the observer's own frame, special-relativistic
+//! aberration is automatic: a camera with a large velocity relative to the
+//! local static frame sees the sky bunched towards its direction of motion.
+//! [`Camera::frequency_shift`] and [`Camera::sky_frequency_shift`] give the
+//! matching Doppler and gravitational shifts for each ray.
+//!
+//! For observers at infinity, [`image_plane`] maps Bardeen screen
+//! coordinates (alpha, beta) to rays along the same axes.
+
+pub mod image_plane;
+pub mod projection;
+
+pub use image_plane::{distant_ray, screen_position};
+pub use projection::Projection;
+
+use glam::{DQuat, DVec3};
+
+use crate::geodesic::GeodesicState;
+use crate::metric::{contract, Metric};
+use crate::tetrad::{boosted_velocity, static_velocity, zamo_velocity, Tetrad};
+
+/// A camera carried by a local observer.
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    /// Event x^mu = (t, r, theta, phi) in the metric's coordinates.
+    pub position: [f64; 4],
+    /// Observer 4-velocity u^mu in the metric's coordinates (normalized, u.u = -1).
+    pub velocity: [f64; 4],
+    /// Rotation of camera space relative to the default view (see module docs).
+    pub orientation: DQuat,
+    /// Pixel-to-direction mapping.
+    pub projection: Projection,
+    /// Field of view in radians (vertical for pinhole, image circle for fisheye).
+    pub fov: f64,
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+}
+
+impl Camera {
+    /// Create a camera at `position` moving with 4-velocity `velocity`.
+    pub fn new(position: [f64; 4], velocity: [f64; 4], fov: f64, width: u32, height: u32) -> Self {
+        Self {
+            position,
+            velocity,
+            orientation: DQuat::IDENTITY,
+            projection: Projection::Pinhole,
+            fov,
+            width,
+            height,
+        }
+    }
+
+    /// Camera carried by the zero-angular-momentum observer at `position`.
+    pub fn zamo<M: Metric>(
+        metric: &M,
+        position: [f64; 4],
+        fov: f64,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let u = zamo_velocity(metric, position[1], position[2]);
+        Self::new(position, u, fov, width, height)
+    }
+
+    /// Camera carried by the static observer at `position`.
+    ///
+    /// Returns `None` inside the ergoregion.
+    pub fn static_observer<M: Metric>(
+        metric: &M,
+        position: [f64; 4],
+        fov: f64,
+        width: u32,
+        height: u32,
+    ) -> Option<Self> {
+        static_velocity(metric, position[1], position[2])
+            .map(|u| Self::new(position, u, fov, width, height))
+    }
+
+    /// Camera carried by an observer moving with 3-velocity `v` = (v_r,
+    /// v_theta, v_phi) relative to the ZAMO at `position` (see
+    /// [`boosted_velocity`]).
+    ///
+    /// Returns `None` unless |v| < 1.
+    pub fn moving<M: Metric>(
+        metric: &M,
+        position: [f64; 4],
+        v: [f64; 3],
+        fov: f64,
+        width: u32,
+        height: u32,
+    ) -> Option<Self> {
+        boosted_velocity(metric, position[1], position[2], v)
+            .map(|u| Self::new(position, u, fov, width, height))
+    }
+
+    /// Replace the camera orientation.
+    pub fn with_orientation(mut self, orientation: DQuat) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Replace the projection.
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Width / height.
+    #[inline]
+    pub fn aspect(&self) -> f64 {
+        self.width as f64 / self.height.max(1) as f64
+    }
+
+    /// The observer's orthonormal frame at the camera position.
+    pub fn tetrad<M: Metric>(&self, metric: &M) -> Tetrad {
+        Tetrad::adapted(metric, self.position[1], self.position[2], self.velocity)
+    }
+
+    /// Unit viewing direction in the frame (e_r, e_theta, e_phi) for the
+    /// continuous image coordinate (x, y).
+    ///
+    /// Pixel (i, j) covers [i, i+1] x [j, j+1], with row 0 at the top.
+    /// Returns `None` where the projection has no ray (outside a fisheye circle).
+    pub fn direction(&self, x: f64, y: f64) -> Option<[f64; 3]> {
+        let u = x / self.width as f64;
+        let v = y / self.height as f64;
+        let cam = self.projection.direction(u, v, self.fov, self.aspect())?;
+        Some(camera_to_frame(self.orientation * cam))
+    }
+
+    /// Unit frame direction of the image's up axis, camera-space +Y.
+    pub fn up(&self) -> [f64; 3] {
+        camera_to_frame(self.orientation * DVec3::Y)
+    }
+
+    /// Image coordinate (x, y) of a frame direction, or `None` if the
+    /// direction is outside the image (e.g. behind a pinhole camera).
+    pub fn image_position(&self, direction: [f64; 3]) -> Option<(f64, f64)> {
+        let cam = self.orientation.inverse() * frame_to_camera(direction);
+        let (u, v) = self.projection.image_coords(cam, self.fov, self.aspect())?;
+        Some((u * self.width as f64, v * self.height as f64))
+    }
+
+    /// Backward-traced null ray through image coordinate (x, y), with unit
+    /// energy measured by the camera.
+    pub fn ray(&self, tetrad: &Tetrad, x: f64, y: f64) -> Option<GeodesicState> {
+        let n = self.direction(x, y)?;
+        // Arriving photon: k^(a) = (1, -n). Traced ray: p = -k.
+        let p = tetrad.covector_from_frame(&[-1.0, n[0], n[1], n[2]]);
+        Some(GeodesicState {
+            x: self.position,
+            p,
+        })
+    }
+
+    /// Ray through the centre of pixel (i, j).
+    pub fn pixel_ray(&self, tetrad: &Tetrad, i: u32, j: u32) -> Option<GeodesicState> {
+        self.ray(tetrad, i as f64 + 0.5, j as f64 + 0.5)
+    }
+
+    /// Frequency ratio nu_camera / nu_ref for a ray at the camera event, where
+    /// nu_ref is measured by an observer with 4-velocity `u_ref` at the same event.
+    ///
+    /// Values above 1 mean the camera sees the light blueshifted relative to
+    /// that observer (for example when flying into the light).
+    pub fn frequency_shift(&self, ray: &GeodesicState, u_ref: &[f64; 4]) -> f64 {
+        contract(&ray.p, &self.velocity) / contract(&ray.p, u_ref)
+    }
+
+    /// Frequency ratio nu_camera / nu_emit for light along `ray` from a source
+    /// at rest at infinity: the combined gravitational and Doppler shift of
+    /// the background sky.
+    pub fn sky_frequency_shift(&self, ray: &GeodesicState) -> f64 {
+        contract(&ray.p, &self.velocity) / ray.p[0]
+    }
+
+    /// Rays for every pixel, in row-major order from the top-left.
+    pub fn rays<M: Metric>(&self, metric: &M) -> Vec<Option<GeodesicState>> {
+        let tetrad = self.tetrad(metric);
+        let mut rays = Vec::with_capacity((self.width * self.height) as usize);
+        for j in 0..self.height {
+            for i in 0..self.width {
+                rays.push(self.pixel_ray(&tetrad, i, j));
+            }
+        }
+        rays
+    }
+}
+
+/// Camera-space vector to frame components (e_r, e_theta, e_phi).
+fn camera_to_frame(v: DVec3) -> [f64; 3] {
+    [v.z, -v.y, v.x]
+}
+
+/// Frame components (e_r, e_theta, e_phi) to a camera-space vector.
+fn frame_to_camera(n: [f64; 3]) -> DVec3 {
+    DVec3::new(n[2], -n[1], n[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invariants::hamiltonian;
+    use crate::metric::Kerr;
+
+    #[test]
+    fn test_rays_are_null_in_both_coordinates() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            let cam = Camera::zamo(&bh, [0.0, 15.0, 1.3, 0.0], 0.8, 8, 6);
+            for ray in cam.rays(&bh) {
+                assert!(hamiltonian(&ray.unwrap(), &bh).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_panoramic_projections_emit_null_rays() {
+        let bh = Kerr::kerr_schild(1.0, 0.9);
+        let pos = [0.0, 12.0, 1.0, 0.0];
+        let equirect =
+            Camera::zamo(&bh, pos, 0.0, 16, 8).with_projection(Projection::Equirectangular);
+        let cubemap = Camera::zamo(&bh, pos, 0.0, 24, 4).with_projection(Projection::Cubemap);
+        for cam in [equirect, cubemap] {
+            for ray in cam.rays(&bh) {
+                assert!(hamiltonian(&ray.unwrap(), &bh).abs() < 1e-12);
+            }
+        }
+
+        let fisheye =
+            Camera::zamo(&bh, pos, std::f64::consts::PI, 8, 8).with_projection(Projection::Fisheye);
+        let rays = fisheye.rays(&bh);
+        assert!(rays[0].is_none(), "corner lies outside the image circle");
+        assert!(rays[4 * 8 + 4].is_some());
+    }
+
+    #[test]
+    fn test_centre_pixel_looks_inward() {
+        let bh = Kerr::new(1.0, 0.5);
+        let cam = Camera::static_observer(&bh, [0.0, 30.0, 1.2, 0.0], 0.5, 9, 9).unwrap();
+        let frame = cam.tetrad(&bh);
+        let ray = cam.pixel_ray(&frame, 4, 4).unwrap();
+        let local = frame.measure(&ray.p);
+        let dir = local.direction();
+        assert!((dir[0] + 1.0).abs() < 1e-12);
+        // Backward ray: inward radial momentum, past-directed.
+        assert!(ray.p[1] < 0.0);
+        assert!((local.energy + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_moving_camera_matches_boosted_zamo() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            let pos = [0.0, 6.0, 1.1, 0.0];
+            let v = [-0.3, 0.1, 0.5];
+            let cam = Camera::moving(&bh, pos, v, 1.0, 8, 6).unwrap();
+            let g = bh.covariant(pos[1], pos[2]);
+            let mut norm = 0.0;
+            for mu in 0..4 {
+                for nu in 0..4 {
+                    norm += g.get(mu, nu) * cam.velocity[mu] * cam.velocity[nu];
+                }
+            }
+            assert!((norm + 1.0).abs() < 1e-12);
+            let zamo = Tetrad::zamo(&bh, pos[1], pos[2]);
+            let measured = zamo.relative_velocity(&cam.velocity);
+            for i in 0..3 {
+                assert!((measured[i] - v[i]).abs() < 1e-12);
+            }
+            for ray in cam.rays(&bh) {
+                assert!(hamiltonian(&ray.unwrap(), &bh).abs() < 1e-12);
+            }
+            assert!(Camera::moving(&bh, pos, [0.6, 0.0, 0.8], 1.0, 8, 6).is_none());
+        }
+    }
+
+    #[test]
+    fn test_infalling_camera_aberration_and_blueshift() {
+        use crate::tetrad::radial_infall_velocity;
+
+        // Schwarzschild raindrop at r = 8M: v = sqrt(2M/r) = 0.5 relative to
+        // the static observer, moving along -e_r (the default view direction).
+        let bh = Kerr::new(1.0, 0.0);
+        let pos = [0.0, 8.0, 1.3, 0.0];
+        let u = radial_infall_velocity(&bh, pos[1], pos[2]).unwrap();
+        let cam = Camera::new(pos, u, 1.2, 33, 33);
+        let frame = cam.tetrad(&bh);
+        let static_cam = Camera::static_observer(&bh, pos, 1.2, 33, 33).unwrap();
+        let static_frame = static_cam.tetrad(&bh);
+        let v = 0.5;
+
+        let ray = cam.pixel_ray(&frame, 30, 16).unwrap();
+        let n_cam = frame.measure(&ray.p).direction();
+        let n_static = static_frame.measure(&ray.p).direction();
+        let cos_cam = -n_cam[0];
+        let cos_static = -n_static[0];
+
+        // The static observer sees the same star further from the forward
+        // direction: cos_cam = (cos_static + v) / (1 + v cos_static).
+        let expected = (cos_static + v) / (1.0 + v * cos_static);
+        assert!((cos_cam - expected).abs() < 1e-10);
+        assert!(cos_static < cos_cam);
+
+        // Doppler: nu_cam / nu_static = 1 / (gamma (1 - v cos_cam)).
+        let gamma = 1.0 / (1.0 - v * v).sqrt();
+        let shift = cam.frequency_shift(&ray, &static_cam.velocity);
+        assert!((shift - 1.0 / (gamma * (1.0 - v * cos_cam))).abs() < 1e-10);
+
+        // Looking straight ahead, the sky is blueshifted: the raindrop's
+        // Doppler factor sqrt((1+v)/(1-v)) times the static gravitational
+        // blueshift 1/sqrt(1-2M/r).
+        let centre = cam.pixel_ray(&frame, 16, 16).unwrap();
+        let sky = cam.sky_frequency_shift(&centre);
+        let expected_sky = ((1.0 + v) / (1.0 - v)).sqrt() / (1.0 - 2.0 / 8.0_f64).sqrt();
+        assert!((sky - expected_sky).abs() < 1e-10, "sky shift {}", sky);
+    }
+
+    #[test]
+    fn test_image_position_round_trip() {
+        let cam = Camera::new([0.0; 4], [1.0, 0.0, 0.0, 0.0], 1.0, 64, 48)
+            .with_orientation(DQuat::from_rotation_y(0.3));
+        let (x, y) = (10.25, 40.5);
+        let back = cam.image_position(cam.direction(x, y).unwrap()).unwrap();
+        assert!((back.0 - x).abs() < 1e-9 && (back.1 - y).abs() < 1e-9);
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn test_bl_and_ks_cameras_agree() {
+        // Same observer in both charts: momenta must differ only by the
+        // coordinate transformation.
+        let bl = Kerr::new(1.0, 0.7);
+        let ks = Kerr::kerr_schild(1.0, 0.7);
+        let pos = [0.0, 8.0, 1.0, 0.0];
+        let cam_bl = Camera::static_observer(&bl, pos, 0.9, 5, 5).unwrap();
+        let cam_ks = Camera::static_observer(&ks, pos, 0.9, 5, 5).unwrap();
+        for (a, b) in cam_bl.rays(&bl).iter().zip(cam_ks.rays(&ks)) {
+            let (a, b) = (a.unwrap(), b.unwrap());
+            let mapped = bl.covector_bl_to_ks(pos[1], a.p);
+            for mu in 0..4 {
+                assert!((mapped[mu] - b.p[mu]).abs() < 1e-10);
+            }
+        }
+    }
+}
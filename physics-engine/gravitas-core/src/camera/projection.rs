@@ -0,0 +1,215 @@
+//! Image projections: normalized image coordinates <-> camera-space directions.
+//!
+//! Image coordinates (u, v) run over [0, 1] x [0, 1] from the top-left corner.
+//! Directions are unit vectors in camera space (+X right, +Y up, -Z forward).
+
+use glam::DVec3;
+
+use std::f64::consts::{PI, TAU};
+
+/// How the camera maps pixels to viewing directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Projection {
+    /// Rectilinear pinhole; `fov` is the vertical field of view.
+    #[default]
+    Pinhole,
+    /// Equidistant fisheye (angle from the axis proportional to image radius);
+    /// `fov` is the full angle across the image circle, up to 2 pi. The
+    /// circle fills the shorter image dimension; pixels outside it have no ray.
+    Fisheye,
+    /// Full-sky equirectangular map: longitude across the width, centred on
+    /// the forward direction and increasing to the right, latitude down the
+    /// height from +Y to -Y. Ignores `fov`.
+    Equirectangular,
+    /// Cubemap as a horizontal strip of six square faces in GPU order
+    /// (+X, -X, +Y, -Y, +Z, -Z), each with the standard cube-texture
+    /// orientation. Expects width = 6 * height. Ignores `fov`.
+    Cubemap,
+}
+
+impl Projection {
+    /// Camera-space unit direction for image coordinate (u, v), or `None`
+    /// if the point lies outside the projection's image.
+    pub fn direction(self, u: f64, v: f64, fov: f64, aspect: f64) -> Option<DVec3> {
+        match self {
+            Projection::Pinhole => {
+                let tan_half = (0.5 * fov).tan();
+                let x = (2.0 * u - 1.0) * tan_half * aspect;
+                let y = (1.0 - 2.0 * v) * tan_half;
+                Some(DVec3::new(x, y, -1.0).normalize())
+            }
+            Projection::Fisheye => {
+                let (px, py) = fisheye_disk(u, v, aspect);
+                let rho = (px * px + py * py).sqrt();
+                if rho > 1.0 {
+                    return None;
+                }
+                let angle = rho * 0.5 * fov;
+                let azimuth = py.atan2(px);
+                Some(DVec3::new(
+                    angle.sin() * azimuth.cos(),
+                    angle.sin() * azimuth.sin(),
+                    -angle.cos(),
+                ))
+            }
+            Projection::Equirectangular => {
+                let lon = (u - 0.5) * TAU;
+                let lat = (0.5 - v) * PI;
+                Some(DVec3::new(
+                    lat.cos() * lon.sin(),
+                    lat.sin(),
+                    -lat.cos() * lon.cos(),
+                ))
+            }
+            Projection::Cubemap => {
+                let strip = (u * 6.0).clamp(0.0, 6.0 - 1e-12);
+                let face = strip.floor();
+                let s = 2.0 * (strip - face) - 1.0;
+                let t = 2.0 * v - 1.0;
+                let dir = match face as u8 {
+                    0 => DVec3::new(1.0, -t, -s),
+                    1 => DVec3::new(-1.0, -t, s),
+                    2 => DVec3::new(s, 1.0, t),
+                    3 => DVec3::new(s, -1.0, -t),
+                    4 => DVec3::new(s, -t, 1.0),
+                    _ => DVec3::new(-s, -t, -1.0),
+                };
+                Some(dir.normalize())
+            }
+        }
+    }
+
+    /// Image coordinate (u, v) of a camera-space direction, or `None` if the
+    /// direction is not visible in this projection.
+    pub fn image_coords(self, dir: DVec3, fov: f64, aspect: f64) -> Option<(f64, f64)> {
+        let dir = dir.normalize();
+        match self {
+            Projection::Pinhole => {
+                if dir.z >= 0.0 {
+                    return None;
+                }
+                let tan_half = (0.5 * fov).tan();
+                let x = dir.x / (-dir.z * tan_half * aspect);
+                let y = dir.y / (-dir.z * tan_half);
+                Some((0.5 * (x + 1.0), 0.5 * (1.0 - y)))
+            }
+            Projection::Fisheye => {
+                let angle = (-dir.z).clamp(-1.0, 1.0).acos();
+                let rho = angle / (0.5 * fov);
+                if rho > 1.0 {
+                    return None;
+                }
+                let azimuth = dir.y.atan2(dir.x);
+                let (px, py) = (rho * azimuth.cos(), rho * azimuth.sin());
+                let (sx, sy) = if aspect >= 1.0 {
+                    (aspect, 1.0)
+                } else {
+                    (1.0, 1.0 / aspect)
+                };
+                Some((0.5 * (px / sx + 1.0), 0.5 * (1.0 - py / sy)))
+            }
+            Projection::Equirectangular => {
+                let lon = dir.x.atan2(-dir.z);
+                let lat = dir.y.clamp(-1.0, 1.0).asin();
+                Some((lon / TAU + 0.5, 0.5 - lat / PI))
+            }
+            Projection::Cubemap => {
+                let abs = dir.abs();
+                let (face, s, t) = if abs.x >= abs.y && abs.x >= abs.z {
+                    if dir.x > 0.0 {
+                        (0.0, -dir.z / abs.x, -dir.y / abs.x)
+                    } else {
+                        (1.0, dir.z / abs.x, -dir.y / abs.x)
+                    }
+                } else if abs.y >= abs.z {
+                    if dir.y > 0.0 {
+                        (2.0, dir.x / abs.y, dir.z / abs.y)
+                    } else {
+                        (3.0, dir.x / abs.y, -dir.z / abs.y)
+                    }
+                } else if dir.z > 0.0 {
+                    (4.0, dir.x / abs.z, -dir.y / abs.z)
+                } else {
+                    (5.0, -dir.x / abs.z, -dir.y / abs.z)
+                };
+                Some(((face + 0.5 * (s + 1.0)) / 6.0, 0.5 * (t + 1.0)))
+            }
+        }
+    }
+}
+
+/// Fisheye disk coordinates with the image circle of radius 1 inscribed in
+/// the shorter image dimension.
+fn fisheye_disk(u: f64, v: f64, aspect: f64) -> (f64, f64) {
+    let x = 2.0 * u - 1.0;
+    let y = 1.0 - 2.0 * v;
+    if aspect >= 1.0 {
+        (x * aspect, y)
+    } else {
+        (x, y / aspect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Projection; 4] = [
+        Projection::Pinhole,
+        Projection::Fisheye,
+        Projection::Equirectangular,
+        Projection::Cubemap,
+    ];
+
+    #[test]
+    fn test_round_trip_all_projections() {
+        for projection in ALL {
+            let aspect = if projection == Projection::Cubemap {
+                6.0
+            } else {
+                1.5
+            };
+            for &(u, v) in &[(0.47, 0.5), (0.3, 0.6), (0.71, 0.2), (0.05, 0.52)] {
+                let Some(dir) = projection.direction(u, v, 3.0, aspect) else {
+                    continue;
+                };
+                assert!((dir.length() - 1.0).abs() < 1e-12);
+                let (u2, v2) = projection.image_coords(dir, 3.0, aspect).unwrap();
+                assert!(
+                    (u2 - u).abs() < 1e-9 && (v2 - v).abs() < 1e-9,
+                    "{:?}: ({}, {}) -> ({}, {})",
+                    projection,
+                    u,
+                    v,
+                    u2,
+                    v2
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_image_centre_looks_forward() {
+        for projection in [
+            Projection::Pinhole,
+            Projection::Fisheye,
+            Projection::Equirectangular,
+        ] {
+            let dir = projection.direction(0.5, 0.5, 1.0, 1.0).unwrap();
+            assert!((dir - DVec3::NEG_Z).length() < 1e-12);
+        }
+        // Centre of the -Z cube face
+        let dir = Projection::Cubemap
+            .direction(5.5 / 6.0, 0.5, 0.0, 6.0)
+            .unwrap();
+        assert!((dir - DVec3::NEG_Z).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_fisheye_outside_circle_has_no_ray() {
+        assert!(Projection::Fisheye.direction(0.0, 0.0, PI, 1.0).is_none());
+        // 360 degree fisheye: the rim looks straight backwards
+        let rim = Projection::Fisheye.direction(1.0, 0.5, TAU, 1.0).unwrap();
+        assert!((rim - DVec3::Z).length() < 1e-9);
+    }
+}
@@ -36,6 +36,27 @@ pub const SI_H: f64 = 6.626_070_15e-34;
 /// Reduced Planck constant (hbar) in J*s.
 pub const SI_HBAR: f64 = SI_H / (2.0 * std::f64::consts::PI);
 
+/// Electron mass in kg.
+pub const SI_ELECTRON_MASS: f64 = 9.109_383_701_5e-31;
+
+/// Elementary charge in C.
+pub const SI_ELEMENTARY_CHARGE: f64 = 1.602_176_634e-19;
+
+/// Kiloelectronvolt in J.
+pub const SI_KEV: f64 = 1e3 * SI_ELEMENTARY_CHARGE;
+
+/// Vacuum permittivity in F/m.
+pub const SI_EPSILON_0: f64 = 8.854_187_812_8e-12;
+
+/// Proton mass in kg.
+pub const SI_PROTON_MASS: f64 = 1.672_621_923_69e-27;
+
+/// Vacuum permeability in N/A^2.
+pub const SI_MU_0: f64 = 1.256_637_062_12e-6;
+
+/// Thomson cross-section in m^2.
+pub const SI_THOMSON_CROSS_SECTION: f64 = 6.652_458_732_1e-29;
+
 /// Conversion factor: SI mass (kg) -> geometric mass (m).
 /// m_geom = m_si * G / c^2
 pub const SI_TO_GEOM_MASS: f64 = SI_G / (SI_C * SI_C);
@@ -44,5 +65,14 @@ pub const SI_TO_GEOM_MASS: f64 = SI_G / (SI_C * SI_C);
 /// Rs = 2 * M_solar_geom = 2 * M_solar * G / c^2
 pub const RS_SOLAR_METERS: f64 = 2.0 * (SI_SOLAR_MASS * SI_TO_GEOM_MASS);
 
+/// Parsec in meters.
+pub const SI_PARSEC: f64 = 3.085_677_581_491_367e16;
+
+/// Jansky in W m^-2 Hz^-1.
+pub const SI_JANSKY: f64 = 1e-26;
+
+/// Microarcseconds per radian.
+pub const MICROARCSEC_PER_RAD: f64 = 180.0 / std::f64::consts::PI * 3600.0 * 1e6;
+
 /// Planck length in meters.
 pub const PLANCK_LENGTH: f64 = 1.616_255e-35;
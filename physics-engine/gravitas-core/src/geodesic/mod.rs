@@ -5,6 +5,7 @@
 
 mod hamiltonian;
 mod integrator;
+mod observer;
 mod termination;
 
 pub use hamiltonian::get_state_derivative;
@@ -12,6 +13,7 @@ pub use integrator::{
     adaptive_rkf45_step, step_rk4, step_symplectic, AdaptiveStepper, IntegrationMethod,
     IntegrationOptions,
 };
+pub use observer::GeodesicObserver;
 pub use termination::TerminationReason;
 
 use crate::metric::Metric;
@@ -31,6 +33,7 @@ pub struct GeodesicState {
 
 impl GeodesicState {
     /// Create a new geodesic state.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         t: f64,
         r: f64,
@@ -104,6 +107,7 @@ impl GeodesicState {
         n
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn add_scaled_4(
         &self,
         k1: Self,
@@ -123,6 +127,7 @@ impl GeodesicState {
         n
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn add_scaled_5(
         &self,
         k1: Self,
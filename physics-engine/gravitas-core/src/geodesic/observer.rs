@@ -0,0 +1,195 @@
+//! Observers moving along timelike geodesics.
+//!
+//! A [`GeodesicObserver`] carries a unit-mass particle state: x^mu and the
+//! covariant 4-velocity u_mu, so the affine parameter of Hamilton's equations
+//! is the proper time tau. The same equations of motion as for photons are
+//! used; only the constraint changes from H = 0 to H = -1/2.
+
+use crate::geodesic::{adaptive_rkf45_step, GeodesicState, TerminationReason};
+use crate::invariants::renormalize_timelike;
+use crate::metric::{Kerr, Metric, Orbit};
+use crate::tetrad::keplerian_velocity;
+
+/// A freely falling observer advanced in proper time.
+#[derive(Debug, Clone, Copy)]
+pub struct GeodesicObserver {
+    /// Position x^mu and covariant 4-velocity u_mu.
+    pub state: GeodesicState,
+    /// Proper time elapsed since the observer was created.
+    pub proper_time: f64,
+    /// Why the observer stopped, or `TerminationReason::None` while moving.
+    pub termination: TerminationReason,
+    /// The observer stops once r drops below this radius.
+    pub min_radius: f64,
+    /// Local error tolerance of the adaptive stepper.
+    pub tolerance: f64,
+    /// Current adaptive step size in proper time.
+    step: f64,
+}
+
+impl GeodesicObserver {
+    /// Observer at `position` with contravariant 4-velocity `velocity`.
+    ///
+    /// The 4-velocity is renormalized to u.u = -1. By default the observer
+    /// stops just outside the event horizon; lower `min_radius` with
+    /// [`GeodesicObserver::with_min_radius`] to follow it inside in
+    /// horizon-penetrating coordinates.
+    pub fn new<M: Metric>(metric: &M, position: [f64; 4], velocity: [f64; 4]) -> Self {
+        let g = metric.covariant(position[1], position[2]);
+        let mut state = GeodesicState {
+            x: position,
+            p: g.lower_index(&velocity),
+        };
+        renormalize_timelike(&mut state, metric);
+        Self {
+            state,
+            proper_time: 0.0,
+            termination: TerminationReason::None,
+            min_radius: metric.event_horizon() * 1.001,
+            tolerance: 1e-9,
+            step: 0.01,
+        }
+    }
+
+    /// Observer released from rest at (r, theta, phi) with zero angular
+    /// momentum: u^r = u^theta = 0 and u_phi = 0 at the initial event.
+    ///
+    /// Returns `None` where no such observer exists (inside the horizon).
+    pub fn released_from_rest<M: Metric>(metric: &M, r: f64, theta: f64, phi: f64) -> Option<Self> {
+        let g_inv = metric.contravariant(r, theta);
+        // u^r = g^{rt} u_t + g^{rr} u_r = 0
+        let shift = g_inv.get(1, 0) / g_inv.get(1, 1);
+        let effective = g_inv.get(0, 0) - g_inv.get(0, 1) * shift;
+        if effective >= 0.0 || !effective.is_finite() {
+            return None;
+        }
+        let u_t = -1.0 / (-effective).sqrt();
+        let u_lower = [u_t, -shift * u_t, 0.0, 0.0];
+        let velocity = g_inv.raise_index(&u_lower);
+        Some(Self::new(metric, [0.0, r, theta, phi], velocity))
+    }
+
+    /// Observer on the equatorial circular geodesic at radius r.
+    ///
+    /// Returns `None` where no timelike circular orbit exists. Orbits inside
+    /// the ISCO exist but are unstable and will eventually plunge.
+    pub fn circular(bh: &Kerr, r: f64, orbit: Orbit, phi: f64) -> Option<Self> {
+        keplerian_velocity(bh, r, orbit)
+            .map(|u| Self::new(bh, [0.0, r, std::f64::consts::FRAC_PI_2, phi], u))
+    }
+
+    /// Replace the radius at which the observer stops.
+    pub fn with_min_radius(mut self, min_radius: f64) -> Self {
+        self.min_radius = min_radius;
+        self
+    }
+
+    /// Current position x^mu.
+    #[inline]
+    pub fn position(&self) -> [f64; 4] {
+        self.state.x
+    }
+
+    /// Current contravariant 4-velocity u^mu.
+    pub fn velocity<M: Metric>(&self, metric: &M) -> [f64; 4] {
+        metric
+            .contravariant(self.state.x[1], self.state.x[2])
+            .raise_index(&self.state.p)
+    }
+
+    /// Advance the observer by `dtau` of proper time.
+    ///
+    /// Returns the termination state; once terminated the observer no
+    /// longer moves.
+    pub fn advance<M: Metric>(&mut self, metric: &M, dtau: f64) -> TerminationReason {
+        const SAFETY: f64 = 0.9;
+        const MIN_STEP: f64 = 1e-8;
+        const MAX_STEP: f64 = 1.0;
+
+        let mut remaining = dtau;
+        while remaining > 0.0 && self.termination == TerminationReason::None {
+            let h = self.step.min(remaining).max(MIN_STEP.min(remaining));
+            let (next, error) = adaptive_rkf45_step(&self.state, metric, h);
+            let ratio = error / self.tolerance;
+
+            if ratio <= 1.0 || h <= MIN_STEP {
+                self.state = next;
+                renormalize_timelike(&mut self.state, metric);
+                self.proper_time += h;
+                remaining -= h;
+
+                let growth = if ratio < 1e-4 {
+                    5.0
+                } else {
+                    (SAFETY * ratio.powf(-0.2)).min(5.0)
+                };
+                // Do not let a short final sub-step shrink the next step.
+                if h >= self.step {
+                    self.step = (h * growth).min(MAX_STEP);
+                }
+
+                let finite = self
+                    .state
+                    .x
+                    .iter()
+                    .chain(&self.state.p)
+                    .all(|v| v.is_finite());
+                if !finite || self.state.x[1] < self.min_radius {
+                    self.termination = TerminationReason::Horizon;
+                }
+            } else {
+                self.step = (h * (SAFETY * ratio.powf(-0.25)).max(0.1)).max(MIN_STEP);
+            }
+        }
+        self.termination
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schwarzschild_fall_time_from_rest() {
+        // Proper time to fall from rest at r0 to r = 2M:
+        // tau = (r0^{3/2} / sqrt(2M)) (pi/2 - asin(sqrt(2M/r0)) + sqrt(2M/r0 (1 - 2M/r0)))
+        let r0: f64 = 20.0;
+        let x = (2.0 / r0).sqrt();
+        let expected = r0.powf(1.5) / 2.0_f64.sqrt()
+            * (std::f64::consts::FRAC_PI_2 - x.asin() + (x * x * (1.0 - x * x)).sqrt());
+
+        let bh = Kerr::kerr_schild(1.0, 0.0);
+        let mut obs = GeodesicObserver::released_from_rest(&bh, r0, 1.0, 0.0)
+            .unwrap()
+            .with_min_radius(2.0);
+        let mut ticks = 0;
+        while obs.advance(&bh, 0.5) == TerminationReason::None && ticks < 10_000 {
+            ticks += 1;
+        }
+        assert_eq!(obs.termination, TerminationReason::Horizon);
+        // The last sub-step overshoots r = 2M by at most one step.
+        assert!(
+            (obs.proper_time - expected).abs() < 0.05,
+            "tau = {}, expected {}",
+            obs.proper_time,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_circular_orbit_keeps_radius_and_period() {
+        let bh = Kerr::new(1.0, 0.7);
+        let r = 8.0;
+        let mut obs = GeodesicObserver::circular(&bh, r, Orbit::Prograde, 0.0).unwrap();
+        let u = obs.velocity(&bh);
+        let omega = u[3] / u[0];
+        let period_tau = 2.0 * std::f64::consts::PI / omega / u[0];
+
+        obs.advance(&bh, period_tau);
+        assert_eq!(obs.termination, TerminationReason::None);
+        assert!((obs.state.x[1] - r).abs() < 1e-6);
+        let phase = obs.state.x[3].rem_euclid(2.0 * std::f64::consts::PI);
+        assert!(phase.min(2.0 * std::f64::consts::PI - phase) < 1e-5);
+        assert!((obs.proper_time - period_tau).abs() < 1e-12);
+    }
+}
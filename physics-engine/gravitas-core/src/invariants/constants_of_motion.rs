@@ -1,7 +1,27 @@
 //! Constants of motion in Kerr spacetime.
+//!
+//! Besides E, L_z and Q, a photon carrying a polarization vector f^mu
+//! (orthogonal to its momentum k^mu and parallel-transported along the ray)
+//! conserves the complex Walker-Penrose constant
+//!
+//!   kappa = (r - i a cos(theta)) (A - i B),
+//!   A = (k^t f^r - k^r f^t) + a sin^2(theta) (k^r f^phi - k^phi f^r),
+//!   B = [(r^2 + a^2) (k^phi f^theta - k^theta f^phi)
+//!        - a (k^t f^theta - k^theta f^t)] sin(theta),
+//!
+//! in Boyer-Lindquist components. kappa is unchanged by gauge shifts
+//! f -> f + c k, and |kappa|^2 = K f.f with K = Q + (L_z - a E)^2.
+//!
+//! # References
+//!
+//! - Walker, M. & Penrose, R. (1970). "On quadratic first integrals of the
+//!   geodesic equations for type {22} spacetimes"
+//! - Connors, P. A., Piran, T. & Stark, R. F. (1980). "Polarization
+//!   features of X-ray radiation emitted near black holes"
 
 use crate::geodesic::GeodesicState;
-use crate::metric::Metric;
+use crate::metric::kerr::CoordinateSystem;
+use crate::metric::{Kerr, Metric};
 use num_complex::Complex64;
 
 /// The four constants of motion for a null geodesic in Kerr spacetime.
@@ -15,8 +35,6 @@ pub struct ConstantsOfMotion {
     pub carter_constant: f64,
     /// Hamiltonian H = (1/2) g^{mu nu} p_mu p_nu (should be 0 for null rays).
     pub hamiltonian: f64,
-    /// Walker-Penrose constant (complex, conserved for null geodesics).
-    pub walker_penrose: Complex64,
 }
 
 /// Compute all constants of motion for a geodesic state.
@@ -25,7 +43,6 @@ pub fn compute_constants<M: Metric>(state: &GeodesicState, metric: &M) -> Consta
     let p_th = state.p[2];
     let p_ph = state.p[3];
 
-    let r = state.x[1];
     let theta = state.x[2];
 
     let m = metric.mass();
@@ -47,15 +64,32 @@ pub fn compute_constants<M: Metric>(state: &GeodesicState, metric: &M) -> Consta
     // Hamiltonian
     let h = crate::invariants::hamiltonian(state, metric);
 
-    // Walker-Penrose constant
-    let rho_inv = Complex64::new(r, a * cos_theta);
-    let walker_penrose = rho_inv * carter.max(0.0).sqrt();
-
     ConstantsOfMotion {
         energy,
         angular_momentum,
         carter_constant: carter,
         hamiltonian: h,
-        walker_penrose,
     }
 }
+
+/// Walker-Penrose constant of the ray `state` carrying the polarization
+/// vector `f` (contravariant, in the coordinates of `bh`).
+///
+/// Linear in both the momentum and `f`, so rescaling or reversing the ray
+/// only rescales kappa.
+pub fn walker_penrose(bh: &Kerr, state: &GeodesicState, f: &[f64; 4]) -> Complex64 {
+    let (r, theta) = (state.x[1], state.x[2]);
+    let mut k = bh.contravariant(r, theta).raise_index(&state.p);
+    let mut f = *f;
+    if bh.coordinate_system() == CoordinateSystem::KerrSchild {
+        k = bh.vector_ks_to_bl(r, k);
+        f = bh.vector_ks_to_bl(r, f);
+    }
+    let a = bh.a();
+    let (sin, cos) = theta.sin_cos();
+    let wedge = |i: usize, j: usize| k[i] * f[j] - k[j] * f[i];
+
+    let real = wedge(0, 1) + a * sin * sin * wedge(1, 3);
+    let imag = ((r * r + a * a) * wedge(3, 2) - a * wedge(0, 2)) * sin;
+    Complex64::new(r, -a * cos) * Complex64::new(real, -imag)
+}
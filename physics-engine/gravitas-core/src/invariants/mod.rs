@@ -6,6 +6,9 @@
 //! 2. **Angular momentum** L_z = p_phi (axial symmetry)
 //! 3. **Carter constant** Q (hidden symmetry, Killing-Yano tensor)
 //! 4. **Hamiltonian** H = 0 (null geodesic constraint)
+//!
+//! Polarized photons also conserve the complex Walker-Penrose constant,
+//! see [`walker_penrose`].
 
 mod audit;
 mod constants_of_motion;
@@ -13,8 +16,8 @@ mod renormalization;
 
 pub use audit::NumericalAudit;
 pub use constants_of_motion::compute_constants;
-pub use constants_of_motion::ConstantsOfMotion;
-pub use renormalization::renormalize_null;
+pub use constants_of_motion::{walker_penrose, ConstantsOfMotion};
+pub use renormalization::{renormalize_null, renormalize_timelike};
 
 use crate::geodesic::GeodesicState;
 use crate::metric::Metric;
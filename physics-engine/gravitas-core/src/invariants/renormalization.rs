@@ -1,7 +1,8 @@
-//! Momentum renormalization for null geodesics.
+//! Momentum renormalization for null and timelike geodesics.
 //!
-//! Projects the radial momentum p_r onto the null constraint surface H = 0
-//! to correct numerical drift accumulated during integration.
+//! Projects the radial momentum p_r onto the constraint surface H = 0
+//! (photons) or H = -1/2 (unit-mass particles) to correct numerical drift
+//! accumulated during integration.
 
 use crate::geodesic::GeodesicState;
 use crate::metric::Metric;
@@ -11,6 +12,19 @@ use crate::metric::Metric;
 /// Solves for p_r from the quadratic A*p_r^2 + B*p_r + C = 0,
 /// choosing the root closest to the current p_r to maintain ray direction.
 pub fn renormalize_null<M: Metric>(state: &mut GeodesicState, metric: &M) {
+    renormalize_mass_shell(state, metric, 0.0);
+}
+
+/// Renormalize momentum to strictly satisfy g^{mu nu} p_mu p_nu = -1
+/// (timelike geodesic of unit mass, affine parameter = proper time).
+///
+/// Uses the same root selection as [`renormalize_null`].
+pub fn renormalize_timelike<M: Metric>(state: &mut GeodesicState, metric: &M) {
+    renormalize_mass_shell(state, metric, 1.0);
+}
+
+/// Solve g^{mu nu} p_mu p_nu = -mass_sq for p_r.
+fn renormalize_mass_shell<M: Metric>(state: &mut GeodesicState, metric: &M, mass_sq: f64) {
     let r = state.x[1];
     let theta = state.x[2];
     let g_inv = metric.contravariant(r, theta);
@@ -24,8 +38,11 @@ pub fn renormalize_null<M: Metric>(state: &mut GeodesicState, metric: &M) {
     // Quadratic in p_r: A*pr^2 + B*pr + C = 0
     let a_quad = g[5]; // g^rr
     let b_quad = 2.0 * (g[1] * p_t + g[7] * p_ph); // 2(g^tr*pt + g^rph*pph)
-    let c_quad =
-        g[0] * p_t * p_t + g[10] * p_th * p_th + g[15] * p_ph * p_ph + 2.0 * g[3] * p_t * p_ph;
+    let c_quad = g[0] * p_t * p_t
+        + g[10] * p_th * p_th
+        + g[15] * p_ph * p_ph
+        + 2.0 * g[3] * p_t * p_ph
+        + mass_sq;
 
     if a_quad.abs() > 1e-12 {
         let discriminant = b_quad * b_quad - 4.0 * a_quad * c_quad;
@@ -31,17 +31,21 @@
 //! - [`physics`] -- Physical observables: photon tracing, accretion disk, redshift, spectrum
 //! - [`spacetime`] -- Visualization helpers: embedding diagrams, light cones, curvature
 //! - [`tensor`] -- Tensor algebra: MetricTensor4, Christoffel symbols
+//! - [`render`] -- CPU reference ray tracer: shadow, thin disk and lensed sky images
 //! - [`quantum`] -- Semi-classical effects: Hawking temperature, Planck-scale fluctuations
 //! - [`constants`] -- Physical constants in SI and geometric units
 
+pub mod camera;
 pub mod constants;
 pub mod geodesic;
 pub mod invariants;
 pub mod metric;
 pub mod physics;
 pub mod quantum;
+pub mod render;
 pub mod spacetime;
 pub mod tensor;
+pub mod tetrad;
 
 /// Convenience re-exports for common usage.
 pub mod prelude {
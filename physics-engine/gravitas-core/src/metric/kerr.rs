@@ -201,6 +201,60 @@ impl Kerr {
         let a = self.a();
         r * r - 2.0 * self.mass_val * r + a * a
     }
+
+    /// Transform a contravariant vector V^mu from Boyer-Lindquist to Kerr-Schild
+    /// components at radius r.
+    ///
+    /// The ingoing coordinates satisfy dt_KS = dt + (2Mr/Delta) dr and
+    /// dphi_KS = dphi + (a/Delta) dr, so only V^t and V^phi change.
+    pub fn vector_bl_to_ks(&self, r: f64, v: [f64; 4]) -> [f64; 4] {
+        let delta = self.delta(r);
+        let dt_dr = 2.0 * self.mass_val * r / delta;
+        let dphi_dr = self.a() / delta;
+        [v[0] + dt_dr * v[1], v[1], v[2], v[3] + dphi_dr * v[1]]
+    }
+
+    /// Transform a contravariant vector V^mu from Kerr-Schild to
+    /// Boyer-Lindquist components at radius r; the inverse of
+    /// [`vector_bl_to_ks`](Self::vector_bl_to_ks).
+    pub fn vector_ks_to_bl(&self, r: f64, v: [f64; 4]) -> [f64; 4] {
+        let delta = self.delta(r);
+        let dt_dr = 2.0 * self.mass_val * r / delta;
+        let dphi_dr = self.a() / delta;
+        [v[0] - dt_dr * v[1], v[1], v[2], v[3] - dphi_dr * v[1]]
+    }
+
+    /// Coordinate offsets (t_KS - t_BL, phi_KS - phi_BL) at radius r outside
+    /// the horizon, the integrals of 2Mr/Delta and a/Delta that relate the
+    /// two charts (up to constants).
+    ///
+    /// When the horizons merge (|a| = M, Delta = (r - M)^2) the integrals
+    /// are 2M (ln|r - M| - M / (r - M)) and -a / (r - M) instead.
+    pub fn kerr_schild_offsets(&self, r: f64) -> (f64, f64) {
+        let m = self.mass_val;
+        let r_plus = self.event_horizon();
+        let r_minus = self.cauchy_horizon();
+        let split = r_plus - r_minus;
+        if split <= 1e-6 * m {
+            let x = r - m;
+            return (2.0 * m * (x.abs().ln() - m / x), -self.a() / x);
+        }
+        let dt = 2.0 * m / split
+            * (r_plus * (r - r_plus).abs().ln() - r_minus * (r - r_minus).abs().ln());
+        let dphi = self.a() / split * ((r - r_plus) / (r - r_minus)).abs().ln();
+        (dt, dphi)
+    }
+
+    /// Transform a covariant vector p_mu from Boyer-Lindquist to Kerr-Schild
+    /// components at radius r.
+    ///
+    /// Only the radial component changes:
+    /// p_r^KS = p_r^BL - (2Mr p_t + a p_phi) / Delta.
+    pub fn covector_bl_to_ks(&self, r: f64, p: [f64; 4]) -> [f64; 4] {
+        let delta = self.delta(r);
+        let shift = (2.0 * self.mass_val * r * p[0] + self.a() * p[3]) / delta;
+        [p[0], p[1] - shift, p[2], p[3]]
+    }
 }
 
 impl Metric for Kerr {
@@ -385,12 +439,14 @@ impl Kerr {
         let sin2 = 1.0 - cos2;
         let sigma = r2 + a2 * cos2;
 
+        // g = eta + 2H l l, with eta the flat metric in oblate spheroidal
+        // coordinates and l the ingoing principal null direction.
         let h = (m * r) / sigma;
-        let l_r = sigma / (r2 + a2);
-        let l = [1.0, l_r, 0.0, -a * sin2];
+        let l = [1.0, 1.0, 0.0, -a * sin2];
 
         let eta_tt = -1.0;
-        let eta_rr = sigma / (r2 + a2);
+        let eta_rr = 1.0;
+        let eta_rph = -a * sin2;
         let eta_thth = sigma;
         let eta_phph = (r2 + a2) * sin2;
 
@@ -400,10 +456,10 @@ impl Kerr {
         g[3] = 2.0 * h * l[0] * l[3];
         g[4] = 2.0 * h * l[1] * l[0];
         g[5] = eta_rr + 2.0 * h * l[1] * l[1];
-        g[7] = 2.0 * h * l[1] * l[3];
+        g[7] = eta_rph + 2.0 * h * l[1] * l[3];
         g[10] = eta_thth;
         g[12] = 2.0 * h * l[3] * l[0];
-        g[13] = 2.0 * h * l[3] * l[1];
+        g[13] = eta_rph + 2.0 * h * l[3] * l[1];
         g[15] = eta_phph + 2.0 * h * l[3] * l[3];
 
         MetricTensor4::from_array(g)
@@ -595,4 +651,42 @@ mod tests {
             h_ks
         );
     }
+
+    #[test]
+    fn test_kerr_schild_covariant_inverts_contravariant() {
+        let ks = Kerr::kerr_schild(1.0, 0.7);
+        let g = ks.covariant(3.0, 1.0);
+        let g_inv = ks.contravariant(3.0, 1.0);
+        for i in 0..4 {
+            for j in 0..4 {
+                let prod: f64 = (0..4).map(|k| g.get(i, k) * g_inv.get(k, j)).sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!(
+                    (prod - expected).abs() < 1e-12,
+                    "g * g^-1 should be the identity, entry ({}, {}) = {}",
+                    i,
+                    j,
+                    prod
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_kerr_schild_offsets_integrate_to_delta() {
+        // d(dt)/dr = 2Mr/Delta and d(dphi)/dr = a/Delta, including the
+        // extremal hole whose horizons coincide.
+        for spin in [0.0, 0.7, 1.0, -1.0] {
+            let bh = Kerr::new(1.0, spin);
+            for r in [2.5, 6.0, 30.0] {
+                let h = 1e-5;
+                let (t1, p1) = bh.kerr_schild_offsets(r + h);
+                let (t0, p0) = bh.kerr_schild_offsets(r - h);
+                let delta = bh.delta(r);
+                assert!(t1.is_finite() && p1.is_finite(), "a = {spin}");
+                assert!(((t1 - t0) / (2.0 * h) - 2.0 * r / delta).abs() < 1e-6);
+                assert!(((p1 - p0) / (2.0 * h) - bh.a() / delta).abs() < 1e-6);
+            }
+        }
+    }
 }
@@ -83,3 +83,21 @@ pub trait Metric {
         }
     }
 }
+
+/// p_mu u^mu: a covariant vector contracted with a contravariant one.
+pub(crate) fn contract(p: &[f64; 4], u: &[f64; 4]) -> f64 {
+    p[0] * u[0] + p[1] * u[1] + p[2] * u[2] + p[3] * u[3]
+}
+
+/// g_mu nu a^mu b^nu at event `x`.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn dot<M: Metric>(metric: &M, x: &[f64; 4], a: &[f64; 4], b: &[f64; 4]) -> f64 {
+    let g = metric.covariant(x[1], x[2]);
+    let mut sum = 0.0;
+    for i in 0..4 {
+        for j in 0..4 {
+            sum += g.get(i, j) * a[i] * b[j];
+        }
+    }
+    sum
+}
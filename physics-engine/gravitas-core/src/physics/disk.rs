@@ -10,6 +10,9 @@
 //! - Page, D. N. & Thorne, K. S. (1974). "Disk-Accretion onto a Black Hole"
 //! - Bardeen, Press & Teukolsky (1972). "ISCO and Circular Orbits"
 
+use std::f64::consts::PI;
+
+use crate::constants::{SI_C, SI_SIGMA_SB, SI_TO_GEOM_MASS};
 use crate::metric::{Kerr, Metric, Orbit};
 
 // ============================================================================
@@ -71,11 +74,13 @@ fn angular_velocity(r: f64, m: f64, a: f64) -> f64 {
 ///
 /// The flux per unit proper area of the disk is:
 ///
-///   F(r) = -(M_dot / 4 pi) * (Omega,r / (E - Omega*Lz)^2)
+///   F(r) = -(M_dot / 4 pi sqrt(-g)) * (Omega,r / (E - Omega*Lz)^2)
 ///          * integral_{r_isco}^{r} (E - Omega*Lz) * Lz,r  dr'
 ///
 /// where E, Lz, Omega are the specific energy, angular momentum, and angular
-/// velocity of circular equatorial geodesics. The subscript ,r denotes d/dr.
+/// velocity of circular equatorial geodesics, and sqrt(-g) = r is the
+/// determinant of the equatorial (t, r, phi) metric. The subscript ,r
+/// denotes d/dr. Far out, F tends to the Newtonian 3 M M_dot / (8 pi r^3).
 ///
 /// This function evaluates the integral numerically using Simpson's rule.
 ///
@@ -88,9 +93,16 @@ fn angular_velocity(r: f64, m: f64, a: f64) -> f64 {
 /// The dimensionless flux F(r) * 4pi / M_dot. Multiply by M_dot/(4 pi) to get
 /// physical flux.
 pub fn page_thorne_flux(r: f64, bh: &Kerr, m_dot: f64) -> f64 {
+    page_thorne_flux_from(r, bh.isco(Orbit::Prograde), bh, m_dot)
+}
+
+/// [`page_thorne_flux`] of a disk truncated at `inner_radius`, where the
+/// zero-torque condition then holds instead of at the ISCO. Radii inside
+/// the ISCO have no circular orbits and are raised to it.
+pub fn page_thorne_flux_from(r: f64, inner_radius: f64, bh: &Kerr, m_dot: f64) -> f64 {
     let m = bh.mass();
     let a = bh.a();
-    let r_isco = bh.isco(Orbit::Prograde);
+    let r_isco = inner_radius.max(bh.isco(Orbit::Prograde));
 
     if r <= r_isco {
         return 0.0;
@@ -110,7 +122,8 @@ pub fn page_thorne_flux(r: f64, bh: &Kerr, m_dot: f64) -> f64 {
     let dr = r * 1e-5;
     let omega_dr = (angular_velocity(r + dr, m, a) - angular_velocity(r - dr, m, a)) / (2.0 * dr);
 
-    // Numerical integration of the Page-Thorne integrand from r_isco to r
+    // Numerical integration of the Page-Thorne integrand from the inner edge
+    // to r
     // using composite Simpson's rule with 200 panels
     let n = 200usize;
     let h = (r - r_isco) / n as f64;
@@ -142,21 +155,27 @@ pub fn page_thorne_flux(r: f64, bh: &Kerr, m_dot: f64) -> f64 {
     }
     let integral = sum * h / 3.0;
 
-    // F(r) = -(M_dot / 4pi) * (dOmega/dr) / (E - Omega*Lz)^2 * integral
+    // F(r) = -(M_dot / 4pi sqrt(-g)) * (dOmega/dr) / (E - Omega*Lz)^2 * integral
     // We return F * 4pi / M_dot (dimensionless), then scale by m_dot
-    let flux = -(omega_dr / (denom * denom)) * integral;
+    let flux = -(omega_dr / (denom * denom)) * integral / r;
 
     // flux should be positive for physical emission
     flux.abs() * m_dot
 }
 
+/// Scale of [`temperature`]: the peak dimensionless Page-Thorne flux of a
+/// Schwarzschild disk is 1.72e-4, so this puts its peak at 2.05e6 K.
+const TEMPERATURE_SCALE: f64 = 1.792e7;
+
 /// Temperature profile from the full Page-Thorne flux.
 ///
 /// T(r) = [F(r) / sigma_SB]^{1/4}
 ///
 /// We use an effective sigma_SB = 1 in geometric units and scale the result
 /// to produce temperatures in the range ~10^6 - 10^8 K typical of stellar-mass
-/// black hole accretion disks.
+/// black hole accretion disks: a Schwarzschild disk at m_dot = 1 peaks at
+/// about 2 x 10^6 K. These are display temperatures; see
+/// [`effective_temperature`] for a disk of given mass and accretion rate.
 pub fn temperature(r: f64, bh: &Kerr, m_dot: f64) -> f64 {
     let flux = page_thorne_flux(r, bh, m_dot);
     if flux <= 0.0 {
@@ -164,11 +183,47 @@ pub fn temperature(r: f64, bh: &Kerr, m_dot: f64) -> f64 {
     }
 
     // Scale to physical temperature
-    // T_max ~ 10^7 K for M_dot ~ 1 (Eddington), M ~ 10 M_sun
-    let t_scale = 1e7 * m_dot.powf(0.25);
+    let t_scale = TEMPERATURE_SCALE * m_dot.powf(0.25);
     t_scale * flux.powf(0.25)
 }
 
+/// Radiative efficiency 1 - E_isco: the fraction of the accreted rest-mass
+/// energy that a Novikov-Thorne disk radiates.
+pub fn radiative_efficiency(bh: &Kerr) -> f64 {
+    1.0 - specific_energy(bh.isco(Orbit::Prograde), bh.mass(), bh.a())
+}
+
+/// Effective temperature in Kelvin at radius r of a disk around a hole of
+/// `mass_kg` accreting `m_dot` kg/s; r is in units of the hole's mass.
+///
+/// T = [F / sigma_SB]^{1/4}, with the physical flux
+/// F = M_dot c^2 f(r) / (4 pi r_g^2) for r_g = GM/c^2 and f the
+/// [`page_thorne_flux`] of a unit-mass hole at unit rate. Unlike
+/// [`temperature`], this carries the disk's true mass and accretion rate.
+pub fn effective_temperature(r: f64, bh: &Kerr, mass_kg: f64, m_dot: f64) -> f64 {
+    effective_temperature_from(r, bh.isco(Orbit::Prograde), bh, mass_kg, m_dot)
+}
+
+/// [`effective_temperature`] of a disk truncated at `inner_radius`, with
+/// the flux of [`page_thorne_flux_from`].
+pub fn effective_temperature_from(
+    r: f64,
+    inner_radius: f64,
+    bh: &Kerr,
+    mass_kg: f64,
+    m_dot: f64,
+) -> f64 {
+    let unit = Kerr::new(1.0, bh.spin());
+    let m = bh.mass();
+    let f = page_thorne_flux_from(r / m, inner_radius / m, &unit, 1.0);
+    if f <= 0.0 {
+        return 0.0;
+    }
+    let r_g = mass_kg * SI_TO_GEOM_MASS;
+    let flux = m_dot * SI_C * SI_C * f / (4.0 * PI * r_g * r_g);
+    (flux / SI_SIGMA_SB).powf(0.25)
+}
+
 /// Generate a lookup table of disk temperature from r_isco to r_outer.
 ///
 /// Returns a Vec<f32> of normalized temperatures suitable for GPU texture upload.
@@ -256,6 +311,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_flux_newtonian_limit() {
+        // F 4 pi / M_dot -> 3 / (2 r^3) far from the hole, up to the
+        // inner-edge term of order sqrt(r_isco / r).
+        let bh = Kerr::new(1.0, 0.0);
+        let r = 1e4f64;
+        let ratio = page_thorne_flux(r, &bh, 1.0) * r.powi(3) / 1.5;
+        assert!(ratio > 0.9 && ratio < 1.0, "{ratio}");
+        let slope = (page_thorne_flux(2.0 * r, &bh, 1.0) / page_thorne_flux(r, &bh, 1.0)).log2();
+        assert!((slope + 3.0).abs() < 0.05, "{slope}");
+    }
+
+    #[test]
+    fn test_flux_matches_page_thorne_closed_form() {
+        // Page & Thorne's closed form for a = 0, with x = sqrt(r / M):
+        // F 4 pi / M_dot = 3 / (2 x^5 (x^2 - 3)) * [x - x0
+        //   - (sqrt3 / 2) ln((x - sqrt3) / (x0 - sqrt3))
+        //   + (sqrt3 / 2) ln((x + sqrt3) / (x0 + sqrt3))], x0 = sqrt6.
+        let bh = Kerr::new(1.0, 0.0);
+        let (s3, x0) = (3f64.sqrt(), 6f64.sqrt());
+        for r in [7.0, 9.55, 10.0, 30.0, 100.0] {
+            let x = f64::sqrt(r);
+            let bracket = x - x0 - 0.5 * s3 * ((x - s3) / (x0 - s3)).ln()
+                + 0.5 * s3 * ((x + s3) / (x0 + s3)).ln();
+            let exact = 1.5 * bracket / (x.powi(5) * (x * x - 3.0));
+            let f = page_thorne_flux(r, &bh, 1.0);
+            assert!((f / exact - 1.0).abs() < 1e-4, "r = {r}: {f} vs {exact}");
+        }
+        // The peak, at r = 9.55 M.
+        assert!((page_thorne_flux(9.55, &bh, 1.0) - 1.7188e-4).abs() < 1e-7);
+        assert!((temperature(9.55, &bh, 1.0) / 2.05e6 - 1.0).abs() < 0.01);
+    }
+
     #[test]
     fn test_spinning_bh_has_higher_peak_flux() {
         let bh_schwarz = Kerr::new(1.0, 0.0);
@@ -306,4 +394,42 @@ mod tests {
         );
         assert!(*peak_temp > 0.0, "Peak temperature should be positive");
     }
+
+    #[test]
+    fn test_efficiency_and_effective_temperature() {
+        let schwarzschild = Kerr::new(1.0, 0.0);
+        let eta = radiative_efficiency(&schwarzschild);
+        assert!((eta - (1.0 - (8.0f64 / 9.0).sqrt())).abs() < 1e-12);
+        assert!(radiative_efficiency(&Kerr::new(1.0, 0.998)) > 0.3);
+
+        // A 10 solar-mass hole at a tenth of Eddington peaks at a few
+        // million kelvin.
+        let mass = 10.0 * crate::constants::SI_SOLAR_MASS;
+        let m_dot = 0.1 * 1.26e32 / (eta * SI_C * SI_C);
+        let peak = (0..100)
+            .map(|k| effective_temperature(6.5 + 0.2 * k as f64, &schwarzschild, mass, m_dot))
+            .fold(0.0, f64::max);
+        assert!(peak > 2e6 && peak < 8e6, "{peak}");
+        // T scales as M^(-1/2) at fixed accretion rate and r / M.
+        let heavier = effective_temperature(10.0, &schwarzschild, 4.0 * mass, m_dot);
+        let ratio = heavier / effective_temperature(10.0, &schwarzschild, mass, m_dot);
+        assert!((ratio - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_truncated_flux_has_zero_torque_at_its_edge() {
+        let bh = Kerr::new(1.0, 0.5);
+        let edge = 12.0;
+        assert_eq!(page_thorne_flux_from(edge, edge, &bh, 1.0), 0.0);
+        // Truncation removes the torque of the inner annulus, so the flux
+        // is lower everywhere and recovers the full disk far out.
+        for r in [13.0, 20.0, 50.0] {
+            assert!(page_thorne_flux_from(r, edge, &bh, 1.0) < page_thorne_flux(r, &bh, 1.0));
+        }
+        let far = page_thorne_flux_from(1e4, edge, &bh, 1.0) / page_thorne_flux(1e4, &bh, 1.0);
+        assert!(far > 0.95 && far < 1.0, "{far}");
+        // An edge inside the ISCO is the untruncated disk.
+        let full = page_thorne_flux_from(10.0, 1.0, &bh, 1.0);
+        assert_eq!(full, page_thorne_flux(10.0, &bh, 1.0));
+    }
 }
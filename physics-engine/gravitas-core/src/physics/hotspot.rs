@@ -0,0 +1,373 @@
+//! Orbiting hotspot: a compact, heated blob of plasma on an equatorial orbit.
+//!
+//! Following Broderick & Loeb, the blob is a Gaussian overdensity,
+//!
+//!   n_e = n_0 exp(-d^2 / (2 R^2)),
+//!
+//! where d is the distance from the blob centre measured in the rest frame
+//! of the centre, at the same coordinate time. The centre either follows a
+//! prograde Keplerian orbit or plunges from the ISCO with the ISCO's energy
+//! and angular momentum, and the whole blob moves with the centre.
+//!
+//! The blob is time-dependent: its density at an event is set by the
+//! event's coordinate time x^0. Along a backward ray that is the emission
+//! time of the photon, so rendering with the camera at coordinate time
+//! t_obs includes every light-travel-time delay; see
+//! [`render_sequence`](crate::render::render_sequence) for image sequences,
+//! light curves and centroid tracks.
+//!
+//! Times, positions and the orbit are defined in Boyer-Lindquist
+//! coordinates; in Kerr-Schild coordinates events are converted first.
+//!
+//! # References
+//!
+//! - Broderick, A. E. & Loeb, A. (2005). "Imaging bright-spots in the
+//!   accretion flow near the black hole horizon of Sgr A*"
+//! - Broderick, A. E. & Loeb, A. (2006). "Imaging optically-thin hotspots
+//!   near the black hole horizon of Sgr A* at radio and near-infrared
+//!   wavelengths"
+//! - GRAVITY Collaboration (2018). "Detection of orbital motions near the
+//!   last stable circular orbit of the massive black hole SgrA*"
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use super::matter::{
+    equipartition_field, normalize_velocity, toroidal_field, MagneticField, MatterField,
+    MatterMedium,
+};
+use super::synchrotron::ThermalSynchrotron;
+use crate::metric::kerr::CoordinateSystem;
+use crate::metric::{Kerr, Metric, Orbit};
+use crate::tetrad::keplerian_velocity;
+
+/// Coordinate-time step of the tabulated plunge, in M.
+const PLUNGE_STEP: f64 = 0.1;
+
+/// Blob density is cut off beyond this many Gaussian radii.
+const CUTOFF: f64 = 4.0;
+
+/// Orbit of the blob centre.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HotspotOrbit {
+    /// Prograde Keplerian circular orbit at `radius`.
+    Circular { radius: f64 },
+    /// Plunge starting at `radius`, just inside the prograde ISCO, with
+    /// the ISCO's energy and angular momentum. Before t = 0 the centre
+    /// circles at the ISCO.
+    Plunging { radius: f64 },
+}
+
+/// A Gaussian blob of hot plasma orbiting a Kerr black hole.
+#[derive(Debug, Clone)]
+pub struct Hotspot {
+    bh: Kerr,
+    /// Boyer-Lindquist twin of `bh`, in which the orbit is defined.
+    bl: Kerr,
+    orbit: HotspotOrbit,
+    /// Energy and angular momentum per unit mass of a plunge.
+    constants: (f64, f64),
+    /// Plunge radius and azimuth at t = i * PLUNGE_STEP.
+    track: Vec<(f64, f64)>,
+    /// Gaussian radius R of the blob, in M.
+    pub size: f64,
+    /// Peak electron density n_0, m^-3.
+    pub density_scale: f64,
+    /// Electron temperature, K.
+    pub temperature: f64,
+    /// Plasma beta of the blob's toroidal field.
+    pub plasma_beta: f64,
+    /// Azimuth of the centre at t = 0.
+    pub phase: f64,
+}
+
+impl Hotspot {
+    /// Blob on a circular Keplerian orbit at `radius`. The defaults,
+    /// R = 1.5 M, n_0 = 1e14 m^-3, T = 1e11 K and beta = 1, make a 230 GHz
+    /// flare of a few Jy for Sgr A*.
+    pub fn circular(bh: &Kerr, radius: f64) -> Self {
+        Self::new(bh, HotspotOrbit::Circular { radius })
+    }
+
+    /// Blob plunging from just inside the prograde ISCO, with the same
+    /// defaults as [`circular`](Self::circular).
+    pub fn plunging(bh: &Kerr) -> Self {
+        let radius = bh.isco(Orbit::Prograde) - 0.05 * bh.mass();
+        Self::new(bh, HotspotOrbit::Plunging { radius })
+    }
+
+    fn new(bh: &Kerr, orbit: HotspotOrbit) -> Self {
+        let bl = Kerr::new(bh.mass(), bh.spin());
+        let mut hotspot = Self {
+            bh: *bh,
+            bl,
+            orbit,
+            constants: (0.0, 0.0),
+            track: Vec::new(),
+            size: 1.5,
+            density_scale: 1e14,
+            temperature: 1e11,
+            plasma_beta: 1.0,
+            phase: 0.0,
+        };
+        if let HotspotOrbit::Plunging { radius } = orbit {
+            hotspot.constants = isco_constants(&bl);
+            hotspot.track = plunge_track(&bl, hotspot.constants, radius);
+        }
+        hotspot
+    }
+
+    /// Set the Gaussian radius of the blob.
+    pub fn with_size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the peak density and the temperature.
+    pub fn with_plasma(mut self, density: f64, temperature: f64) -> Self {
+        self.density_scale = density;
+        self.temperature = temperature;
+        self
+    }
+
+    /// Set the plasma beta.
+    pub fn with_plasma_beta(mut self, beta: f64) -> Self {
+        self.plasma_beta = beta;
+        self
+    }
+
+    /// Set the azimuth of the centre at t = 0.
+    pub fn with_phase(mut self, phase: f64) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    /// The orbit of the centre.
+    pub fn orbit(&self) -> HotspotOrbit {
+        self.orbit
+    }
+
+    /// Orbital period in coordinate time of a circular orbit, or of the
+    /// ISCO circle a plunge starts from.
+    pub fn period(&self) -> f64 {
+        let radius = match self.orbit {
+            HotspotOrbit::Circular { radius } => radius,
+            HotspotOrbit::Plunging { .. } => self.bl.isco(Orbit::Prograde),
+        };
+        TAU / self.bl.keplerian_frequency(radius)
+    }
+
+    /// The blob with thermal synchrotron emission. Set the transfer
+    /// length unit to GM/c^2 in meters.
+    pub fn medium(self) -> MatterMedium<Self, ThermalSynchrotron> {
+        MatterMedium::new(self, ThermalSynchrotron)
+    }
+
+    /// Boyer-Lindquist radius and azimuth of the centre at coordinate time
+    /// `t`, or `None` once a plunge has crossed the horizon.
+    pub fn center(&self, t: f64) -> Option<(f64, f64)> {
+        match self.orbit {
+            HotspotOrbit::Circular { radius } => {
+                Some((radius, self.phase + self.bl.keplerian_frequency(radius) * t))
+            }
+            HotspotOrbit::Plunging { .. } if t < 0.0 => {
+                let isco = self.bl.isco(Orbit::Prograde);
+                Some((isco, self.phase + self.bl.keplerian_frequency(isco) * t))
+            }
+            HotspotOrbit::Plunging { .. } => {
+                let index = t / PLUNGE_STEP;
+                let i = index.floor() as usize;
+                let (a, b) = (self.track.get(i)?, self.track.get(i + 1)?);
+                let f = index - i as f64;
+                Some((a.0 + f * (b.0 - a.0), self.phase + a.1 + f * (b.1 - a.1)))
+            }
+        }
+    }
+
+    /// Boyer-Lindquist 4-velocity of the centre at radius `r` on its orbit.
+    fn center_velocity(&self, t: f64, r: f64) -> Option<[f64; 4]> {
+        match self.orbit {
+            HotspotOrbit::Circular { .. } => keplerian_velocity(&self.bl, r, Orbit::Prograde),
+            HotspotOrbit::Plunging { .. } if t < 0.0 => {
+                keplerian_velocity(&self.bl, r, Orbit::Prograde)
+            }
+            HotspotOrbit::Plunging { .. } => {
+                let (e, l) = self.constants;
+                let (dt, dr, dphi) = plunge_rates(&self.bl, e, l, r);
+                Some([dt, dr, 0.0, dphi].map(|v| v / (r * r)))
+            }
+        }
+    }
+
+    /// Event `x` in Boyer-Lindquist coordinates.
+    fn to_bl(&self, x: &[f64; 4]) -> [f64; 4] {
+        match self.bh.coordinate_system() {
+            CoordinateSystem::BoyerLindquist => *x,
+            CoordinateSystem::KerrSchild => {
+                let (dt, dphi) = self.bh.kerr_schild_offsets(x[1]);
+                [x[0] - dt, x[1], x[2], x[3] - dphi]
+            }
+        }
+    }
+
+    /// Squared rest-frame distance from the centre and the centre's
+    /// velocity, for a Boyer-Lindquist event.
+    fn offset(&self, x: &[f64; 4]) -> Option<(f64, [f64; 4])> {
+        let (r, phi) = self.center(x[0])?;
+        let u = self.center_velocity(x[0], r)?;
+        let dphi = (x[3] - phi + PI).rem_euclid(TAU) - PI;
+        let d = [0.0, x[1] - r, x[2] - FRAC_PI_2, dphi];
+        let g = self.bl.covariant(r, FRAC_PI_2);
+        let (mut norm, mut along) = (0.0, 0.0);
+        for i in 0..4 {
+            for j in 0..4 {
+                norm += g.get(i, j) * d[i] * d[j];
+                along += g.get(i, j) * u[i] * d[j];
+            }
+        }
+        Some((norm + along * along, u))
+    }
+}
+
+impl MatterField for Hotspot {
+    fn density(&self, x: &[f64; 4]) -> f64 {
+        if x[1] <= self.bh.event_horizon() * 1.01 {
+            return 0.0;
+        }
+        match self.offset(&self.to_bl(x)) {
+            Some((d2, _)) if d2 < (CUTOFF * self.size).powi(2) => {
+                self.density_scale * (-0.5 * d2 / (self.size * self.size)).exp()
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn temperature(&self, _x: &[f64; 4]) -> f64 {
+        self.temperature
+    }
+
+    fn velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+        if x[1] <= self.bh.event_horizon() * 1.01 {
+            return None;
+        }
+        let bl = self.to_bl(x);
+        let (_, u) = self.offset(&bl)?;
+        let u = normalize_velocity(&self.bl, &bl, u)?;
+        Some(match self.bh.coordinate_system() {
+            CoordinateSystem::BoyerLindquist => u,
+            CoordinateSystem::KerrSchild => self.bh.vector_bl_to_ks(x[1], u),
+        })
+    }
+
+    fn magnetic_field(&self, x: &[f64; 4]) -> MagneticField {
+        let Some(u) = self.velocity(x) else {
+            return MagneticField::default();
+        };
+        let strength = equipartition_field(self.density(x), self.temperature, self.plasma_beta);
+        toroidal_field(&self.bh, x, &u, strength)
+    }
+}
+
+/// Energy and angular momentum per unit mass of the prograde ISCO orbit.
+fn isco_constants(bl: &Kerr) -> (f64, f64) {
+    let r = bl.isco(Orbit::Prograde);
+    let u = keplerian_velocity(bl, r, Orbit::Prograde).unwrap_or([1.0, 0.0, 0.0, 0.0]);
+    let g = bl.covariant(r, FRAC_PI_2);
+    let u_t = g.get(0, 0) * u[0] + g.get(0, 3) * u[3];
+    let u_phi = g.get(3, 0) * u[0] + g.get(3, 3) * u[3];
+    (-u_t, u_phi)
+}
+
+/// r^2 (dt, dr, dphi) / dtau on the equator for an ingoing geodesic with
+/// energy `e` and angular momentum `l`.
+fn plunge_rates(bl: &Kerr, e: f64, l: f64, r: f64) -> (f64, f64, f64) {
+    let a = bl.a();
+    let delta = bl.delta(r);
+    let p = e * (r * r + a * a) - a * l;
+    let radial = (p * p - delta * (r * r + (l - a * e).powi(2))).max(0.0);
+    let dt = -a * (a * e - l) + (r * r + a * a) / delta * p;
+    let dphi = -(a * e - l) + a / delta * p;
+    (dt, -radial.sqrt(), dphi)
+}
+
+/// Radius and azimuth of a plunge from `radius` at steps of
+/// [`PLUNGE_STEP`] in coordinate time, until it nears the horizon.
+fn plunge_track(bl: &Kerr, (e, l): (f64, f64), radius: f64) -> Vec<(f64, f64)> {
+    let rate = |r: f64| {
+        let (dt, dr, dphi) = plunge_rates(bl, e, l, r);
+        (dr / dt, dphi / dt)
+    };
+    let end = bl.event_horizon() * 1.02;
+    let h = PLUNGE_STEP;
+    let mut track = vec![(radius, 0.0)];
+    let (mut r, mut phi) = (radius, 0.0);
+    while r > end && track.len() < 1_000_000 {
+        let k1 = rate(r);
+        let k2 = rate(r + 0.5 * h * k1.0);
+        let k3 = rate(r + 0.5 * h * k2.0);
+        let k4 = rate(r + h * k3.0);
+        r += h / 6.0 * (k1.0 + 2.0 * k2.0 + 2.0 * k3.0 + k4.0);
+        phi += h / 6.0 * (k1.1 + 2.0 * k2.1 + 2.0 * k3.1 + k4.1);
+        track.push((r, phi));
+    }
+    track
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn norm(bh: &Kerr, x: &[f64; 4], u: &[f64; 4]) -> f64 {
+        let g = bh.covariant(x[1], x[2]);
+        (0..4)
+            .flat_map(|i| (0..4).map(move |j| (i, j)))
+            .map(|(i, j)| g.get(i, j) * u[i] * u[j])
+            .sum()
+    }
+
+    #[test]
+    fn test_circular_hotspot() {
+        for bh in [Kerr::new(1.0, 0.5), Kerr::kerr_schild(1.0, 0.5)] {
+            let spot = Hotspot::circular(&bh, 8.0).with_phase(0.3);
+            let period = spot.period();
+            let (r0, phi0) = spot.center(0.0).unwrap();
+            let (r1, phi1) = spot.center(period).unwrap();
+            assert_eq!(r0, r1);
+            assert!((phi1 - phi0 - TAU).abs() < 1e-12);
+
+            // Peak density at the centre; the blob has gone half an orbit
+            // later.
+            let mut x = [0.0, 8.0, FRAC_PI_2, 0.3];
+            if bh.coordinate_system() == CoordinateSystem::KerrSchild {
+                let (dt, dphi) = bh.kerr_schild_offsets(8.0);
+                x = [dt, 8.0, FRAC_PI_2, 0.3 + dphi];
+            }
+            assert!((spot.density(&x) / spot.density_scale - 1.0).abs() < 1e-12);
+            let later = [x[0] + 0.5 * period, x[1], x[2], x[3]];
+            assert_eq!(spot.density(&later), 0.0);
+
+            let u = spot.velocity(&[x[0], 8.5, 1.5, x[3] + 0.1]).unwrap();
+            assert!((norm(&bh, &[0.0, 8.5, 1.5, 0.0], &u) + 1.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_plunging_hotspot() {
+        let bh = Kerr::new(1.0, 0.7);
+        let spot = Hotspot::plunging(&bh);
+        let isco = bh.isco(Orbit::Prograde);
+        assert_eq!(spot.center(-10.0).unwrap().0, isco);
+
+        let mut last = (isco, f64::NEG_INFINITY);
+        let mut t = 0.0;
+        while let Some((r, phi)) = spot.center(t) {
+            assert!(r <= last.0 && phi > last.1);
+            let u = spot.center_velocity(t, r).unwrap();
+            assert!((norm(&bh, &[t, r, FRAC_PI_2, phi], &u) + 1.0).abs() < 1e-9);
+            last = (r, phi);
+            t += 1.0;
+        }
+        // Finite fall time, ending at the horizon.
+        assert!(t < 1000.0, "{t}");
+        assert!(last.0 < 1.05 * bh.event_horizon());
+    }
+}
@@ -0,0 +1,629 @@
+//! Emitting plasma: density, temperature, magnetic field and flow velocity.
+//!
+//! A [`MatterField`] describes a fluid in the coordinates of the [`Kerr`]
+//! metric it lives in, Boyer-Lindquist or Kerr-Schild. Its 4-velocity is
+//! normalized, u_mu u^mu = -1, and its magnetic field is the fluid-frame
+//! field b^mu, orthogonal to u. Fields are combined with an [`Emission`]
+//! model into a [`MatterMedium`] for the radiative transfer integrator,
+//! and give the redshift of their emission through [`g_factor`].
+//!
+//! Physical quantities are SI: electron number density in m^-3,
+//! temperature in K and field strength in Tesla. Positions are in units
+//! of M.
+//!
+//! # References
+//!
+//! - Novikov, I. D. & Thorne, K. S. (1973). "Astrophysics of Black Holes"
+//! - Broderick, A. E. & Loeb, A. (2009). "Imaging the Black Hole Silhouette
+//!   of M87: Implications for Jet Formation and Black Hole Spin"
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::transfer::{Coefficients, RadiativeMedium};
+use super::{disk, spectrum};
+use crate::constants::{SI_KB, SI_MU_0};
+use crate::geodesic::GeodesicState;
+use crate::metric::{contract, dot, Kerr, Metric, Orbit};
+use crate::tetrad::{circular_velocity, energy_ratio, zamo_velocity};
+
+/// Radial samples in the thin-disk temperature table.
+const TABLE_SIZE: usize = 256;
+
+/// Magnetic field measured in the fluid frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MagneticField {
+    /// Field strength |b| in Tesla.
+    pub strength: f64,
+    /// Unit spacelike direction b^mu / |b|, orthogonal to the fluid
+    /// 4-velocity.
+    pub direction: [f64; 4],
+}
+
+/// State of the fluid at one event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FluidState {
+    /// Electron number density in m^-3.
+    pub density: f64,
+    /// Electron temperature in K.
+    pub temperature: f64,
+    /// Normalized 4-velocity u^mu.
+    pub velocity: [f64; 4],
+    /// Fluid-frame magnetic field.
+    pub magnetic_field: MagneticField,
+}
+
+impl FluidState {
+    /// Angle between the field and a photon with covariant momentum `k`
+    /// (future-directed), measured in the fluid frame. Without a field
+    /// every direction counts as perpendicular.
+    pub fn pitch_angle(&self, k: &[f64; 4]) -> f64 {
+        if self.magnetic_field.strength <= 0.0 {
+            return FRAC_PI_2;
+        }
+        let energy = -contract(k, &self.velocity);
+        let cos = contract(k, &self.magnetic_field.direction) / energy;
+        cos.clamp(-1.0, 1.0).acos()
+    }
+}
+
+/// A fluid filling part of spacetime. `x` is an event (t, r, theta, phi).
+pub trait MatterField {
+    /// Electron number density in m^-3 (zero outside the matter).
+    fn density(&self, x: &[f64; 4]) -> f64;
+
+    /// Electron temperature in K.
+    fn temperature(&self, x: &[f64; 4]) -> f64;
+
+    /// Normalized 4-velocity u^mu, or `None` where there is no matter.
+    fn velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]>;
+
+    /// Fluid-frame magnetic field. Unmagnetized by default.
+    fn magnetic_field(&self, _x: &[f64; 4]) -> MagneticField {
+        MagneticField::default()
+    }
+
+    /// Everything at `x`, or `None` where there is no matter.
+    fn fluid(&self, x: &[f64; 4]) -> Option<FluidState> {
+        let density = self.density(x);
+        if density <= 0.0 {
+            return None;
+        }
+        Some(FluidState {
+            density,
+            temperature: self.temperature(x),
+            velocity: self.velocity(x)?,
+            magnetic_field: self.magnetic_field(x),
+        })
+    }
+}
+
+/// Frequency ratio g = nu_camera / nu_fluid for light emitted by `field`
+/// at the ray state `emit` and received by a camera with 4-velocity
+/// `u_camera`, where it is the backward ray `camera_ray`.
+///
+/// Returns `None` where there is no matter or g is not positive.
+pub fn g_factor<F: MatterField + ?Sized>(
+    field: &F,
+    emit: &GeodesicState,
+    camera_ray: &GeodesicState,
+    u_camera: &[f64; 4],
+) -> Option<f64> {
+    let u = field.velocity(&emit.x)?;
+    let g = energy_ratio(&emit.p, &u, &camera_ray.p, u_camera);
+    (g > 0.0 && g.is_finite()).then_some(g)
+}
+
+/// Scale the future-directed vector `v` at event `x` to a unit timelike
+/// 4-velocity. Returns `None` if `v` is not timelike.
+pub fn normalize_velocity<M: Metric>(metric: &M, x: &[f64; 4], v: [f64; 4]) -> Option<[f64; 4]> {
+    let norm = dot(metric, x, &v, &v);
+    if norm.is_nan() || norm >= 0.0 {
+        return None;
+    }
+    let scale = 1.0 / (-norm).sqrt();
+    Some(v.map(|c| c * scale))
+}
+
+/// Toroidal field of the given strength: d/dphi projected orthogonal to the
+/// fluid velocity `u`. Zero on the axis, where d/dphi vanishes.
+pub fn toroidal_field<M: Metric>(
+    metric: &M,
+    x: &[f64; 4],
+    u: &[f64; 4],
+    strength: f64,
+) -> MagneticField {
+    let phi = [0.0, 0.0, 0.0, 1.0];
+    let along = dot(metric, x, u, &phi);
+    let b: [f64; 4] = std::array::from_fn(|i| phi[i] + along * u[i]);
+    let norm = dot(metric, x, &b, &b);
+    if norm.is_nan() || norm <= 0.0 {
+        return MagneticField::default();
+    }
+    MagneticField {
+        strength,
+        direction: b.map(|c| c / norm.sqrt()),
+    }
+}
+
+/// Field strength in Tesla for plasma beta `beta` = p_gas / p_mag, with
+/// gas pressure n k T and magnetic pressure B^2 / (2 mu_0).
+pub fn equipartition_field(density: f64, temperature: f64, beta: f64) -> f64 {
+    (2.0 * SI_MU_0 * density * SI_KB * temperature / beta)
+        .max(0.0)
+        .sqrt()
+}
+
+/// Geometrically thin Novikov-Thorne disk with a Gaussian vertical profile,
+/// on prograde Keplerian orbits from the ISCO outward.
+#[derive(Debug, Clone)]
+pub struct ThinDiskField {
+    bh: Kerr,
+    /// Inner edge (the prograde ISCO).
+    pub inner_radius: f64,
+    /// Outer edge.
+    pub outer_radius: f64,
+    /// Scale height over cylindrical radius, H / R.
+    pub aspect_ratio: f64,
+    /// Midplane electron density at the inner edge, m^-3.
+    pub density_scale: f64,
+    /// Radial power law of the midplane density, n ~ R^-index.
+    pub density_index: f64,
+    /// Gas over magnetic pressure of the toroidal field.
+    pub plasma_beta: f64,
+    temperature: Vec<f64>,
+}
+
+impl ThinDiskField {
+    /// Novikov-Thorne disk around `bh` out to `outer_radius`, with
+    /// temperatures for accretion rate `accretion_rate` (in the units of
+    /// [`disk::temperature`]).
+    pub fn novikov_thorne(bh: &Kerr, outer_radius: f64, accretion_rate: f64) -> Self {
+        let inner_radius = bh.isco(Orbit::Prograde);
+        let temperature = (0..TABLE_SIZE)
+            .map(|i| {
+                let r = inner_radius
+                    + (outer_radius - inner_radius) * i as f64 / (TABLE_SIZE - 1) as f64;
+                disk::temperature(r, bh, accretion_rate)
+            })
+            .collect();
+        Self {
+            bh: *bh,
+            inner_radius,
+            outer_radius,
+            aspect_ratio: 0.05,
+            density_scale: 1e24,
+            density_index: 1.5,
+            plasma_beta: 10.0,
+            temperature,
+        }
+    }
+
+    /// Set the scale height H / R.
+    pub fn with_aspect_ratio(mut self, aspect_ratio: f64) -> Self {
+        self.aspect_ratio = aspect_ratio;
+        self
+    }
+
+    /// Set the inner-edge midplane density and its radial power law.
+    pub fn with_density(mut self, scale: f64, index: f64) -> Self {
+        self.density_scale = scale;
+        self.density_index = index;
+        self
+    }
+
+    /// Set the plasma beta of the toroidal field.
+    pub fn with_plasma_beta(mut self, beta: f64) -> Self {
+        self.plasma_beta = beta;
+        self
+    }
+
+    /// Cylindrical radius R and height z over the scale height, if `x` lies
+    /// within three scale heights of the disk.
+    fn locate(&self, x: &[f64; 4]) -> Option<(f64, f64)> {
+        let (cyl, z) = cylindrical(x);
+        let height = z / (self.aspect_ratio * cyl);
+        ((self.inner_radius..=self.outer_radius).contains(&cyl) && height.abs() <= 3.0)
+            .then_some((cyl, height))
+    }
+}
+
+impl MatterField for ThinDiskField {
+    fn density(&self, x: &[f64; 4]) -> f64 {
+        self.locate(x).map_or(0.0, |(cyl, height)| {
+            self.density_scale
+                * (cyl / self.inner_radius).powf(-self.density_index)
+                * (-0.5 * height * height).exp()
+        })
+    }
+
+    fn temperature(&self, x: &[f64; 4]) -> f64 {
+        let Some((cyl, _)) = self.locate(x) else {
+            return 0.0;
+        };
+        let t = (cyl - self.inner_radius) / (self.outer_radius - self.inner_radius)
+            * (TABLE_SIZE - 1) as f64;
+        let i = (t as usize).min(TABLE_SIZE - 2);
+        let f = t - i as f64;
+        self.temperature[i] * (1.0 - f) + self.temperature[i + 1] * f
+    }
+
+    fn velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+        let (cyl, _) = self.locate(x)?;
+        circular_velocity(&self.bh, x[1], x[2], self.bh.keplerian_frequency(cyl))
+    }
+
+    fn magnetic_field(&self, x: &[f64; 4]) -> MagneticField {
+        match self.velocity(x) {
+            Some(u) => {
+                let strength =
+                    equipartition_field(self.density(x), self.temperature(x), self.plasma_beta);
+                toroidal_field(&self.bh, x, &u, strength)
+            }
+            None => MagneticField::default(),
+        }
+    }
+}
+
+/// Geometrically thick torus with a Gaussian cross-section, rotating on
+/// Keplerian cylinders. A simple stand-in for equilibrium tori.
+#[derive(Debug, Clone, Copy)]
+pub struct TorusField {
+    bh: Kerr,
+    /// Radius of the density maximum in the equatorial plane.
+    pub center_radius: f64,
+    /// Gaussian width of the cross-section.
+    pub width: f64,
+    /// Electron density at the centre, m^-3.
+    pub density_scale: f64,
+    /// Electron temperature at the centre, K.
+    pub temperature_scale: f64,
+    /// Gas over magnetic pressure of the toroidal field.
+    pub plasma_beta: f64,
+}
+
+impl TorusField {
+    /// Torus around `bh` centred on `center_radius` with Gaussian `width`.
+    pub fn new(bh: &Kerr, center_radius: f64, width: f64) -> Self {
+        Self {
+            bh: *bh,
+            center_radius,
+            width,
+            density_scale: 1e13,
+            temperature_scale: 1e11,
+            plasma_beta: 10.0,
+        }
+    }
+
+    /// Set the central density and temperature.
+    pub fn with_center(mut self, density: f64, temperature: f64) -> Self {
+        self.density_scale = density;
+        self.temperature_scale = temperature;
+        self
+    }
+
+    /// Set the plasma beta of the toroidal field.
+    pub fn with_plasma_beta(mut self, beta: f64) -> Self {
+        self.plasma_beta = beta;
+        self
+    }
+
+    /// Density over its central value, cut off at four widths.
+    fn profile(&self, x: &[f64; 4]) -> f64 {
+        let (cyl, z) = cylindrical(x);
+        let d2 = ((cyl - self.center_radius).powi(2) + z * z) / (self.width * self.width);
+        if d2 > 16.0 || x[1] <= self.bh.event_horizon() {
+            return 0.0;
+        }
+        (-0.5 * d2).exp()
+    }
+}
+
+impl MatterField for TorusField {
+    fn density(&self, x: &[f64; 4]) -> f64 {
+        self.density_scale * self.profile(x)
+    }
+
+    /// Adiabatic, T ~ n^(2/3).
+    fn temperature(&self, x: &[f64; 4]) -> f64 {
+        self.temperature_scale * self.profile(x).powf(2.0 / 3.0)
+    }
+
+    fn velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+        if self.profile(x) == 0.0 {
+            return None;
+        }
+        let (cyl, _) = cylindrical(x);
+        circular_velocity(&self.bh, x[1], x[2], self.bh.keplerian_frequency(cyl))
+    }
+
+    fn magnetic_field(&self, x: &[f64; 4]) -> MagneticField {
+        match self.velocity(x) {
+            Some(u) => {
+                let strength =
+                    equipartition_field(self.density(x), self.temperature(x), self.plasma_beta);
+                toroidal_field(&self.bh, x, &u, strength)
+            }
+            None => MagneticField::default(),
+        }
+    }
+}
+
+/// Conical bipolar jet flowing radially outward with a fixed Lorentz factor
+/// relative to the zero-angular-momentum observers.
+#[derive(Debug, Clone, Copy)]
+pub struct JetField {
+    bh: Kerr,
+    /// Half-opening angle of each cone about the spin axis, rad.
+    pub opening_angle: f64,
+    /// Lorentz factor of the outflow seen by the ZAMO.
+    pub lorentz_factor: f64,
+    /// Radius where the jet starts.
+    pub base_radius: f64,
+    /// Electron density at the base, m^-3, falling off as r^-2.
+    pub density_scale: f64,
+    /// Electron temperature, K.
+    pub temperature: f64,
+    /// Gas over magnetic pressure of the toroidal field.
+    pub plasma_beta: f64,
+}
+
+impl JetField {
+    /// Jet around `bh` with half-opening angle `opening_angle` and Lorentz
+    /// factor `lorentz_factor`, starting at twice the horizon radius.
+    pub fn new(bh: &Kerr, opening_angle: f64, lorentz_factor: f64) -> Self {
+        Self {
+            bh: *bh,
+            opening_angle,
+            lorentz_factor,
+            base_radius: 2.0 * bh.event_horizon(),
+            density_scale: 1e12,
+            temperature: 1e10,
+            plasma_beta: 1.0,
+        }
+    }
+
+    /// Set the base radius and the density there.
+    pub fn with_base(mut self, radius: f64, density: f64) -> Self {
+        self.base_radius = radius;
+        self.density_scale = density;
+        self
+    }
+
+    /// Set the electron temperature.
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Set the plasma beta of the toroidal field.
+    pub fn with_plasma_beta(mut self, beta: f64) -> Self {
+        self.plasma_beta = beta;
+        self
+    }
+
+    fn contains(&self, x: &[f64; 4]) -> bool {
+        let theta = x[2];
+        x[1] >= self.base_radius && (theta < self.opening_angle || theta > PI - self.opening_angle)
+    }
+}
+
+impl MatterField for JetField {
+    fn density(&self, x: &[f64; 4]) -> f64 {
+        if !self.contains(x) {
+            return 0.0;
+        }
+        self.density_scale * (self.base_radius / x[1]).powi(2)
+    }
+
+    fn temperature(&self, x: &[f64; 4]) -> f64 {
+        if self.contains(x) {
+            self.temperature
+        } else {
+            0.0
+        }
+    }
+
+    /// u = Gamma (n + beta e_r), with n the ZAMO and e_r the unit radial
+    /// direction it measures.
+    fn velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+        if !self.contains(x) {
+            return None;
+        }
+        let n = zamo_velocity(&self.bh, x[1], x[2]);
+        let radial = [0.0, 1.0, 0.0, 0.0];
+        let along = dot(&self.bh, x, &n, &radial);
+        let e: [f64; 4] = std::array::from_fn(|i| radial[i] + along * n[i]);
+        let norm = dot(&self.bh, x, &e, &e);
+        if norm.is_nan() || norm <= 0.0 {
+            return None;
+        }
+        let gamma = self.lorentz_factor.max(1.0);
+        let beta = (1.0 - 1.0 / (gamma * gamma)).sqrt();
+        let u = std::array::from_fn(|i| gamma * (n[i] + beta * e[i] / norm.sqrt()));
+        Some(u)
+    }
+
+    fn magnetic_field(&self, x: &[f64; 4]) -> MagneticField {
+        match self.velocity(x) {
+            Some(u) => {
+                let strength =
+                    equipartition_field(self.density(x), self.temperature, self.plasma_beta);
+                toroidal_field(&self.bh, x, &u, strength)
+            }
+            None => MagneticField::default(),
+        }
+    }
+}
+
+/// Fluid-frame emission and absorption of a plasma.
+pub trait Emission {
+    /// Coefficients (SI) at fluid-frame frequency `nu` in Hz, for a photon
+    /// at `pitch_angle` to the magnetic field.
+    fn coefficients(&self, fluid: &FluidState, nu: f64, pitch_angle: f64) -> Coefficients;
+}
+
+/// Grey absorber in local thermodynamic equilibrium: alpha = sigma n, and
+/// j = alpha B_nu(T) by Kirchhoff's law.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GreyBody {
+    /// Absorption cross-section per electron, m^2.
+    pub cross_section: f64,
+}
+
+impl Emission for GreyBody {
+    fn coefficients(&self, fluid: &FluidState, nu: f64, _pitch_angle: f64) -> Coefficients {
+        let absorptivity = self.cross_section * fluid.density;
+        Coefficients {
+            emissivity: absorptivity * spectrum::planck_frequency(nu, fluid.temperature),
+            absorptivity,
+        }
+    }
+}
+
+/// A matter field radiating with an emission model, for
+/// [`RadiativeTransfer`](super::transfer::RadiativeTransfer). Set
+/// [`TransferSettings::length_unit`](super::transfer::TransferSettings) to
+/// GM/c^2 in meters.
+#[derive(Debug, Clone)]
+pub struct MatterMedium<F, E> {
+    /// Where the matter is and how it moves.
+    pub field: F,
+    /// How it emits and absorbs.
+    pub emission: E,
+}
+
+impl<F: MatterField, E: Emission> MatterMedium<F, E> {
+    /// Combine `field` with `emission`.
+    pub fn new(field: F, emission: E) -> Self {
+        Self { field, emission }
+    }
+}
+
+impl<F: MatterField, E: Emission> RadiativeMedium for MatterMedium<F, E> {
+    fn fluid_velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+        if self.field.density(x) > 0.0 {
+            self.field.velocity(x)
+        } else {
+            None
+        }
+    }
+
+    fn coefficients(&self, x: &[f64; 4], k: &[f64; 4], nu: f64) -> Coefficients {
+        match self.field.fluid(x) {
+            Some(fluid) => self.emission.coefficients(&fluid, nu, fluid.pitch_angle(k)),
+            None => Coefficients::default(),
+        }
+    }
+}
+
+/// Cylindrical radius and height of event `x`.
+fn cylindrical(x: &[f64; 4]) -> (f64, f64) {
+    let (sin, cos) = x[2].sin_cos();
+    (x[1] * sin, x[1] * cos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::redshift::kerr_g_factor;
+    use crate::tetrad::keplerian_velocity;
+
+    fn fields(bh: &Kerr) -> Vec<(Box<dyn MatterField>, [f64; 4])> {
+        vec![
+            (
+                Box::new(ThinDiskField::novikov_thorne(bh, 30.0, 1.0)),
+                [0.0, 8.0, FRAC_PI_2 + 0.02, 1.0],
+            ),
+            (
+                Box::new(TorusField::new(bh, 12.0, 3.0)),
+                [0.0, 11.0, 1.3, 2.0],
+            ),
+            (Box::new(JetField::new(bh, 0.3, 5.0)), [0.0, 10.0, 0.2, 0.5]),
+            (
+                Box::new(JetField::new(bh, 0.3, 5.0)),
+                [0.0, 20.0, PI - 0.1, 0.5],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_velocity_normalized_and_field_orthogonal() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            for (field, x) in fields(&bh) {
+                let fluid = field.fluid(&x).expect("point inside the matter");
+                let u = fluid.velocity;
+                assert!((dot(&bh, &x, &u, &u) + 1.0).abs() < 1e-10);
+                assert!(u[0] > 0.0);
+                let b = fluid.magnetic_field;
+                assert!(b.strength > 0.0);
+                assert!(dot(&bh, &x, &u, &b.direction).abs() < 1e-10);
+                assert!((dot(&bh, &x, &b.direction, &b.direction) - 1.0).abs() < 1e-10);
+            }
+            assert!(fields(&bh)[1].0.fluid(&[0.0, 40.0, 1.3, 0.0]).is_none());
+        }
+    }
+
+    #[test]
+    fn test_thin_disk_matches_keplerian_redshift() {
+        let bh = Kerr::new(1.0, 0.7);
+        let disk = ThinDiskField::novikov_thorne(&bh, 30.0, 1.0);
+        let r = 9.0;
+        let x = [0.0, r, FRAC_PI_2, 0.0];
+        let u = disk.velocity(&x).unwrap();
+        let kepler = keplerian_velocity(&bh, r, Orbit::Prograde).unwrap();
+        for i in 0..4 {
+            assert!((u[i] - kepler[i]).abs() < 1e-12);
+        }
+
+        let lambda = 4.0;
+        let emit = GeodesicState {
+            x,
+            p: [-1.0, 0.3, 0.0, lambda],
+        };
+        let camera = GeodesicState {
+            x: [0.0, 1e6, FRAC_PI_2, 0.0],
+            p: [-1.0, 0.0, 0.0, 0.0],
+        };
+        let g = g_factor(&disk, &emit, &camera, &[1.0, 0.0, 0.0, 0.0]).unwrap();
+        let expected = kerr_g_factor(r, 1.0, 0.7, lambda);
+        assert!((g - expected).abs() < 1e-10, "{g} vs {expected}");
+    }
+
+    #[test]
+    fn test_jet_lorentz_factor() {
+        let bh = Kerr::kerr_schild(1.0, 0.5);
+        let jet = JetField::new(&bh, 0.4, 10.0);
+        let x = [0.0, 6.0, 0.1, 0.0];
+        let u = jet.velocity(&x).unwrap();
+        let n = zamo_velocity(&bh, x[1], x[2]);
+        assert!((-dot(&bh, &x, &u, &n) - 10.0).abs() < 1e-9);
+        assert!(jet.velocity(&[0.0, 6.0, 1.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn test_grey_body_medium() {
+        let bh = Kerr::new(1.0, 0.0);
+        let medium = MatterMedium::new(
+            TorusField::new(&bh, 12.0, 3.0),
+            GreyBody {
+                cross_section: 1e-20,
+            },
+        );
+        let x = [0.0, 12.0, FRAC_PI_2, 0.0];
+        let fluid = medium.field.fluid(&x).unwrap();
+
+        // A photon moving along the field has zero pitch angle.
+        let g = bh.covariant(x[1], x[2]);
+        let v: [f64; 4] =
+            std::array::from_fn(|i| fluid.velocity[i] + fluid.magnetic_field.direction[i]);
+        let k: [f64; 4] = std::array::from_fn(|i| (0..4).map(|j| g.get(i, j) * v[j]).sum());
+        assert!(fluid.pitch_angle(&k) < 1e-6);
+
+        let nu = 230e9;
+        let c = medium.coefficients(&x, &k, nu);
+        assert!((c.absorptivity - 1e-20 * 1e13).abs() < 1e-12 * c.absorptivity);
+        let source = c.emissivity / c.absorptivity;
+        let planck = spectrum::planck_frequency(nu, 1e11);
+        assert!((source - planck).abs() < 1e-10 * planck);
+    }
+}
@@ -1,6 +1,13 @@
 //! Physical observables and astrophysical models.
 
 pub mod disk;
+pub mod hotspot;
+pub mod matter;
+pub mod polarization;
 pub mod redshift;
+pub mod riaf;
 pub mod shadow;
 pub mod spectrum;
+pub mod synchrotron;
+pub mod torus;
+pub mod transfer;
@@ -0,0 +1,639 @@
+//! Polarized radiative transfer along geodesics.
+//!
+//! Polarization is described by the Stokes parameters (I, Q, U, V) in a
+//! basis (f, g) of the plane orthogonal to the photon's direction, with
+//! g = n x f for propagation direction n. Linear polarization at angle chi
+//! from f, towards g, has Q = P cos(2 chi) and U = P sin(2 chi); V > 0 is
+//! circular polarization rotating from f to g.
+//!
+//! The basis vector f is parallel-transported along the ray. In Kerr this
+//! needs no integration: the Walker-Penrose constant kappa of f is
+//! conserved ([`walker_penrose`]), and since kappa is linear in f it fixes
+//! the transported f at every point of the ray by a 2 x 2 solve. Where
+//! there is matter, Stokes vectors are rotated into the fluid-frame basis
+//! with Q along the projected magnetic field and integrated through
+//!
+//!   d S / dl = j - K S,
+//!
+//!   K = | alpha_I  alpha_Q  alpha_U  alpha_V |
+//!       | alpha_Q  alpha_I  rho_V   -rho_U   |
+//!       | alpha_U -rho_V    alpha_I  rho_Q   |
+//!       | alpha_V  rho_U   -rho_Q    alpha_I |
+//!
+//! with emissivities j, absorptivities alpha and Faraday rotativities rho.
+//! Faraday rotation rho_V turns the polarization angle at rate rho_V / 2;
+//! conversion rho_Q exchanges U and V. As for intensity, S / nu^3 is the
+//! invariant that the equation carries from emitter to camera.
+//!
+//! [`parallel_transport`] integrates the transport equation directly, for
+//! other metrics or as a check.
+//!
+//! # References
+//!
+//! - Connors, P. A., Piran, T. & Stark, R. F. (1980). "Polarization
+//!   features of X-ray radiation emitted near black holes"
+//! - Shcherbakov, R. V. & Huang, L. (2011). "General relativistic
+//!   polarized radiative transfer: building a dynamics-observations
+//!   interface"
+//! - Dexter, J. (2016). "A public code for general relativistic, polarised
+//!   radiative transfer around spinning black holes"
+//! - Moscibrodzka, M. & Gammie, C. F. (2018). "IPOLE - semi-analytic
+//!   scheme for relativistic polarized radiative transport"
+
+use std::ops::Add;
+
+use num_complex::Complex64;
+
+use super::matter::{FluidState, MatterField, MatterMedium};
+use super::transfer::{segment_midpoint, TransferSettings, OPAQUE_DEPTH};
+use crate::geodesic::{step_rk4, GeodesicState};
+use crate::invariants::walker_penrose;
+use crate::metric::{contract, Kerr, Metric};
+use crate::tensor::christoffel_from_metric_derivs;
+use crate::tetrad::Tetrad;
+
+/// Finite-difference step for the Christoffel symbols of
+/// [`parallel_transport`].
+const CHRISTOFFEL_EPS: f64 = 1e-6;
+
+/// Largest optical or Faraday depth of one integration substep.
+const MAX_SUBSTEP_DEPTH: f64 = 0.25;
+
+/// Substeps per segment at most; deeper segments are thick or scrambled.
+const MAX_SUBSTEPS: usize = 4096;
+
+/// Absorption depth beyond which a segment is treated as being in local
+/// equilibrium, S = K^-1 j.
+const THICK_DEPTH: f64 = 30.0;
+
+/// Stokes parameters of a polarized intensity.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Stokes {
+    /// Total intensity.
+    pub i: f64,
+    /// Linear polarization along the first basis vector.
+    pub q: f64,
+    /// Linear polarization at 45 degrees, towards the second basis vector.
+    pub u: f64,
+    /// Circular polarization.
+    pub v: f64,
+}
+
+impl Stokes {
+    /// Stokes vector (I, Q, U, V).
+    pub fn new(i: f64, q: f64, u: f64, v: f64) -> Self {
+        Self { i, q, u, v }
+    }
+
+    /// Linearly polarized intensity sqrt(Q^2 + U^2).
+    pub fn linear(&self) -> f64 {
+        self.q.hypot(self.u)
+    }
+
+    /// Degree of linear polarization, zero for zero intensity.
+    pub fn linear_fraction(&self) -> f64 {
+        if self.i > 0.0 {
+            self.linear() / self.i
+        } else {
+            0.0
+        }
+    }
+
+    /// Signed degree of circular polarization, V / I.
+    pub fn circular_fraction(&self) -> f64 {
+        if self.i > 0.0 {
+            self.v / self.i
+        } else {
+            0.0
+        }
+    }
+
+    /// Electric vector position angle chi = atan2(U, Q) / 2 in radians,
+    /// from the first basis vector towards the second, in (-pi/2, pi/2].
+    pub fn evpa(&self) -> f64 {
+        0.5 * self.u.atan2(self.q)
+    }
+
+    /// Components in the basis rotated by `angle` from the current one.
+    pub fn rotated(&self, angle: f64) -> Self {
+        let (sin, cos) = (2.0 * angle).sin_cos();
+        Self {
+            i: self.i,
+            q: self.q * cos + self.u * sin,
+            u: -self.q * sin + self.u * cos,
+            v: self.v,
+        }
+    }
+
+    /// Every component multiplied by `factor`.
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self::from_array(self.to_array().map(|s| s * factor))
+    }
+
+    fn from_array(s: [f64; 4]) -> Self {
+        Self::new(s[0], s[1], s[2], s[3])
+    }
+
+    fn to_array(self) -> [f64; 4] {
+        [self.i, self.q, self.u, self.v]
+    }
+}
+
+impl Add for Stokes {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.i + other.i,
+            self.q + other.q,
+            self.u + other.u,
+            self.v + other.v,
+        )
+    }
+}
+
+/// Fluid-frame polarized transfer coefficients at one frequency, in the
+/// basis with Stokes Q along the magnetic field projected on the sky.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PolarizedCoefficients {
+    /// Emissivities (j_I, j_Q, j_U, j_V).
+    pub emissivity: Stokes,
+    /// Absorptivities (alpha_I, alpha_Q, alpha_U, alpha_V).
+    pub absorptivity: Stokes,
+    /// Faraday conversion rho_Q and rho_U and rotation rho_V, inverse
+    /// length.
+    pub rotativity: [f64; 3],
+}
+
+/// Fluid-frame polarized emission, absorption and Faraday effects.
+pub trait PolarizedEmission {
+    /// Coefficients (SI) at fluid-frame frequency `nu` in Hz, for a photon
+    /// at `pitch_angle` to the magnetic field.
+    fn polarized_coefficients(
+        &self,
+        fluid: &FluidState,
+        nu: f64,
+        pitch_angle: f64,
+    ) -> PolarizedCoefficients;
+}
+
+/// Result of integrating polarized transfer along one ray.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PolarizedResult {
+    /// Observed Stokes parameters at the camera frequency, in the camera's
+    /// polarization basis.
+    pub stokes: Stokes,
+    /// Total Stokes I optical depth along the traced part of the ray.
+    pub optical_depth: f64,
+}
+
+/// Polarization basis (f, g = n x f) for the photon with covariant
+/// momentum `k`, seen by the observer of `tetrad`: f is the unit frame
+/// direction `reference` made orthogonal to the photon's direction n.
+///
+/// Returns coordinate vectors, or `None` if `k` has no spatial direction.
+/// If `reference` is along n, any orthogonal f is chosen.
+pub fn polarization_basis(
+    tetrad: &Tetrad,
+    k: &[f64; 4],
+    reference: [f64; 3],
+) -> Option<([f64; 4], [f64; 4])> {
+    let k_frame = tetrad.project_covector(k);
+    let n = normalized([k_frame[1], k_frame[2], k_frame[3]])?;
+    let along = dot3(&reference, &n);
+    let f = normalized(std::array::from_fn(|i| reference[i] - along * n[i])).or_else(|| {
+        // Least aligned axis, made orthogonal to n.
+        let axis = (0..3)
+            .min_by(|&i, &j| n[i].abs().total_cmp(&n[j].abs()))
+            .unwrap_or(0);
+        normalized(std::array::from_fn(|i| {
+            let unit = if i == axis { 1.0 } else { 0.0 };
+            unit - n[axis] * n[i]
+        }))
+    })?;
+    let g = cross(&n, &f);
+    Some((
+        tetrad.vector_from_frame(&[0.0, f[0], f[1], f[2]]),
+        tetrad.vector_from_frame(&[0.0, g[0], g[1], g[2]]),
+    ))
+}
+
+/// Angle from `f` towards `g` of the parallel-transported polarization
+/// vector with Walker-Penrose constant `kappa`, where (f, g) is a
+/// polarization basis of the ray `state`.
+///
+/// Returns `None` where kappa does not determine the vector, as for rays
+/// with vanishing K = Q + (L - a E)^2.
+pub fn transported_angle(
+    bh: &Kerr,
+    state: &GeodesicState,
+    kappa: Complex64,
+    f: &[f64; 4],
+    g: &[f64; 4],
+) -> Option<f64> {
+    let kf = walker_penrose(bh, state, f);
+    let kg = walker_penrose(bh, state, g);
+    let det = kf.re * kg.im - kg.re * kf.im;
+    if det.abs() <= 1e-12 * (kf.norm_sqr() + kg.norm_sqr()) {
+        return None;
+    }
+    let along_f = (kappa.re * kg.im - kg.re * kappa.im) / det;
+    let along_g = (kf.re * kappa.im - kappa.re * kf.im) / det;
+    Some(along_g.atan2(along_f))
+}
+
+/// Advance the ray `state` by one RK4 step `h`, parallel-transporting the
+/// contravariant vector `f` with it:
+///
+///   df^mu / dlambda = -Gamma^mu_(alpha beta) k^alpha f^beta.
+pub fn parallel_transport<M: Metric>(
+    metric: &M,
+    state: &mut GeodesicState,
+    f: &mut [f64; 4],
+    h: f64,
+) {
+    let rate = |s: &GeodesicState, f: &[f64; 4]| {
+        let k = metric.contravariant(s.x[1], s.x[2]).raise_index(&s.p);
+        let gamma = christoffel_from_metric_derivs(metric, s.x[1], s.x[2], CHRISTOFFEL_EPS);
+        let mut out = [0.0; 4];
+        for (mu, out) in out.iter_mut().enumerate() {
+            for alpha in 0..4 {
+                for beta in 0..4 {
+                    *out -= gamma[mu][alpha][beta] * k[alpha] * f[beta];
+                }
+            }
+        }
+        out
+    };
+    let shifted = |f: &[f64; 4], df: &[f64; 4], h: f64| -> [f64; 4] {
+        std::array::from_fn(|i| f[i] + h * df[i])
+    };
+
+    let mut half = *state;
+    step_rk4(&mut half, metric, 0.5 * h);
+    let mut full = *state;
+    step_rk4(&mut full, metric, h);
+
+    let k1 = rate(state, f);
+    let k2 = rate(&half, &shifted(f, &k1, 0.5 * h));
+    let k3 = rate(&half, &shifted(f, &k2, 0.5 * h));
+    let k4 = rate(&full, &shifted(f, &k3, h));
+    for i in 0..4 {
+        f[i] += h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+    }
+    *state = full;
+}
+
+/// Integrate polarized transfer along a recorded backward ray `path`
+/// (camera first) through `medium`.
+///
+/// The camera has 4-velocity `u_camera` and measures Stokes parameters in
+/// the polarization basis with first vector `f_camera`, for example from
+/// [`polarization_basis`]. The equation is integrated from the far end of
+/// the path towards the camera, starting where the camera's optical depth
+/// exceeds [`OPAQUE_DEPTH`].
+pub fn polarized_transfer_along_path<F: MatterField, E: PolarizedEmission>(
+    path: &[GeodesicState],
+    bh: &Kerr,
+    medium: &MatterMedium<F, E>,
+    settings: TransferSettings,
+    u_camera: &[f64; 4],
+    f_camera: &[f64; 4],
+) -> PolarizedResult {
+    let Some(first) = path.first() else {
+        return PolarizedResult::default();
+    };
+    let camera_energy = contract(&first.p, u_camera);
+    let kappa = walker_penrose(bh, first, f_camera);
+
+    // Segments in matter, outward from the camera until opaque.
+    let mut segments = Vec::new();
+    let mut optical_depth = 0.0;
+    let mut last_angle = 0.0;
+    for pair in path.windows(2) {
+        if optical_depth > OPAQUE_DEPTH {
+            break;
+        }
+        let Some((mid, dlambda)) = segment_midpoint(bh, &pair[0], &pair[1]) else {
+            continue;
+        };
+        let Some(fluid) = medium.field.fluid(&mid.x) else {
+            continue;
+        };
+        let energy = contract(&mid.p, &fluid.velocity);
+        let shift = energy / camera_energy;
+        if !(shift > 0.0 && shift.is_finite()) {
+            continue;
+        }
+        let nu = shift * settings.frequency;
+        let k = mid.p.map(|p| -p);
+        let tetrad = Tetrad::adapted(bh, mid.x[1], mid.x[2], fluid.velocity);
+        let b_frame = tetrad.project_vector(&fluid.magnetic_field.direction);
+        let Some((e1, e2)) = polarization_basis(&tetrad, &k, [b_frame[1], b_frame[2], b_frame[3]])
+        else {
+            continue;
+        };
+        // Rays with K = 0 (radial ones, say) keep the last known angle.
+        let angle = transported_angle(bh, &mid, kappa, &e1, &e2).unwrap_or(last_angle);
+        last_angle = angle;
+
+        let c = medium
+            .emission
+            .polarized_coefficients(&fluid, nu, fluid.pitch_angle(&k));
+        let length = energy * dlambda * settings.length_unit;
+        let segment = Segment::new(&c, nu, length, angle);
+        optical_depth += segment.matrix[0][0];
+        segments.push(segment);
+    }
+
+    // Stokes parameters in the transported basis, from the far end in.
+    let mut invariant = Stokes::default();
+    for segment in segments.iter().rev() {
+        // The field basis lies at -angle from the transported one.
+        let local = segment.propagate(invariant.rotated(-segment.angle));
+        invariant = local.rotated(segment.angle);
+    }
+    PolarizedResult {
+        stokes: invariant.scaled(settings.frequency.powi(3)),
+        optical_depth,
+    }
+}
+
+/// One stretch of matter with constant coefficients, scaled by its length.
+struct Segment {
+    /// Invariant emission j / nu^3 times length.
+    source: [f64; 4],
+    /// Transfer matrix K times length.
+    matrix: [[f64; 4]; 4],
+    /// Angle of the transported basis from the field basis.
+    angle: f64,
+}
+
+impl Segment {
+    fn new(c: &PolarizedCoefficients, nu: f64, length: f64, angle: f64) -> Self {
+        let j = c.emissivity.scaled(length / nu.powi(3));
+        let [ai, aq, au, av] = c.absorptivity.scaled(length).to_array();
+        let [rq, ru, rv] = c.rotativity.map(|rho| rho * length);
+        Self {
+            source: j.to_array(),
+            matrix: [
+                [ai, aq, au, av],
+                [aq, ai, rv, -ru],
+                [au, -rv, ai, rq],
+                [av, ru, -rq, ai],
+            ],
+            angle,
+        }
+    }
+
+    /// Stokes vector leaving the segment for `incoming` entering it.
+    fn propagate(&self, incoming: Stokes) -> Stokes {
+        if self.matrix[0][0] > THICK_DEPTH {
+            if let Some(equilibrium) = solve(self.matrix, self.source) {
+                return Stokes::from_array(equilibrium);
+            }
+        }
+        let depth = self
+            .matrix
+            .iter()
+            .flatten()
+            .fold(0.0f64, |m, x| m.max(x.abs()));
+        let substeps = ((depth / MAX_SUBSTEP_DEPTH).ceil() as usize).clamp(1, MAX_SUBSTEPS);
+        let h = 1.0 / substeps as f64;
+        let rate = |s: &[f64; 4]| -> [f64; 4] {
+            std::array::from_fn(|i| {
+                self.source[i] - (0..4).map(|j| self.matrix[i][j] * s[j]).sum::<f64>()
+            })
+        };
+        let shifted = |s: &[f64; 4], ds: &[f64; 4], h: f64| -> [f64; 4] {
+            std::array::from_fn(|i| s[i] + h * ds[i])
+        };
+
+        let mut s = incoming.to_array();
+        for _ in 0..substeps {
+            let k1 = rate(&s);
+            let k2 = rate(&shifted(&s, &k1, 0.5 * h));
+            let k3 = rate(&shifted(&s, &k2, 0.5 * h));
+            let k4 = rate(&shifted(&s, &k3, h));
+            for i in 0..4 {
+                s[i] += h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+            }
+        }
+        Stokes::from_array(s)
+    }
+}
+
+/// Solve a x = b by Gaussian elimination with partial pivoting.
+#[allow(clippy::needless_range_loop)]
+fn solve(mut a: [[f64; 4]; 4], mut b: [f64; 4]) -> Option<[f64; 4]> {
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col] == 0.0 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..4 {
+            let factor = a[row][col] / a[col][col];
+            for k in col..4 {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; 4];
+    for row in (0..4).rev() {
+        let rest: f64 = (row + 1..4).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+    Some(x)
+}
+
+fn normalized(v: [f64; 3]) -> Option<[f64; 3]> {
+    let norm = dot3(&v, &v).sqrt();
+    (norm > 1e-9).then(|| v.map(|x| x / norm))
+}
+
+fn dot3(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Camera;
+    use crate::invariants::compute_constants;
+    use crate::physics::matter::MagneticField;
+    use crate::tetrad::static_velocity;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn test_walker_penrose_is_conserved_by_transport() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            let camera = Camera::zamo(&bh, [0.0, 30.0, 1.0, 0.0], 0.6, 8, 8);
+            let tetrad = camera.tetrad(&bh);
+            let mut state = camera.ray(&tetrad, 1.5, 6.0).unwrap();
+            let k = state.p.map(|p| -p);
+            let (mut f, _) = polarization_basis(&tetrad, &k, camera.up()).unwrap();
+            let kappa = walker_penrose(&bh, &state, &f);
+
+            // |kappa|^2 = K for a unit f orthogonal to k.
+            let c = compute_constants(&state, &bh);
+            let big_k = c.carter_constant + (c.angular_momentum - bh.a() * c.energy).powi(2);
+            assert!((kappa.norm_sqr() / big_k - 1.0).abs() < 1e-10);
+
+            for _ in 0..500 {
+                parallel_transport(&bh, &mut state, &mut f, 0.04);
+            }
+            assert!(state.x[1] < 15.0);
+            let drift = (walker_penrose(&bh, &state, &f) - kappa).norm() / kappa.norm();
+            assert!(drift < 1e-6, "{drift}");
+        }
+    }
+
+    /// Static, uniformly magnetized shells: emitting for density 1,
+    /// a pure Faraday screen for density 2.
+    struct Shells {
+        bh: Kerr,
+        emitter: (f64, f64),
+        screen: (f64, f64),
+        /// Field angle from -e_theta (image up) towards -e_phi (image left).
+        field_angle: f64,
+    }
+
+    impl MatterField for Shells {
+        fn density(&self, x: &[f64; 4]) -> f64 {
+            let inside = |(inner, outer): (f64, f64)| (inner..=outer).contains(&x[1]);
+            if inside(self.emitter) {
+                1.0
+            } else if inside(self.screen) {
+                2.0
+            } else {
+                0.0
+            }
+        }
+
+        fn temperature(&self, _x: &[f64; 4]) -> f64 {
+            1e10
+        }
+
+        fn velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+            static_velocity(&self.bh, x[1], x[2])
+        }
+
+        fn magnetic_field(&self, x: &[f64; 4]) -> MagneticField {
+            let (sin, cos) = self.field_angle.sin_cos();
+            MagneticField {
+                strength: 1.0,
+                direction: [0.0, 0.0, -cos / x[1], -sin / (x[1] * x[2].sin())],
+            }
+        }
+    }
+
+    /// Constant coefficients for the emitter and the screen.
+    struct Constant {
+        emitter: PolarizedCoefficients,
+        screen: PolarizedCoefficients,
+    }
+
+    impl PolarizedEmission for Constant {
+        fn polarized_coefficients(
+            &self,
+            fluid: &FluidState,
+            _nu: f64,
+            _pitch_angle: f64,
+        ) -> PolarizedCoefficients {
+            if fluid.density == 1.0 {
+                self.emitter
+            } else {
+                self.screen
+            }
+        }
+    }
+
+    /// Stokes parameters seen along a radial ray through the shells.
+    fn observe(field_angle: f64, rotation: f64) -> PolarizedResult {
+        let bh = Kerr::new(1.0, 0.0);
+        let camera =
+            Camera::static_observer(&bh, [0.0, 100.0, FRAC_PI_2, 0.0], 0.01, 1, 1).unwrap();
+        let tetrad = camera.tetrad(&bh);
+        // Radial rays have K = 0 and leave kappa blind; aim just off.
+        let mut ray = camera.ray(&tetrad, 0.5001, 0.5).unwrap();
+        let (f, _) = polarization_basis(&tetrad, &ray.p.map(|p| -p), camera.up()).unwrap();
+
+        let mut path = vec![ray];
+        while ray.x[1] > 5.0 {
+            step_rk4(&mut ray, &bh, 0.05);
+            path.push(ray);
+        }
+        let medium = MatterMedium {
+            field: Shells {
+                bh,
+                emitter: (10.0, 20.0),
+                screen: (25.0, 30.0),
+                field_angle,
+            },
+            emission: Constant {
+                emitter: PolarizedCoefficients {
+                    emissivity: Stokes::new(1.0, -0.5, 0.0, 0.0),
+                    ..PolarizedCoefficients::default()
+                },
+                screen: PolarizedCoefficients {
+                    rotativity: [0.0, 0.0, rotation],
+                    ..PolarizedCoefficients::default()
+                },
+            },
+        };
+        polarized_transfer_along_path(
+            &path,
+            &bh,
+            &medium,
+            TransferSettings::default(),
+            &camera.velocity,
+            &f,
+        )
+    }
+
+    /// Difference of two position angles, modulo pi.
+    fn angle_difference(a: f64, b: f64) -> f64 {
+        (a - b + FRAC_PI_2).rem_euclid(PI) - FRAC_PI_2
+    }
+
+    #[test]
+    fn test_polarization_is_across_the_field() {
+        for field_angle in [0.0, 0.5, -1.2] {
+            let result = observe(field_angle, 0.0);
+            let stokes = result.stokes;
+            assert!((stokes.linear_fraction() - 0.5).abs() < 1e-9);
+            let error = angle_difference(stokes.evpa(), field_angle + FRAC_PI_2);
+            assert!(error.abs() < 1e-6, "{field_angle}: {}", stokes.evpa());
+        }
+    }
+
+    #[test]
+    fn test_faraday_rotation() {
+        // The screen turns the EVPA by rho_V L / 2, with L its proper
+        // thickness, integral dr / sqrt(1 - 2/r).
+        let proper = |r: f64| (r * (r - 2.0)).sqrt() + 2.0 * (r.sqrt() + (r - 2.0).sqrt()).ln();
+        let thickness = proper(30.0) - proper(25.0);
+        let rho = 0.1;
+        let plain = observe(0.3, 0.0).stokes;
+        let rotated = observe(0.3, rho).stokes;
+        let turn = angle_difference(rotated.evpa(), plain.evpa());
+        assert!(
+            (turn - 0.5 * rho * thickness).abs() < 1e-3,
+            "{turn} vs {}",
+            0.5 * rho * thickness
+        );
+        assert!((rotated.i - plain.i).abs() < 1e-12 * plain.i);
+        assert!((rotated.linear() - plain.linear()).abs() < 1e-9 * plain.linear());
+    }
+}
@@ -11,6 +11,7 @@
 //! - Chandrasekhar, S. (1983). "The Mathematical Theory of Black Holes"
 //! - Gralla, Lupsasca & Marolf (2020). "Observational appearance of black holes"
 
+use crate::camera::image_plane::{beta_squared, constants_to_screen};
 use crate::metric::{Kerr, Metric};
 
 // ============================================================================
@@ -82,7 +83,6 @@ pub fn bardeen_shadow(bh: &Kerr, theta_obs: f64, n_points: usize) -> Vec<(f64, f
     let m = bh.mass();
     let a = bh.a();
     let sin_obs = theta_obs.sin();
-    let cos_obs = theta_obs.cos();
 
     // Schwarzschild special case (a=0): shadow is a perfect circle
     // at b_crit = 3*sqrt(3)*M. The Bardeen formula degenerates here
@@ -125,9 +125,7 @@ pub fn bardeen_shadow(bh: &Kerr, theta_obs: f64, n_points: usize) -> Vec<(f64, f
         let t = i as f64 / steps as f64;
         let r = r_ph_pro + t * (r_ph_retro - r_ph_pro);
         let params = critical_params(r, m, a);
-        let beta_sq = params.eta + a * a * cos_obs * cos_obs
-            - params.xi * params.xi * cos_obs * cos_obs / (sin_obs * sin_obs);
-        if beta_sq >= 0.0 {
+        if beta_squared(params.xi, params.eta, a, theta_obs) >= 0.0 {
             r_min = r;
             break;
         }
@@ -137,9 +135,7 @@ pub fn bardeen_shadow(bh: &Kerr, theta_obs: f64, n_points: usize) -> Vec<(f64, f
         let t = i as f64 / steps as f64;
         let r = r_ph_pro + t * (r_ph_retro - r_ph_pro);
         let params = critical_params(r, m, a);
-        let beta_sq = params.eta + a * a * cos_obs * cos_obs
-            - params.xi * params.xi * cos_obs * cos_obs / (sin_obs * sin_obs);
-        if beta_sq >= 0.0 {
+        if beta_squared(params.xi, params.eta, a, theta_obs) >= 0.0 {
             r_max = r;
             break;
         }
@@ -155,13 +151,7 @@ pub fn bardeen_shadow(bh: &Kerr, theta_obs: f64, n_points: usize) -> Vec<(f64, f
         let r = r_min + t * (r_max - r_min);
 
         let params = critical_params(r, m, a);
-        let alpha = a * sin_obs - params.xi / sin_obs;
-        let beta_sq = params.eta + a * a * cos_obs * cos_obs
-            - params.xi * params.xi * cos_obs * cos_obs / (sin_obs * sin_obs);
-
-        // Max(0.0) ensures we safely clamp tiny floating point negatives at the roots
-        let beta = beta_sq.max(0.0).sqrt();
-        points.push((alpha, -beta));
+        points.push(shadow_point(&params, a, theta_obs, -1.0));
     }
 
     // Sweep back from retrograde to prograde (top half)
@@ -171,17 +161,25 @@ pub fn bardeen_shadow(bh: &Kerr, theta_obs: f64, n_points: usize) -> Vec<(f64, f
         let r = r_min + t * (r_max - r_min);
 
         let params = critical_params(r, m, a);
-        let alpha = a * sin_obs - params.xi / sin_obs;
-        let beta_sq = params.eta + a * a * cos_obs * cos_obs
-            - params.xi * params.xi * cos_obs * cos_obs / (sin_obs * sin_obs);
-
-        let beta = beta_sq.max(0.0).sqrt();
-        points.push((alpha, beta));
+        points.push(shadow_point(&params, a, theta_obs, 1.0));
     }
 
     points
 }
 
+/// Screen position of a critical orbit, shared with traced images through
+/// [`constants_to_screen`].
+fn shadow_point(
+    params: &CriticalOrbitParams,
+    a: f64,
+    theta_obs: f64,
+    beta_sign: f64,
+) -> (f64, f64) {
+    // Tiny floating point negatives of beta^2 at the roots fall back to beta = 0
+    constants_to_screen(params.xi, params.eta, a, theta_obs, beta_sign)
+        .unwrap_or((-params.xi / theta_obs.sin(), 0.0))
+}
+
 /// Schwarzschild shadow radius (a=0 special case).
 ///
 /// For a Schwarzschild black hole, the shadow is a circle with radius
@@ -323,6 +321,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_near_extremal_equatorial_shadow_extent() {
+        // For a -> M at theta_obs = 90 deg the shadow spans alpha in [-2M, 7M].
+        let bh = Kerr::new(1.0, 0.9999);
+        let shadow = bardeen_shadow(&bh, std::f64::consts::FRAC_PI_2, 400);
+        let min_alpha = shadow.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+        let max_alpha = shadow.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+        assert!((min_alpha + 2.0).abs() < 0.1, "min alpha = {}", min_alpha);
+        assert!((max_alpha - 7.0).abs() < 0.05, "max alpha = {}", max_alpha);
+    }
+
     #[test]
     fn test_einstein_angle_scaling() {
         let theta1 = einstein_angle(1.0, 100.0);
@@ -17,6 +17,17 @@ pub fn planck_law(lambda: f64, temperature: f64) -> f64 {
     (C1 / lambda.powi(5)) / (exponent.exp() - 1.0)
 }
 
+/// Planck's Law per unit frequency: B(nu, T) in W m^-2 Hz^-1 sr^-1.
+///
+/// B = (2h nu^3 / c^2) / (exp(h nu / k T) - 1)
+pub fn planck_frequency(nu: f64, temperature: f64) -> f64 {
+    let exponent = H * nu / (SI_KB * temperature);
+    if exponent > 700.0 || temperature <= 0.0 {
+        return 0.0;
+    }
+    2.0 * H * nu.powi(3) / (SI_C * SI_C) / exponent.exp_m1()
+}
+
 /// Integrate Planck spectrum against CIE 1931 XYZ color matching functions.
 ///
 /// Returns [X, Y, Z] tristimulus values.
@@ -0,0 +1,629 @@
+//! Synchrotron emission and absorption of magnetized plasma.
+//!
+//! Coefficients are evaluated in the fluid frame, in SI units, from the
+//! electron density, electron temperature and field strength of a
+//! [`FluidState`], and the pitch angle theta between the photon and the
+//! field measured in that frame. Emission vanishes along the field.
+//!
+//! - [`ThermalSynchrotron`] uses the fit of Leung, Gammie & Noble for a
+//!   relativistic Maxwell-Juettner distribution,
+//!   j_nu = sqrt(2) pi e^2 n nu_s (X^1/2 + 2^11/12 X^1/6)^2 exp(-X^1/3)
+//!   / (3 K_2(1 / Theta_e) c),
+//!   with nu_s = (2/9) nu_B Theta_e^2 sin(theta) and X = nu / nu_s, where
+//!   nu_B = e B / (2 pi m_e) is the cyclotron frequency and
+//!   Theta_e = k T_e / (m_e c^2). Absorption follows from Kirchhoff's law,
+//!   alpha_nu = j_nu / B_nu(T_e). Its polarized coefficients, including
+//!   Faraday rotation rho_V and conversion rho_Q, follow the fits of
+//!   Dexter (2016) and Shcherbakov (2008), in the basis with Stokes Q
+//!   along the field projected on the sky ([`PolarizedEmission`]).
+//! - [`PowerLawSynchrotron`] takes n(gamma) ~ gamma^-p between gamma_min and
+//!   gamma_max, with the classic optically thin and self-absorbed power
+//!   laws j_nu ~ nu^-(p-1)/2 and alpha_nu ~ nu^-(p+4)/2.
+//! - [`AveragedThermalSynchrotron`] is the angle-averaged thermal fit of
+//!   Mahadevan, Narayan & Yi, for flows without a field direction.
+//!
+//! Formulas are quoted in Gaussian units; e^2 stands for e^2 / (4 pi eps_0).
+//!
+//! # References
+//!
+//! - Leung, P. K., Gammie, C. F. & Noble, S. C. (2011). "Numerical
+//!   Calculation of Magnetobremsstrahlung Emission and Absorption
+//!   Coefficients"
+//! - Dexter, J. (2016). "A public code for general relativistic, polarised
+//!   radiative transfer around spinning black holes"
+//! - Shcherbakov, R. V. (2008). "Propagation Effects in Magnetized
+//!   Transrelativistic Plasmas"
+//! - Mahadevan, R., Narayan, R. & Yi, I. (1996). "Harmony in Electrons:
+//!   Cyclotron and Synchrotron Emission by Thermal Electrons in a Magnetic
+//!   Field"
+//! - Rybicki, G. B. & Lightman, A. P. (1979). "Radiative Processes in
+//!   Astrophysics"
+
+use std::f64::consts::PI;
+
+use super::matter::{Emission, FluidState};
+use super::polarization::{PolarizedCoefficients, PolarizedEmission, Stokes};
+use super::spectrum;
+use super::transfer::Coefficients;
+use crate::constants::{SI_C, SI_ELECTRON_MASS, SI_ELEMENTARY_CHARGE, SI_EPSILON_0, SI_KB};
+
+/// Electron temperatures below this Theta_e are treated as non-emitting;
+/// the relativistic fits do not hold there.
+const MIN_THETA: f64 = 0.01;
+
+/// Dimensionless electron temperature Theta_e = k T / (m_e c^2).
+pub fn electron_temperature(temperature: f64) -> f64 {
+    SI_KB * temperature / (SI_ELECTRON_MASS * SI_C * SI_C)
+}
+
+/// Cyclotron frequency nu_B = e B / (2 pi m_e) in Hz, for B in Tesla.
+pub fn cyclotron_frequency(field_strength: f64) -> f64 {
+    SI_ELEMENTARY_CHARGE * field_strength / (2.0 * PI * SI_ELECTRON_MASS)
+}
+
+/// Modified Bessel function of the second kind K_2(x), x > 0.
+///
+/// Polynomial approximations of K_0 and K_1 (Abramowitz & Stegun 9.8),
+/// combined with K_2 = K_0 + (2 / x) K_1. Relative error below 1e-6.
+pub fn bessel_k2(x: f64) -> f64 {
+    if x <= 0.0 {
+        return f64::INFINITY;
+    }
+    let (k0, k1) = bessel_k01(x);
+    k0 + 2.0 / x * k1
+}
+
+/// K_0(x) and K_1(x) for x > 0, Abramowitz & Stegun 9.8.5 - 9.8.8.
+fn bessel_k01(x: f64) -> (f64, f64) {
+    if x <= 2.0 {
+        let t = (x / 2.0).powi(2);
+        let s = (x / 3.75).powi(2);
+        let i0 = 1.0
+            + s * (3.5156229
+                + s * (3.0899424
+                    + s * (1.2067492 + s * (0.2659732 + s * (0.0360768 + s * 0.0045813)))));
+        let i1 = x
+            * (0.5
+                + s * (0.87890594
+                    + s * (0.51498869
+                        + s * (0.15084934
+                            + s * (0.02658733 + s * (0.00301532 + s * 0.00032411))))));
+        let k0 = -(x / 2.0).ln() * i0
+            + (-0.57721566
+                + t * (0.42278420
+                    + t * (0.23069756
+                        + t * (0.03488590 + t * (0.00262698 + t * (0.00010750 + t * 0.0000074))))));
+        let k1 = ((x / 2.0).ln() * x * i1
+            + (1.0
+                + t * (0.15443144
+                    + t * (-0.67278579
+                        + t * (-0.18156897
+                            + t * (-0.01919402 + t * (-0.00110404 + t * -0.00004686)))))))
+            / x;
+        (k0, k1)
+    } else {
+        let y = 2.0 / x;
+        let scale = (-x).exp() / x.sqrt();
+        let k0 = scale
+            * (1.25331414
+                + y * (-0.07832358
+                    + y * (0.02189568
+                        + y * (-0.01062446
+                            + y * (0.00587872 + y * (-0.00251540 + y * 0.00053208))))));
+        let k1 = scale
+            * (1.25331414
+                + y * (0.23498619
+                    + y * (-0.03655620
+                        + y * (0.01504268
+                            + y * (-0.00780353 + y * (0.00325614 + y * -0.00068245))))));
+        (k0, k1)
+    }
+}
+
+/// Gamma function, Lanczos approximation (relative error ~1e-15) with the
+/// reflection formula below 1/2.
+pub fn gamma_function(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return PI / ((PI * x).sin() * gamma_function(1.0 - x));
+    }
+    let x = x - 1.0;
+    let t = x + G + 0.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    (2.0 * PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * series
+}
+
+/// e^2 / (4 pi eps_0), the Gaussian e^2 in SI units.
+fn charge_squared() -> f64 {
+    SI_ELEMENTARY_CHARGE * SI_ELEMENTARY_CHARGE / (4.0 * PI * SI_EPSILON_0)
+}
+
+/// Kirchhoff absorption for emission from thermal electrons.
+fn kirchhoff(emissivity: f64, nu: f64, temperature: f64) -> Coefficients {
+    let planck = spectrum::planck_frequency(nu, temperature);
+    Coefficients {
+        emissivity,
+        absorptivity: if planck > 0.0 {
+            emissivity / planck
+        } else {
+            0.0
+        },
+    }
+}
+
+/// Synchrotron from a relativistic Maxwell-Juettner distribution, as a
+/// function of pitch angle.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ThermalSynchrotron;
+
+impl ThermalSynchrotron {
+    /// Emissivity j_nu in W m^-3 Hz^-1 sr^-1 at `pitch_angle` to the field.
+    pub fn emissivity(
+        &self,
+        density: f64,
+        temperature: f64,
+        field_strength: f64,
+        nu: f64,
+        pitch_angle: f64,
+    ) -> f64 {
+        let theta = electron_temperature(temperature);
+        let nu_s = 2.0 / 9.0
+            * cyclotron_frequency(field_strength)
+            * theta
+            * theta
+            * pitch_angle.sin().abs();
+        if theta < MIN_THETA || nu_s <= 0.0 || density <= 0.0 {
+            return 0.0;
+        }
+        let x = nu / nu_s;
+        let shape =
+            (x.sqrt() + 2f64.powf(11.0 / 12.0) * x.powf(1.0 / 6.0)).powi(2) * (-x.cbrt()).exp();
+        2f64.sqrt() * PI * charge_squared() * density * nu_s / (3.0 * bessel_k2(1.0 / theta) * SI_C)
+            * shape
+    }
+}
+
+impl ThermalSynchrotron {
+    /// Polarized emission, absorption and Faraday coefficients at
+    /// `pitch_angle` to the field, with Stokes Q along the projected field.
+    ///
+    /// Synchrotron light is polarized across the field, so j_Q < 0. The
+    /// Stokes I emissivity is [`emissivity`](Self::emissivity); the Q and V
+    /// fractions are Dexter's, and every absorptivity follows from
+    /// Kirchhoff's law.
+    pub fn polarized(
+        &self,
+        density: f64,
+        temperature: f64,
+        field_strength: f64,
+        nu: f64,
+        pitch_angle: f64,
+    ) -> PolarizedCoefficients {
+        let theta = electron_temperature(temperature);
+        let nu_b = cyclotron_frequency(field_strength);
+        if density <= 0.0 || nu_b <= 0.0 || theta <= 0.0 {
+            return PolarizedCoefficients::default();
+        }
+        let (sin, cos) = pitch_angle.sin_cos();
+
+        let j_i = self.emissivity(density, temperature, field_strength, nu, pitch_angle);
+        let emissivity = if j_i > 0.0 {
+            // Dexter's shapes I_I, I_Q and I_V of x = nu / nu_c share the
+            // factor exp(-1.8899 x^1/3), which cancels in the fractions.
+            let x = nu / (1.5 * nu_b * sin.abs() * theta * theta);
+            let y = x.powf(-1.0 / 3.0);
+            let shape_i = 2.5651 * (1.0 + 1.92 * y + 0.9977 * y * y);
+            let shape_q = 2.5651 * (1.0 + 0.932 * y + 0.4998 * y * y);
+            let shape_v = 1.81384 / x + 3.42319 * y * y + 0.0292545 / x.sqrt() + 2.03773 * y;
+            Stokes::new(
+                j_i,
+                -j_i * shape_q / shape_i,
+                0.0,
+                j_i * 4.0 * cos / (3.0 * theta * sin) * shape_v / shape_i,
+            )
+        } else {
+            Stokes::default()
+        };
+        let planck = spectrum::planck_frequency(nu, temperature);
+        let absorptivity = if planck > 0.0 {
+            emissivity.scaled(1.0 / planck)
+        } else {
+            Stokes::default()
+        };
+
+        // Thermal Faraday rotation and conversion, reducing to the cold
+        // plasma values rho_V = 2 n e^2 nu_B cos / (m_e c nu^2) and
+        // rho_Q = n e^2 nu_B^2 sin^2 / (m_e c nu^3) for Theta_e -> 0.
+        let (k0_ratio, k1_ratio) = if theta < MIN_THETA {
+            (1.0, 1.0)
+        } else {
+            let (k0, k1) = bessel_k01(1.0 / theta);
+            let k2 = k0 + 2.0 * theta * k1;
+            (k0 / k2, k1 / k2)
+        };
+        let plasma = charge_squared() * density / (SI_ELECTRON_MASS * SI_C);
+        let big_x = theta * (2f64.sqrt() * sin.abs() * 1e3 * nu_b / nu).sqrt();
+        let rho_v = 2.0 * plasma * nu_b * cos / (nu * nu)
+            * k0_ratio
+            * (1.0 - 0.11 * (1.0 + 0.035 * big_x).ln());
+        let rho_q = -plasma * nu_b * nu_b * sin * sin / nu.powi(3)
+            * faraday_conversion_shape(big_x)
+            * (k1_ratio + 6.0 * theta);
+
+        PolarizedCoefficients {
+            emissivity,
+            absorptivity,
+            rotativity: [rho_q, 0.0, rho_v],
+        }
+    }
+}
+
+/// Shcherbakov's correction f_m(X) to cold-plasma Faraday conversion.
+fn faraday_conversion_shape(x: f64) -> f64 {
+    let f = 2.011 * (-x.powf(1.035) / 4.7).exp()
+        - (0.5 * x).cos() * (-x.powf(1.2) / 2.73).exp()
+        - 0.011 * (-x / 47.2).exp();
+    let tail = 0.011 * (-x / 47.2).exp()
+        - 2f64.powf(-1.0 / 3.0) / 3f64.powf(23.0 / 6.0) * PI * 1e4 * (x + 1e-16).powf(-8.0 / 3.0);
+    f + tail * 0.5 * (1.0 + (10.0 * (x / 120.0).ln()).tanh())
+}
+
+impl PolarizedEmission for ThermalSynchrotron {
+    fn polarized_coefficients(
+        &self,
+        fluid: &FluidState,
+        nu: f64,
+        pitch_angle: f64,
+    ) -> PolarizedCoefficients {
+        self.polarized(
+            fluid.density,
+            fluid.temperature,
+            fluid.magnetic_field.strength,
+            nu,
+            pitch_angle,
+        )
+    }
+}
+
+impl Emission for ThermalSynchrotron {
+    fn coefficients(&self, fluid: &FluidState, nu: f64, pitch_angle: f64) -> Coefficients {
+        let emissivity = self.emissivity(
+            fluid.density,
+            fluid.temperature,
+            fluid.magnetic_field.strength,
+            nu,
+            pitch_angle,
+        );
+        kirchhoff(emissivity, nu, fluid.temperature)
+    }
+}
+
+/// Synchrotron from a power-law electron distribution,
+/// n(gamma) ~ gamma^-p for gamma_min < gamma < gamma_max.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerLawSynchrotron {
+    /// Power-law index p.
+    pub index: f64,
+    /// Lower Lorentz factor cutoff.
+    pub gamma_min: f64,
+    /// Upper Lorentz factor cutoff.
+    pub gamma_max: f64,
+    /// Fraction of the fluid's electrons in the power law.
+    pub density_fraction: f64,
+}
+
+impl Default for PowerLawSynchrotron {
+    fn default() -> Self {
+        Self {
+            index: 3.0,
+            gamma_min: 100.0,
+            gamma_max: 1e6,
+            density_fraction: 1.0,
+        }
+    }
+}
+
+impl PowerLawSynchrotron {
+    /// All electrons in a power law of index `index` between the cutoffs.
+    pub fn new(index: f64, gamma_min: f64, gamma_max: f64) -> Self {
+        Self {
+            index,
+            gamma_min,
+            gamma_max,
+            density_fraction: 1.0,
+        }
+    }
+
+    /// Put only `fraction` of the electrons in the power law, as in hybrid
+    /// thermal and non-thermal models.
+    pub fn with_density_fraction(mut self, fraction: f64) -> Self {
+        self.density_fraction = fraction;
+        self
+    }
+
+    /// Emission and absorption for `density` electrons (m^-3) in a field of
+    /// `field_strength` Tesla, at `pitch_angle` to the field.
+    pub fn coefficients_at(
+        &self,
+        density: f64,
+        field_strength: f64,
+        nu: f64,
+        pitch_angle: f64,
+    ) -> Coefficients {
+        let p = self.index;
+        let n = density * self.density_fraction;
+        let nu_b = cyclotron_frequency(field_strength);
+        let sin = pitch_angle.sin().abs();
+        if n <= 0.0 || nu_b <= 0.0 || sin <= 0.0 {
+            return Coefficients::default();
+        }
+        let norm = self.gamma_min.powf(1.0 - p) - self.gamma_max.powf(1.0 - p);
+        let x = nu / (nu_b * sin);
+        let e2 = charge_squared();
+        let emissivity = n * e2 * nu_b / SI_C * 3f64.powf(p / 2.0) * (p - 1.0) * sin
+            / (2.0 * (p + 1.0) * norm)
+            * gamma_function((3.0 * p - 1.0) / 12.0)
+            * gamma_function((3.0 * p + 19.0) / 12.0)
+            * x.powf(-(p - 1.0) / 2.0);
+        let absorptivity =
+            n * e2 / (nu * SI_ELECTRON_MASS * SI_C) * 3f64.powf((p + 1.0) / 2.0) * (p - 1.0)
+                / (4.0 * norm)
+                * gamma_function((3.0 * p + 2.0) / 12.0)
+                * gamma_function((3.0 * p + 22.0) / 12.0)
+                * x.powf(-(p + 2.0) / 2.0);
+        Coefficients {
+            emissivity,
+            absorptivity,
+        }
+    }
+}
+
+impl Emission for PowerLawSynchrotron {
+    fn coefficients(&self, fluid: &FluidState, nu: f64, pitch_angle: f64) -> Coefficients {
+        self.coefficients_at(
+            fluid.density,
+            fluid.magnetic_field.strength,
+            nu,
+            pitch_angle,
+        )
+    }
+}
+
+/// Angle-averaged synchrotron from a relativistic Maxwell-Juettner
+/// distribution, with Kirchhoff absorption.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AveragedThermalSynchrotron;
+
+impl AveragedThermalSynchrotron {
+    /// Emissivity j_nu in W m^-3 Hz^-1 sr^-1.
+    pub fn emissivity(&self, density: f64, temperature: f64, field_strength: f64, nu: f64) -> f64 {
+        let theta = electron_temperature(temperature);
+        let nu_b = cyclotron_frequency(field_strength);
+        if theta < MIN_THETA || nu_b <= 0.0 || density <= 0.0 {
+            return 0.0;
+        }
+        let x = 2.0 * nu / (3.0 * nu_b * theta * theta);
+        let m = 4.0505 / x.powf(1.0 / 6.0)
+            * (1.0 + 0.40 / x.powf(0.25) + 0.5316 / x.sqrt())
+            * (-1.8899 * x.cbrt()).exp();
+        charge_squared() * density * nu * m / (3.0f64.sqrt() * SI_C) / bessel_k2(1.0 / theta)
+    }
+}
+
+impl Emission for AveragedThermalSynchrotron {
+    fn coefficients(&self, fluid: &FluidState, nu: f64, _pitch_angle: f64) -> Coefficients {
+        let emissivity = self.emissivity(
+            fluid.density,
+            fluid.temperature,
+            fluid.magnetic_field.strength,
+            nu,
+        );
+        kirchhoff(emissivity, nu, fluid.temperature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::SI_MU_0;
+
+    #[test]
+    fn test_bessel_k2() {
+        for (x, expected) in [
+            (0.1, 199.503_964_337),
+            (1.0, 1.624_838_898_6),
+            (2.0, 0.253_759_754_6),
+            (5.0, 0.005_308_943_712),
+        ] {
+            let k2 = bessel_k2(x);
+            assert!((k2 - expected).abs() < 1e-6 * expected, "K2({x}) = {k2}");
+        }
+    }
+
+    #[test]
+    fn test_thermal_synchrotron_power() {
+        // Integrated over frequency and solid angle, the emission of
+        // ultrarelativistic thermal electrons is 16 sigma_T c Theta^2 U_B
+        // per electron, with <gamma^2> = 12 Theta^2.
+        let (n, b) = (1e12, 1e-3);
+        let temperature = 1e11;
+        let theta = electron_temperature(temperature);
+        let sync = AveragedThermalSynchrotron;
+
+        let nu_b = cyclotron_frequency(b);
+        let (lo, hi, steps) = ((nu_b * 1e-2).ln(), (nu_b * theta * theta * 1e4).ln(), 4000);
+        let dl = (hi - lo) / steps as f64;
+        let power: f64 = (0..steps)
+            .map(|k| {
+                let nu = (lo + (k as f64 + 0.5) * dl).exp();
+                4.0 * std::f64::consts::PI * sync.emissivity(n, temperature, b, nu) * nu * dl
+            })
+            .sum();
+
+        let sigma_t = 6.652_458_732e-29;
+        let expected = n * 16.0 * sigma_t * SI_C * theta * theta * b * b / (2.0 * SI_MU_0);
+        assert!(
+            (power / expected - 1.0).abs() < 0.15,
+            "{power} vs {expected}"
+        );
+    }
+
+    #[test]
+    fn test_gamma_function() {
+        for (x, expected) in [
+            (0.5, PI.sqrt()),
+            (5.0, 24.0),
+            (1.0 / 3.0, 2.678_938_534_707_747),
+            (2.75, 1.608_359_421_985_546),
+        ] {
+            assert!((gamma_function(x) - expected).abs() < 1e-12 * expected);
+        }
+    }
+
+    /// Synchrotron function F(x) = x integral_x^inf K_5/3(t) dt, from
+    /// K_nu(t) = integral_0^inf exp(-t cosh u) cosh(nu u) du.
+    fn synchrotron_f(x: f64) -> f64 {
+        let top = (60.0 / x).max(1.0).acosh();
+        let steps = 400;
+        let du = top / steps as f64;
+        x * (0..steps)
+            .map(|k| {
+                let u = (k as f64 + 0.5) * du;
+                (5.0 * u / 3.0).cosh() * (-x * u.cosh()).exp() / u.cosh() * du
+            })
+            .sum::<f64>()
+    }
+
+    /// Power per unit frequency emitted by one ultrarelativistic electron,
+    /// P = sqrt(3) e^2 / c (2 pi nu_B) sin(theta) F(nu / nu_crit).
+    fn single_electron(nu: f64, gamma: f64, field_strength: f64, pitch_angle: f64) -> f64 {
+        let nu_b = cyclotron_frequency(field_strength);
+        let sin = pitch_angle.sin();
+        let nu_crit = 1.5 * gamma * gamma * nu_b * sin;
+        3f64.sqrt() * charge_squared() / SI_C * 2.0 * PI * nu_b * sin * synchrotron_f(nu / nu_crit)
+    }
+
+    /// integral f(gamma) d gamma on a log grid.
+    fn integrate_gamma(lo: f64, hi: f64, f: impl Fn(f64) -> f64) -> f64 {
+        let steps = 3000;
+        let dl = (hi / lo).ln() / steps as f64;
+        (0..steps)
+            .map(|k| {
+                let gamma = lo * ((k as f64 + 0.5) * dl).exp();
+                f(gamma) * gamma * dl
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_thermal_pitch_angle_emissivity() {
+        let (n, b, temperature) = (1e12, 1e-3, 5e10);
+        let theta_e = electron_temperature(temperature);
+        let pitch = PI / 3.0;
+        let nu_s = 2.0 / 9.0 * cyclotron_frequency(b) * theta_e * theta_e * pitch.sin();
+        for x in [10.0, 100.0, 1000.0] {
+            let nu = x * nu_s;
+            let direct = integrate_gamma(1.0, 200.0 * theta_e, |gamma| {
+                let density = n * gamma * gamma * (-gamma / theta_e).exp()
+                    / (theta_e * bessel_k2(1.0 / theta_e));
+                density * single_electron(nu, gamma, b, pitch) / (4.0 * PI)
+            });
+            let fit = ThermalSynchrotron.emissivity(n, temperature, b, nu, pitch);
+            assert!(
+                (fit / direct - 1.0).abs() < 0.1,
+                "X = {x}: {fit} vs {direct}"
+            );
+        }
+        assert_eq!(
+            ThermalSynchrotron.emissivity(n, temperature, b, nu_s, 0.0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_power_law_coefficients() {
+        let sync = PowerLawSynchrotron::new(3.0, 10.0, 1e5);
+        let (n, b, pitch) = (1e10, 1e-2, 1.0);
+        let p = sync.index;
+        let norm = n * (p - 1.0) / (sync.gamma_min.powf(1.0 - p) - sync.gamma_max.powf(1.0 - p));
+        let nu = 1e5 * cyclotron_frequency(b);
+
+        let direct_j = integrate_gamma(sync.gamma_min, sync.gamma_max, |gamma| {
+            norm * gamma.powf(-p) * single_electron(nu, gamma, b, pitch) / (4.0 * PI)
+        });
+        // alpha = -1 / (8 pi m_e nu^2) integral P gamma^2 d/dgamma (n / gamma^2)
+        let direct_alpha = integrate_gamma(sync.gamma_min, sync.gamma_max, |gamma| {
+            (p + 2.0) * norm * gamma.powf(-p - 1.0) * single_electron(nu, gamma, b, pitch)
+        }) / (8.0 * PI * SI_ELECTRON_MASS * nu * nu);
+
+        let c = sync.coefficients_at(n, b, nu, pitch);
+        assert!(
+            (c.emissivity / direct_j - 1.0).abs() < 0.02,
+            "{c:?} vs {direct_j}"
+        );
+        assert!(
+            (c.absorptivity / direct_alpha - 1.0).abs() < 0.02,
+            "{c:?} vs {direct_alpha}"
+        );
+
+        let c2 = sync.coefficients_at(n, b, 2.0 * nu, pitch);
+        assert!((c2.emissivity / c.emissivity - 2f64.powf(-(p - 1.0) / 2.0)).abs() < 1e-12);
+        assert!((c2.absorptivity / c.absorptivity - 2f64.powf(-(p + 4.0) / 2.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_thermal_polarized_coefficients() {
+        let (n, temperature, b, nu) = (1e13, 1e11, 1e-3, 230e9);
+        let sync = ThermalSynchrotron;
+        let c = sync.polarized(n, temperature, b, nu, 1.0);
+        let j = c.emissivity;
+        assert_eq!(j.i, sync.emissivity(n, temperature, b, nu, 1.0));
+        // Polarized across the field, fraction below one; V follows cos.
+        assert!(j.q < 0.0 && -j.q < j.i && j.u == 0.0);
+        assert!(j.v > 0.0 && j.v < j.i);
+        let flipped = sync.polarized(n, temperature, b, nu, PI - 1.0);
+        assert!((flipped.emissivity.v + j.v).abs() < 1e-9 * j.v);
+        assert!((flipped.rotativity[2] + c.rotativity[2]).abs() < 1e-9 * c.rotativity[2]);
+        let ratio = c.absorptivity.q / c.absorptivity.i;
+        assert!((ratio - j.q / j.i).abs() < 1e-12);
+
+        // Cold plasma: rho_V = 2 n e^2 nu_B cos / (m_e c nu^2).
+        let cold = sync.polarized(n, 1e6, b, nu, 1.0);
+        let expected = 2.0 * charge_squared() * n * cyclotron_frequency(b) * 1f64.cos()
+            / (SI_ELECTRON_MASS * SI_C * nu * nu);
+        assert!((cold.rotativity[2] / expected - 1.0).abs() < 1e-3);
+        assert_eq!(cold.emissivity, Stokes::default());
+        // Hot electrons rotate less, roughly as ln(Theta) / Theta^2.
+        assert!(c.rotativity[2] < 0.1 * expected);
+    }
+
+    #[test]
+    fn test_thermal_circular_fraction_matches_dexter() {
+        // At x = nu / nu_c = 10, Dexter's fits give I_V / I_I = 0.346875, so
+        // j_V / j_I = 4 cos / (3 Theta_e sin) * 0.346875.
+        let (n, temperature, b, pitch) = (1e13, 1e11, 1e-3, 1.0f64);
+        let theta = electron_temperature(temperature);
+        let nu_c = 1.5 * cyclotron_frequency(b) * pitch.sin() * theta * theta;
+        let j = ThermalSynchrotron
+            .polarized(n, temperature, b, 10.0 * nu_c, pitch)
+            .emissivity;
+        let expected = 4.0 * pitch.cos() / (3.0 * theta * pitch.sin()) * 0.346875;
+        assert!((j.v / j.i / expected - 1.0).abs() < 1e-5, "{}", j.v / j.i);
+    }
+}
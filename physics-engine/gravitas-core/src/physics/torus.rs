@@ -0,0 +1,501 @@
+//! Pressure-supported equilibrium tori in Kerr: Fishbone-Moncrief and
+//! constant-angular-momentum Polish doughnuts.
+//!
+//! A stationary, axisymmetric, barotropic perfect fluid on circular orbits
+//! satisfies
+//!
+//!   ln h = W_in - W(r, theta)
+//!
+//! with h the specific enthalpy and W an effective potential fixed by the
+//! angular momentum distribution. Fishbone & Moncrief hold u^t u_phi
+//! constant, Polish doughnuts (Abramowicz, Jaroszynski & Sikora) hold
+//! l = -u_phi / u_t constant, for which W = ln(-u_t). The fluid fills the
+//! region h > 1 around the pressure maximum (the centre), bounded inside
+//! by the inner edge r_in. Equatorial extrema of W are the centre and the
+//! cusp, where a torus that fills its Roche lobe starts to overflow.
+//!
+//! A polytrope p = K rho^Gamma gives rho and p from h. Density is
+//! normalized to 1 at the centre; [`MatterField`] rescales it to an
+//! electron density and takes the temperature from p / rho.
+//!
+//! # References
+//!
+//! - Fishbone, L. G. & Moncrief, V. (1976). "Relativistic fluid disks in
+//!   orbit around Kerr black holes"
+//! - Abramowicz, M., Jaroszynski, M. & Sikora, M. (1978). "Relativistic,
+//!   accreting disks"
+//! - Gammie, McKinney & Toth (2003). "HARM: A Numerical Scheme for General
+//!   Relativistic Magnetohydrodynamics"
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::matter::{equipartition_field, toroidal_field, MagneticField, MatterField};
+use crate::constants::{SI_C, SI_KB, SI_PROTON_MASS};
+use crate::metric::{Kerr, Metric};
+use crate::tetrad::circular_velocity;
+
+/// Log-spaced radii scanned for the equatorial extrema of the potential.
+const SCAN_SAMPLES: usize = 4000;
+
+/// Outermost radius of the scan, in M.
+const SCAN_OUTER: f64 = 1e4;
+
+/// Angular momentum distribution of the fluid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TorusRotation {
+    /// Constant u^t u_phi (Fishbone & Moncrief).
+    FishboneMoncrief,
+    /// Constant l = -u_phi / u_t (Polish doughnut).
+    ConstantAngularMomentum,
+}
+
+/// Equilibrium torus around a Kerr black hole.
+#[derive(Debug, Clone)]
+pub struct Torus {
+    bh: Kerr,
+    /// Unit-mass Boyer-Lindquist metric the potentials are evaluated in.
+    unit: Kerr,
+    rotation: TorusRotation,
+    /// Conserved angular momentum in units of M.
+    angular_momentum: f64,
+    /// Inner edge in the equatorial plane.
+    pub inner_radius: f64,
+    center_radius: f64,
+    cusp_radius: Option<f64>,
+    /// Potential at the inner edge.
+    potential_in: f64,
+    /// Polytropic constant normalizing rho to 1 at the centre.
+    polytropic_constant: f64,
+    /// Adiabatic index Gamma.
+    pub adiabatic_index: f64,
+    /// Electron density at the centre, m^-3.
+    pub density_scale: f64,
+    /// Ion over electron temperature.
+    pub temperature_ratio: f64,
+    /// Gas over magnetic pressure of the toroidal field.
+    pub plasma_beta: f64,
+}
+
+impl Torus {
+    /// Fishbone-Moncrief torus with inner edge `inner_radius` and pressure
+    /// maximum at `center_radius`.
+    pub fn fishbone_moncrief(bh: &Kerr, inner_radius: f64, center_radius: f64) -> Self {
+        let l = fishbone_moncrief_l(center_radius / bh.mass(), bh.spin());
+        Self::build(bh, TorusRotation::FishboneMoncrief, l, inner_radius)
+    }
+
+    /// Polish doughnut with constant angular momentum `angular_momentum`
+    /// (in units of M) and inner edge `inner_radius`.
+    ///
+    /// Closed tori need l between its marginally stable and marginally
+    /// bound values, and an inner edge between the cusp and the centre.
+    pub fn polish_doughnut(bh: &Kerr, angular_momentum: f64, inner_radius: f64) -> Self {
+        Self::build(
+            bh,
+            TorusRotation::ConstantAngularMomentum,
+            angular_momentum / bh.mass(),
+            inner_radius,
+        )
+    }
+
+    /// Polish doughnut whose centre is at `center_radius`, where its angular
+    /// momentum equals the Keplerian value.
+    pub fn polish_doughnut_centered(bh: &Kerr, center_radius: f64, inner_radius: f64) -> Self {
+        let l = keplerian_angular_momentum(center_radius / bh.mass(), bh.spin());
+        Self::polish_doughnut(bh, l * bh.mass(), inner_radius)
+    }
+
+    fn build(bh: &Kerr, rotation: TorusRotation, angular_momentum: f64, inner_radius: f64) -> Self {
+        let mut torus = Self {
+            bh: *bh,
+            unit: Kerr::new(1.0, bh.spin()),
+            rotation,
+            angular_momentum,
+            inner_radius,
+            center_radius: inner_radius,
+            cusp_radius: None,
+            potential_in: 0.0,
+            polytropic_constant: 1.0,
+            adiabatic_index: 4.0 / 3.0,
+            density_scale: 1e13,
+            temperature_ratio: 1.0,
+            plasma_beta: 10.0,
+        };
+        let m = bh.mass();
+        torus.potential_in = torus.potential(inner_radius / m, FRAC_PI_2);
+        let (cusp, center) = torus.equatorial_extrema();
+        torus.cusp_radius = cusp.map(|r| r * m);
+        torus.center_radius = center.map_or(inner_radius, |r| r * m);
+        torus.normalize();
+        torus
+    }
+
+    /// Set the adiabatic index Gamma of the polytrope.
+    pub fn with_adiabatic_index(mut self, gamma: f64) -> Self {
+        self.adiabatic_index = gamma;
+        self.normalize();
+        self
+    }
+
+    /// Set the electron density at the centre, m^-3.
+    pub fn with_density_scale(mut self, density: f64) -> Self {
+        self.density_scale = density;
+        self
+    }
+
+    /// Set the ion-to-electron temperature ratio.
+    pub fn with_temperature_ratio(mut self, ratio: f64) -> Self {
+        self.temperature_ratio = ratio;
+        self
+    }
+
+    /// Set the plasma beta of the toroidal field.
+    pub fn with_plasma_beta(mut self, beta: f64) -> Self {
+        self.plasma_beta = beta;
+        self
+    }
+
+    /// Angular momentum distribution.
+    pub fn rotation(&self) -> TorusRotation {
+        self.rotation
+    }
+
+    /// Conserved angular momentum, u^t u_phi or -u_phi / u_t.
+    pub fn angular_momentum(&self) -> f64 {
+        self.angular_momentum * self.bh.mass()
+    }
+
+    /// Radius of the pressure and density maximum.
+    pub fn center_radius(&self) -> f64 {
+        self.center_radius
+    }
+
+    /// Radius of the cusp, the saddle of the potential inside the centre,
+    /// if it lies outside the horizon.
+    pub fn cusp_radius(&self) -> Option<f64> {
+        self.cusp_radius
+    }
+
+    /// Specific enthalpy h at (r, theta); 1 outside the torus.
+    pub fn enthalpy(&self, r: f64, theta: f64) -> f64 {
+        let m = self.bh.mass();
+        if r < self
+            .cusp_radius
+            .unwrap_or(self.inner_radius)
+            .min(self.inner_radius)
+        {
+            return 1.0;
+        }
+        let ln_h = self.potential(r / m, theta) - self.potential_in;
+        if ln_h > 0.0 {
+            ln_h.exp()
+        } else {
+            1.0
+        }
+    }
+
+    /// Rest-mass density, 1 at the centre.
+    pub fn rest_mass_density(&self, r: f64, theta: f64) -> f64 {
+        let gamma = self.adiabatic_index;
+        let h = self.enthalpy(r, theta);
+        ((h - 1.0) * (gamma - 1.0) / (gamma * self.polytropic_constant)).powf(1.0 / (gamma - 1.0))
+    }
+
+    /// Pressure K rho^Gamma, in the same units as the density.
+    pub fn pressure(&self, r: f64, theta: f64) -> f64 {
+        self.polytropic_constant * self.rest_mass_density(r, theta).powf(self.adiabatic_index)
+    }
+
+    /// Angular velocity u^phi / u^t of the fluid at (r, theta).
+    pub fn angular_velocity(&self, r: f64, theta: f64) -> Option<f64> {
+        let m = self.bh.mass();
+        let (r, l) = (r / m, self.angular_momentum);
+        let g = self.unit.covariant(r, theta);
+        let (g_tt, g_tp, g_pp) = (g.get(0, 0), g.get(0, 3), g.get(3, 3));
+        let omega = match self.rotation {
+            TorusRotation::ConstantAngularMomentum => -(g_tp + l * g_tt) / (g_pp + l * g_tp),
+            TorusRotation::FishboneMoncrief => {
+                let uphi = fishbone_moncrief_uphi(r, theta, self.bh.spin(), l)?;
+                // g_tt (u^t)^2 + 2 g_tphi u^t u^phi + g_phiphi (u^phi)^2 = -1
+                let b = 2.0 * g_tp * uphi;
+                let c = g_pp * uphi * uphi + 1.0;
+                let ut = if g_tt.abs() < 1e-12 {
+                    -c / b
+                } else {
+                    (-b - (b * b - 4.0 * g_tt * c).max(0.0).sqrt()) / (2.0 * g_tt)
+                };
+                uphi / ut
+            }
+        };
+        omega.is_finite().then_some(omega / m)
+    }
+
+    /// Isodensity surface at `level` times the central density, as (x, y, z)
+    /// vertices with y along the spin axis, in the layout of
+    /// [`ergosphere_mesh`](crate::spacetime::frame_drag::ergosphere_mesh).
+    ///
+    /// The cross-section is sampled at `n_polar` angles around the centre
+    /// (a closed loop) and swept through `n_azimuthal` azimuths.
+    pub fn isodensity_mesh(&self, level: f64, n_polar: usize, n_azimuthal: usize) -> Vec<f32> {
+        let inside = |cyl: f64, z: f64| {
+            cyl > 0.0 && self.rest_mass_density(cyl.hypot(z), cyl.atan2(z)) >= level
+        };
+        let step = 0.02 * self.center_radius;
+        let mut vertices = Vec::with_capacity(n_polar * n_azimuthal * 3);
+
+        for i in 0..n_polar {
+            let psi = 2.0 * PI * i as f64 / n_polar as f64;
+            let (dz, dcyl) = psi.sin_cos();
+            let point = |s: f64| (self.center_radius + s * dcyl, s * dz);
+
+            // March out of the surface, then bisect the crossing.
+            let (mut lo, mut hi) = (0.0, step);
+            while hi < 10.0 * self.center_radius && {
+                let (cyl, z) = point(hi);
+                inside(cyl, z)
+            } {
+                lo = hi;
+                hi += step;
+            }
+            for _ in 0..40 {
+                let mid = 0.5 * (lo + hi);
+                let (cyl, z) = point(mid);
+                if inside(cyl, z) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            let (cyl, z) = point(0.5 * (lo + hi));
+
+            for j in 0..n_azimuthal {
+                let phi = 2.0 * PI * j as f64 / n_azimuthal as f64;
+                vertices.push((cyl * phi.cos()) as f32);
+                vertices.push(z as f32);
+                vertices.push((cyl * phi.sin()) as f32);
+            }
+        }
+
+        vertices
+    }
+
+    /// Effective potential -W at unit-mass radius r, so that
+    /// ln h = potential - potential_in. NaN where no circular orbit with
+    /// this angular momentum exists.
+    fn potential(&self, r: f64, theta: f64) -> f64 {
+        let l = self.angular_momentum;
+        match self.rotation {
+            TorusRotation::ConstantAngularMomentum => {
+                // u_t^2 (g^tt - 2 l g^tphi + l^2 g^phiphi) = -1
+                let g = self.unit.contravariant(r, theta);
+                let x = -(g.get(0, 0) - 2.0 * l * g.get(0, 3) + l * l * g.get(3, 3));
+                if x > 0.0 {
+                    0.5 * x.ln()
+                } else {
+                    f64::NAN
+                }
+            }
+            TorusRotation::FishboneMoncrief => {
+                let a = self.bh.spin();
+                let (sin, cos) = theta.sin_cos();
+                let delta = r * r - 2.0 * r + a * a;
+                let sigma = r * r + a * a * cos * cos;
+                let big_a = (r * r + a * a).powi(2) - delta * a * a * sin * sin;
+                if delta <= 0.0 || sin <= 0.0 {
+                    return f64::NAN;
+                }
+                let s = (1.0 + 4.0 * l * l * sigma * sigma * delta / (big_a * big_a * sin * sin))
+                    .sqrt();
+                0.5 * ((1.0 + s) / (sigma * delta / big_a)).ln() - 0.5 * s - 2.0 * a * r * l / big_a
+            }
+        }
+    }
+
+    /// Unit-mass radii of the equatorial minimum (cusp) and maximum
+    /// (centre) of the potential outside the horizon.
+    fn equatorial_extrema(&self) -> (Option<f64>, Option<f64>) {
+        let inner = self.unit.event_horizon() * 1.001;
+        let radius =
+            |k: usize| inner * (SCAN_OUTER / inner).powf(k as f64 / (SCAN_SAMPLES - 1) as f64);
+        let f = |r: f64| self.potential(r, FRAC_PI_2);
+
+        let mut cusp = None;
+        let mut center = None;
+        for k in 1..SCAN_SAMPLES - 1 {
+            let (a, b, c) = (f(radius(k - 1)), f(radius(k)), f(radius(k + 1)));
+            if center.is_none() && b > a && b >= c {
+                center = Some(golden_section(&f, radius(k - 1), radius(k + 1), true));
+            }
+            if cusp.is_none() && center.is_none() && b < a && b <= c {
+                cusp = Some(golden_section(&f, radius(k - 1), radius(k + 1), false));
+            }
+        }
+        (cusp, center)
+    }
+
+    /// Choose K so that rho = 1 at the centre.
+    fn normalize(&mut self) {
+        let gamma = self.adiabatic_index;
+        self.polytropic_constant = 1.0;
+        let h = self.enthalpy(self.center_radius, FRAC_PI_2);
+        if h > 1.0 {
+            self.polytropic_constant = (h - 1.0) * (gamma - 1.0) / gamma;
+        }
+    }
+}
+
+impl MatterField for Torus {
+    fn density(&self, x: &[f64; 4]) -> f64 {
+        self.density_scale * self.rest_mass_density(x[1], x[2])
+    }
+
+    /// Ion temperature from the ideal gas law, k T_i = (p / rho) m_p c^2,
+    /// divided by the temperature ratio.
+    fn temperature(&self, x: &[f64; 4]) -> f64 {
+        let gamma = self.adiabatic_index;
+        let h = self.enthalpy(x[1], x[2]);
+        let p_over_rho = (h - 1.0) * (gamma - 1.0) / gamma;
+        p_over_rho * SI_PROTON_MASS * SI_C * SI_C / SI_KB / self.temperature_ratio
+    }
+
+    fn velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+        if self.enthalpy(x[1], x[2]) <= 1.0 {
+            return None;
+        }
+        let omega = self.angular_velocity(x[1], x[2])?;
+        circular_velocity(&self.bh, x[1], x[2], omega)
+    }
+
+    fn magnetic_field(&self, x: &[f64; 4]) -> MagneticField {
+        match self.velocity(x) {
+            Some(u) => {
+                let strength =
+                    equipartition_field(self.density(x), self.temperature(x), self.plasma_beta);
+                toroidal_field(&self.bh, x, &u, strength)
+            }
+            None => MagneticField::default(),
+        }
+    }
+}
+
+/// Keplerian l = -u_phi / u_t of the prograde equatorial circular orbit at
+/// unit-mass radius r, for spin a.
+pub fn keplerian_angular_momentum(r: f64, a: f64) -> f64 {
+    let sr = r.sqrt();
+    (r * r - 2.0 * a * sr + a * a) / (r * sr - 2.0 * sr + a)
+}
+
+/// Fishbone-Moncrief u^t u_phi that puts the pressure maximum at unit-mass
+/// radius r, for spin a.
+fn fishbone_moncrief_l(r: f64, a: f64) -> f64 {
+    let sr = r.sqrt();
+    let a2 = a * a;
+    let q = a2 - 2.0 * a * sr + r * r;
+    let s1 = (2.0 * a * sr + (r - 3.0) * r).sqrt();
+    let s2 = (1.0 + 2.0 * a / r.powf(1.5) - 3.0 / r).sqrt();
+    q * (-2.0 * a * r * q / s1 + (a + (r - 2.0) * sr) * (r.powi(3) + a2 * (2.0 + r)) / s2)
+        / (r.powi(3) * s1 * (a2 + (r - 2.0) * r))
+}
+
+/// Boyer-Lindquist u^phi of the Fishbone-Moncrief flow at unit-mass radius r.
+fn fishbone_moncrief_uphi(r: f64, theta: f64, a: f64, l: f64) -> Option<f64> {
+    let (sin, cos) = theta.sin_cos();
+    let delta = r * r - 2.0 * r + a * a;
+    let sigma = r * r + a * a * cos * cos;
+    let big_a = (r * r + a * a).powi(2) - delta * a * a * sin * sin;
+    if delta <= 0.0 || sin <= 0.0 {
+        return None;
+    }
+    let exp_m2chi = sigma * sigma * delta / (big_a * big_a * sin * sin);
+    let u1 = ((-1.0 + (1.0 + 4.0 * l * l * exp_m2chi).sqrt()) / 2.0).sqrt();
+    Some(
+        2.0 * a * r * (1.0 + u1 * u1).sqrt() / (big_a * sigma * delta).sqrt()
+            + (sigma / big_a).sqrt() * u1 / sin,
+    )
+}
+
+/// Extremum of `f` in [lo, hi] by golden-section search.
+fn golden_section(f: &impl Fn(f64) -> f64, mut lo: f64, mut hi: f64, maximize: bool) -> f64 {
+    let ratio = 0.5 * (5.0f64.sqrt() - 1.0);
+    let sign = if maximize { -1.0 } else { 1.0 };
+    let g = |r: f64| sign * f(r);
+    for _ in 0..100 {
+        let a = hi - ratio * (hi - lo);
+        let b = lo + ratio * (hi - lo);
+        if g(a) < g(b) {
+            hi = b;
+        } else {
+            lo = a;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Orbit;
+    use crate::tetrad::keplerian_velocity;
+
+    #[test]
+    fn test_fishbone_moncrief_structure() {
+        // The standard GRMHD initial torus.
+        let bh = Kerr::new(1.0, 0.9375);
+        let torus = Torus::fishbone_moncrief(&bh, 6.0, 12.0);
+        assert!((torus.center_radius() - 12.0).abs() < 1e-4);
+        assert!((torus.rest_mass_density(12.0, FRAC_PI_2) - 1.0).abs() < 1e-6);
+        assert!(torus.rest_mass_density(11.0, FRAC_PI_2) < 1.0);
+        assert_eq!(torus.rest_mass_density(5.9, FRAC_PI_2), 0.0);
+        assert!(torus.rest_mass_density(6.1, FRAC_PI_2) > 0.0);
+        assert!(torus.pressure(12.0, 1.4) < torus.pressure(12.0, FRAC_PI_2));
+
+        // The pressure maximum is a circular geodesic.
+        let u = torus.velocity(&[0.0, 12.0, FRAC_PI_2, 0.0]).unwrap();
+        let kepler = keplerian_velocity(&bh, 12.0, Orbit::Prograde).unwrap();
+        for i in 0..4 {
+            assert!((u[i] - kepler[i]).abs() < 1e-6, "{u:?} vs {kepler:?}");
+        }
+    }
+
+    #[test]
+    fn test_polish_doughnut_cusp_and_center() {
+        let bh = Kerr::new(2.0, 0.0);
+        let l = 3.8;
+        let torus = Torus::polish_doughnut(&bh, l * 2.0, 10.0);
+        let cusp = torus.cusp_radius().unwrap() / 2.0;
+        let center = torus.center_radius() / 2.0;
+        assert!(cusp < 6.0 && 6.0 < center);
+        // In Schwarzschild, l_K = r^{3/2} / (r - 2).
+        for r in [cusp, center] {
+            assert!((r.powf(1.5) / (r - 2.0) - l).abs() < 1e-6, "r = {r}");
+        }
+        assert!((keplerian_angular_momentum(center, 0.0) - l).abs() < 1e-6);
+
+        for bh in [Kerr::new(1.0, 0.7), Kerr::kerr_schild(1.0, 0.7)] {
+            let torus = Torus::polish_doughnut_centered(&bh, 10.0, 6.0);
+            assert!((torus.center_radius() - 10.0).abs() < 1e-4);
+            for x in [[0.0, 10.0, FRAC_PI_2, 0.0], [0.0, 8.0, 1.45, 1.0]] {
+                let u = torus.velocity(&x).unwrap();
+                let g = bh.covariant(x[1], x[2]);
+                let norm: f64 = (0..4)
+                    .flat_map(|i| (0..4).map(move |j| (i, j)))
+                    .map(|(i, j)| g.get(i, j) * u[i] * u[j])
+                    .sum();
+                assert!((norm + 1.0).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_isodensity_mesh() {
+        let bh = Kerr::new(1.0, 0.5);
+        let torus = Torus::fishbone_moncrief(&bh, 6.0, 12.0);
+        let mesh = torus.isodensity_mesh(0.2, 24, 8);
+        assert_eq!(mesh.len(), 24 * 8 * 3);
+        for v in mesh.chunks(3) {
+            let (cyl, z) = ((v[0] as f64).hypot(v[2] as f64), v[1] as f64);
+            let rho = torus.rest_mass_density(cyl.hypot(z), cyl.atan2(z));
+            assert!((rho - 0.2).abs() < 1e-3, "{rho}");
+        }
+    }
+}
@@ -0,0 +1,394 @@
+//! Exact lensing lookup tables for the GPU shaders.
+//!
+//! The shaders ray-march approximately. For pixels that only see the
+//! distant sky and the disk, the full geodesic answer can instead be looked
+//! up: a distant observer's ray is fixed by its impact parameter b and
+//! position angle psi on the screen, the hole's spin a and the observer's
+//! inclination i. For every node of a (b, psi, a, i) grid the table stores
+//!
+//! 0. the deflection angle between the undeflected line of sight and the
+//!    asymptotic direction of the ray,
+//! 1. the coordinate time delay relative to the same straight line in flat
+//!    space (Shapiro delay plus geometric delay),
+//! 2. the radius of the first crossing of the thin disk,
+//! 3. the redshift g = nu_obs / nu_emit of the disk gas at that crossing.
+//!
+//! Entries that do not exist (rays captured by the hole, rays missing the
+//! disk) hold [`LUT_MISSING`]. Screen coordinates follow the Bardeen
+//! convention alpha = b cos psi, beta = b sin psi, with alpha along +e_phi
+//! and beta towards the spin axis. Only 0 <= a and 0 < i <= pi/2 need to be
+//! tabulated: a ray at inclination pi - i is the mirror image of the ray at
+//! (i, -psi), and negative spin mirrors psi to pi - psi.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use glam::DVec3;
+
+use crate::geodesic::{integrate, GeodesicState, IntegrationOptions, TerminationReason};
+use crate::metric::{contract, Kerr};
+use crate::tetrad::{static_velocity, Tetrad};
+
+use super::scene::ThinDisk;
+use super::sky::asymptotic_direction;
+use super::{trace_ray, RayHit};
+
+/// Value stored for quantities the ray does not have.
+pub const LUT_MISSING: f32 = -1.0;
+
+/// Floats in the header written before the table by [`DeflectionLut::to_buffer`].
+pub const LUT_HEADER_LEN: usize = 16;
+
+/// Format tag in the first header slot.
+pub const LUT_VERSION: f32 = 1.0;
+
+/// Quantities stored per grid node.
+pub const LUT_CHANNELS: usize = 4;
+
+/// Grid axes and tracing settings for a [`DeflectionLut`].
+#[derive(Debug, Clone)]
+pub struct DeflectionLutSpec {
+    /// Impact parameter range in units of M, sampled linearly.
+    pub impact_range: (f64, f64),
+    /// Number of impact parameter samples.
+    pub impact_samples: usize,
+    /// Number of screen position angles, covering [0, 2 pi) periodically.
+    pub angle_samples: usize,
+    /// Largest dimensionless spin; spins are sampled linearly from 0.
+    pub max_spin: f64,
+    /// Number of spin samples.
+    pub spin_samples: usize,
+    /// Inclination range in radians, sampled linearly.
+    pub inclination_range: (f64, f64),
+    /// Number of inclination samples.
+    pub inclination_samples: usize,
+    /// Distance of the static observer, in units of M.
+    pub observer_radius: f64,
+    /// Outer edge of the Novikov-Thorne disk; the inner edge is the ISCO.
+    pub disk_outer_radius: f64,
+    /// Integrator settings; `escape_radius` must exceed `observer_radius`.
+    pub integration: IntegrationOptions,
+}
+
+impl Default for DeflectionLutSpec {
+    fn default() -> Self {
+        Self {
+            impact_range: (1.0, 40.0),
+            impact_samples: 64,
+            angle_samples: 16,
+            max_spin: 0.998,
+            spin_samples: 8,
+            inclination_range: (1f64.to_radians(), FRAC_PI_2),
+            inclination_samples: 8,
+            observer_radius: 1000.0,
+            disk_outer_radius: 50.0,
+            integration: IntegrationOptions {
+                escape_radius: 2000.0,
+                ..IntegrationOptions::default()
+            },
+        }
+    }
+}
+
+impl DeflectionLutSpec {
+    /// Impact parameter of sample `k`.
+    pub fn impact(&self, k: usize) -> f64 {
+        linear(self.impact_range, k, self.impact_samples)
+    }
+
+    /// Screen position angle of sample `k`.
+    pub fn angle(&self, k: usize) -> f64 {
+        TAU * k as f64 / self.angle_samples.max(1) as f64
+    }
+
+    /// Spin of sample `k`.
+    pub fn spin(&self, k: usize) -> f64 {
+        linear((0.0, self.max_spin), k, self.spin_samples)
+    }
+
+    /// Inclination of sample `k`.
+    pub fn inclination(&self, k: usize) -> f64 {
+        linear(self.inclination_range, k, self.inclination_samples)
+    }
+
+    /// Number of grid nodes.
+    pub fn len(&self) -> usize {
+        self.impact_samples * self.angle_samples * self.spin_samples * self.inclination_samples
+    }
+
+    /// Whether the grid has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Exact values for one ray. Missing quantities are NaN.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeflectionSample {
+    /// Angle between the undeflected line of sight and the ray's asymptotic
+    /// direction, in radians.
+    pub deflection: f64,
+    /// Coordinate time delay against the flat-space straight line, in M.
+    pub time_delay: f64,
+    /// Radius of the first disk crossing.
+    pub disk_radius: f64,
+    /// Redshift of the disk gas at the crossing.
+    pub redshift: f64,
+}
+
+impl DeflectionSample {
+    /// The four LUT channels, with NaN replaced by [`LUT_MISSING`].
+    pub fn channels(&self) -> [f32; LUT_CHANNELS] {
+        [
+            self.deflection,
+            self.time_delay,
+            self.disk_radius,
+            self.redshift,
+        ]
+        .map(|v| if v.is_finite() { v as f32 } else { LUT_MISSING })
+    }
+}
+
+/// Trace the ray arriving at screen position (b, psi) of a static observer
+/// at `spec.observer_radius` and inclination `inclination` around `bh`
+/// (Boyer-Lindquist), and measure its deflection, delay and disk crossing.
+pub fn trace_deflection(
+    bh: &Kerr,
+    disk: Option<&ThinDisk>,
+    inclination: f64,
+    impact: f64,
+    angle: f64,
+    spec: &DeflectionLutSpec,
+) -> DeflectionSample {
+    let mut sample = DeflectionSample {
+        deflection: f64::NAN,
+        time_delay: f64::NAN,
+        disk_radius: f64::NAN,
+        redshift: f64::NAN,
+    };
+    let distance = spec.observer_radius;
+    let Some(u_obs) = static_velocity(bh, distance, inclination) else {
+        return sample;
+    };
+    let (alpha, beta) = (impact * angle.cos(), impact * angle.sin());
+
+    // Viewing direction in the frame (e_r, e_theta, e_phi): towards the
+    // screen point, with beta pointing to decreasing theta.
+    let n = DVec3::new(-distance, -beta, alpha).normalize();
+    let tetrad = Tetrad::adapted(bh, distance, inclination, u_obs);
+    let ray = GeodesicState {
+        x: [0.0, distance, inclination, 0.0],
+        p: tetrad.covector_from_frame(&[-1.0, n.x, n.y, n.z]),
+    };
+
+    // Same direction as a flat-space Cartesian line from the observer.
+    let (st, ct) = inclination.sin_cos();
+    let r_hat = DVec3::new(st, 0.0, ct);
+    let theta_hat = DVec3::new(ct, 0.0, -st);
+    let phi_hat = DVec3::Y;
+    let line = n.x * r_hat + n.y * theta_hat + n.z * phi_hat;
+
+    let sky = integrate(&ray, bh, &spec.integration);
+    if sky.termination == TerminationReason::Escape {
+        let (theta, phi) = asymptotic_direction(&sky.final_state, bh);
+        let (s, c) = theta.sin_cos();
+        let dir = DVec3::new(s * phi.cos(), s * phi.sin(), c);
+        sample.deflection = line.dot(dir).clamp(-1.0, 1.0).acos();
+
+        // Flat path from the observer to the final radius along the line.
+        let b_flat = (distance * r_hat).cross(line).length();
+        let r_end = sky.final_state.x[1];
+        let flat = (distance * distance - b_flat * b_flat).sqrt()
+            + (r_end * r_end - b_flat * b_flat).sqrt();
+        sample.time_delay = (sky.final_state.x[0] - ray.x[0]).abs() - flat;
+    }
+
+    if let Some(disk) = disk {
+        let traced = trace_ray(&ray, bh, &spec.integration, Some(disk));
+        if let RayHit::Disk { radius, state } = traced.hit {
+            sample.disk_radius = radius;
+            if let Some(u_emit) = disk.emitter_velocity(bh, radius) {
+                sample.redshift = contract(&ray.p, &u_obs) / contract(&state.p, &u_emit);
+            }
+        }
+    }
+    sample
+}
+
+/// Tabulated [`DeflectionSample`]s over a [`DeflectionLutSpec`] grid.
+#[derive(Debug, Clone)]
+pub struct DeflectionLut {
+    /// The grid.
+    pub spec: DeflectionLutSpec,
+    /// Channels per node, impact parameter varying fastest, then position
+    /// angle, spin and inclination.
+    pub data: Vec<[f32; LUT_CHANNELS]>,
+}
+
+impl DeflectionLut {
+    /// Trace every node of `spec`.
+    pub fn generate(spec: &DeflectionLutSpec) -> Self {
+        let mut data = Vec::with_capacity(spec.len());
+        for ii in 0..spec.inclination_samples {
+            let inclination = spec.inclination(ii);
+            for is in 0..spec.spin_samples {
+                let bh = Kerr::new(1.0, spec.spin(is));
+                let disk = ThinDisk::novikov_thorne(&bh, spec.disk_outer_radius);
+                for ia in 0..spec.angle_samples {
+                    let angle = spec.angle(ia);
+                    for ib in 0..spec.impact_samples {
+                        let sample = trace_deflection(
+                            &bh,
+                            Some(&disk),
+                            inclination,
+                            spec.impact(ib),
+                            angle,
+                            spec,
+                        );
+                        data.push(sample.channels());
+                    }
+                }
+            }
+        }
+        Self {
+            spec: spec.clone(),
+            data,
+        }
+    }
+
+    /// Channels at node (impact, angle, spin, inclination).
+    pub fn get(
+        &self,
+        impact: usize,
+        angle: usize,
+        spin: usize,
+        inclination: usize,
+    ) -> [f32; LUT_CHANNELS] {
+        let s = &self.spec;
+        let index = ((inclination * s.spin_samples + spin) * s.angle_samples + angle)
+            * s.impact_samples
+            + impact;
+        self.data[index]
+    }
+
+    /// Header followed by the flattened table, ready to copy into the
+    /// shared-memory LUT block.
+    ///
+    /// Header: version, impact samples, angle samples, spin samples,
+    /// inclination samples, b_min, b_max, max spin, i_min, i_max, channels,
+    /// observer radius, disk outer radius, then padding.
+    pub fn to_buffer(&self) -> Vec<f32> {
+        let s = &self.spec;
+        let mut buffer = vec![0.0; LUT_HEADER_LEN];
+        let header = [
+            LUT_VERSION,
+            s.impact_samples as f32,
+            s.angle_samples as f32,
+            s.spin_samples as f32,
+            s.inclination_samples as f32,
+            s.impact_range.0 as f32,
+            s.impact_range.1 as f32,
+            s.max_spin as f32,
+            s.inclination_range.0 as f32,
+            s.inclination_range.1 as f32,
+            LUT_CHANNELS as f32,
+            s.observer_radius as f32,
+            s.disk_outer_radius as f32,
+        ];
+        buffer[..header.len()].copy_from_slice(&header);
+        buffer.extend(self.data.iter().flatten());
+        buffer
+    }
+}
+
+/// Sample `k` of `n` spaced linearly over `range`, inclusive.
+fn linear(range: (f64, f64), k: usize, n: usize) -> f64 {
+    if n <= 1 {
+        return range.0;
+    }
+    range.0 + (range.1 - range.0) * k as f64 / (n - 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_weak_field_deflection() {
+        // Schwarzschild weak-field series to fourth order in M/b.
+        let bh = Kerr::new(1.0, 0.0);
+        let spec = DeflectionLutSpec::default();
+        for b in [20.0, 40.0] {
+            let sample = trace_deflection(&bh, None, 1.0, b, 0.3, &spec);
+            let u: f64 = 1.0 / b;
+            let expected = 4.0 * u
+                + 15.0 * PI / 4.0 * u.powi(2)
+                + 128.0 / 3.0 * u.powi(3)
+                + 3465.0 * PI / 64.0 * u.powi(4);
+            assert!(
+                (sample.deflection - expected).abs() / expected < 2e-3,
+                "b = {b}: {} vs {expected}",
+                sample.deflection
+            );
+            assert!(sample.time_delay > 0.0);
+        }
+        // Inside the critical impact parameter sqrt(27) the ray is captured.
+        let captured = trace_deflection(&bh, None, 1.0, 4.5, 0.0, &spec);
+        assert!(captured.deflection.is_nan());
+        assert_eq!(captured.channels()[0], LUT_MISSING);
+    }
+
+    #[test]
+    fn test_face_on_disk_redshift() {
+        // Nearly face-on, the gas moves across the line of sight:
+        // g = sqrt(1 - 3M/r) for an observer at infinity.
+        let bh = Kerr::new(1.0, 0.0);
+        let disk = ThinDisk::novikov_thorne(&bh, 50.0);
+        let spec = DeflectionLutSpec::default();
+        let sample = trace_deflection(&bh, Some(&disk), 1e-3, 12.0, 0.0, &spec);
+        let r = sample.disk_radius;
+        assert!(r > 6.0 && r < 12.0, "r = {r}");
+        let observer = (1.0 - 2.0 / spec.observer_radius).sqrt();
+        let expected = (1.0 - 3.0 / r).sqrt() / observer;
+        assert!((sample.redshift - expected).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_lut_layout() {
+        let spec = DeflectionLutSpec {
+            impact_range: (1.0, 30.0),
+            impact_samples: 3,
+            angle_samples: 2,
+            spin_samples: 2,
+            inclination_samples: 2,
+            ..DeflectionLutSpec::default()
+        };
+        let lut = DeflectionLut::generate(&spec);
+        assert_eq!(lut.data.len(), spec.len());
+
+        let buffer = lut.to_buffer();
+        assert_eq!(buffer.len(), LUT_HEADER_LEN + LUT_CHANNELS * spec.len());
+        assert_eq!(buffer[0], LUT_VERSION);
+        assert_eq!(buffer[1..5], [3.0, 2.0, 2.0, 2.0]);
+
+        // b = 1 is always captured; b = 30 always escapes.
+        for (ia, is, ii) in [(0, 0, 0), (1, 1, 1), (1, 0, 1)] {
+            assert_eq!(lut.get(0, ia, is, ii)[0], LUT_MISSING);
+            assert!(lut.get(2, ia, is, ii)[0] > 0.0);
+        }
+        let node = trace_deflection(
+            &Kerr::new(1.0, spec.spin(1)),
+            Some(&ThinDisk::novikov_thorne(
+                &Kerr::new(1.0, spec.spin(1)),
+                spec.disk_outer_radius,
+            )),
+            spec.inclination(1),
+            spec.impact(1),
+            spec.angle(1),
+            &spec,
+        );
+        assert_eq!(lut.get(1, 1, 1, 1), node.channels());
+        let offset = LUT_HEADER_LEN + LUT_CHANNELS * (spec.len() - 1 - 1);
+        assert_eq!(buffer[offset..offset + 4], node.channels());
+    }
+}
@@ -0,0 +1,76 @@
+//! Linear floating-point image buffer.
+
+/// A linear-light RGB image stored row-major from the top-left pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+    /// Linear RGB values, `width * height` entries in row-major order.
+    pub pixels: Vec<[f32; 3]>,
+}
+
+impl Image {
+    /// Black image of the given size.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 3]; (width * height) as usize],
+        }
+    }
+
+    /// Pixel (i, j), column i and row j.
+    #[inline]
+    pub fn get(&self, i: u32, j: u32) -> [f32; 3] {
+        self.pixels[self.index(i, j)]
+    }
+
+    /// Set pixel (i, j).
+    #[inline]
+    pub fn set(&mut self, i: u32, j: u32, rgb: [f32; 3]) {
+        let idx = self.index(i, j);
+        self.pixels[idx] = rgb;
+    }
+
+    /// Fill the `size x size` block with top-left corner (i, j), clipped
+    /// to the image.
+    pub fn fill_block(&mut self, i: u32, j: u32, size: u32, rgb: [f32; 3]) {
+        for y in j..(j + size).min(self.height) {
+            for x in i..(i + size).min(self.width) {
+                self.set(x, y, rgb);
+            }
+        }
+    }
+
+    /// Relative luminance of pixel (i, j) (Rec. 709 weights).
+    #[inline]
+    pub fn luminance(&self, i: u32, j: u32) -> f32 {
+        luminance(self.get(i, j))
+    }
+
+    /// Single-channel luminance image in row-major order.
+    pub fn intensity(&self) -> Vec<f32> {
+        self.pixels.iter().map(|&rgb| luminance(rgb)).collect()
+    }
+
+    /// Largest luminance in the image.
+    pub fn max_luminance(&self) -> f32 {
+        self.pixels
+            .iter()
+            .map(|&rgb| luminance(rgb))
+            .fold(0.0, f32::max)
+    }
+
+    #[inline]
+    fn index(&self, i: u32, j: u32) -> usize {
+        (j * self.width + i) as usize
+    }
+}
+
+/// Rec. 709 relative luminance of a linear RGB triple.
+#[inline]
+pub fn luminance(rgb: [f32; 3]) -> f32 {
+    0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2]
+}
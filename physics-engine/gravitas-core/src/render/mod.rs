@@ -0,0 +1,716 @@
+//! CPU reference ray tracer.
+//!
+//! Renders what a [`Camera`] sees: the black hole shadow, a thin accretion
+//! disk shaded with the full general-relativistic g-factor, and a lensed
+//! background sky. The output is a linear-light floating-point [`Image`],
+//! intended as ground truth for the WGSL shaders and for headless renders.
+//!
+//! Each pixel's backward-traced ray is integrated until it falls into the
+//! hole, escapes to `escape_radius`, or crosses the equatorial plane on the
+//! disk. Disk crossings are located by interpolating cos(theta) across the
+//! step in which it changes sign; steps are capped at a fraction of r so the
+//! interpolation stays accurate far from the hole.
+//!
+//! The photon frequency ratio between camera and disk gas is
+//! g = (p.u_camera) / (p.u_emitter), evaluated with the camera's own
+//! 4-velocity, so moving cameras see the correct Doppler shifts.
+//!
+//! [`output`] writes images as PFM, PNG or FITS files. For previews,
+//! [`Renderer::render_progressive`] renders coarse-to-fine in tiles handed
+//! out by a [`TileScheduler`]. [`Renderer::render_adaptive`] supersamples
+//! only the pixels that straddle edges such as the photon rings.
+//!
+//! A [`CelestialSphere`] background adds a sky texture and point stars;
+//! stars are splatted with their lensing magnification by
+//! [`Renderer::add_stars`], which the full-image renders call.
+//!
+//! [`trace_transfer`] integrates general-relativistic radiative transfer
+//! through optically thin or thick flows along the same rays, and
+//! [`render_intensity`] images them at one frequency. [`render_stokes`]
+//! does the same with polarization, giving Stokes I, Q, U, V and EVPA maps.
+//! [`render_sequence`] images variable sources such as orbiting hotspots
+//! over time, with light-travel delays, for light curves and centroid
+//! tracks.
+//!
+//! [`DiskImage`] traces a distant observer's view of the thin disk once, so
+//! that spectra such as relativistic [`LineProfile`]s and multi-colour
+//! [`disk_spectrum`]s can be summed over it.
+//! A [`Lamppost`] corona's [`Illumination`] of the disk turns the same image
+//! into a reverberation [`TransferFunction`] in energy and delay.
+//!
+//! [`DeflectionLut`] tabulates exact deflection, time delay and disk
+//! crossings for distant observers, for the GPU shaders to sample;
+//! [`SurrogateTrainer`] fits a small network to the same function.
+
+pub mod deflection;
+pub mod disk_image;
+pub mod image;
+pub mod line_profile;
+pub mod output;
+pub mod polarimetry;
+pub mod reverberation;
+pub mod scene;
+pub mod sed;
+pub mod sequence;
+pub mod sky;
+pub mod supersample;
+pub mod surrogate;
+pub mod tiling;
+
+pub use deflection::{
+    trace_deflection, DeflectionLut, DeflectionLutSpec, DeflectionSample, LUT_MISSING,
+};
+pub use disk_image::{DiskImage, DiskImageSpec, DiskPatch, LimbDarkening};
+pub use image::{luminance, Image};
+pub use line_profile::{Emissivity, LineProfile};
+pub use output::{
+    flux_density, gravitational_radius_angle, pixel_solid_angle, write_fits, write_pfm, write_png,
+    BitDepth, FitsHeader, PngOptions, ToneMap,
+};
+pub use polarimetry::{render_stokes, StokesImage};
+pub use reverberation::{Illumination, Lamppost, TransferFunction};
+pub use scene::{Background, ThinDisk};
+pub use sed::{disk_spectrum, log_frequencies, DiskSpectrum, SedSpec};
+pub use sequence::{render_sequence, ImageSequence};
+pub use sky::{asymptotic_direction, CelestialSphere, Star};
+pub use supersample::{Sample, SupersampleOptions, SupersampleStats};
+pub use surrogate::{evaluate_buffer, Adam, Mlp, SurrogateSpec, SurrogateTrainer, ValidationError};
+pub use tiling::{PriorityMap, Tile, TileOrder, TileScheduler};
+
+use crate::camera::Camera;
+use crate::geodesic::{
+    step_rk4, step_symplectic, AdaptiveStepper, GeodesicState, IntegrationMethod,
+    IntegrationOptions, TerminationReason,
+};
+use crate::invariants::renormalize_null;
+use crate::metric::{contract, Metric};
+use crate::physics::spectrum::{integrate_planck_xyz, xyz_to_linear_rgb};
+use crate::physics::transfer::{
+    RadiativeMedium, RadiativeTransfer, TransferResult, TransferSettings,
+};
+use crate::tetrad::Tetrad;
+
+/// Largest affine step as a fraction of the current radius.
+const MAX_STEP_FRACTION: f64 = 0.1;
+
+/// How disk pixels are coloured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shading {
+    /// Colour of a blackbody at the observed temperature g T(r). The hottest
+    /// disk temperature, unshifted, maps to luminance 1.
+    #[default]
+    Blackbody,
+    /// Bolometric intensity g^4 F(r) / F_max, written to all three channels.
+    Intensity,
+}
+
+/// Scene and integration settings for [`render`].
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    /// Integrator settings; `escape_radius` is where rays sample the sky.
+    pub integration: IntegrationOptions,
+    /// Optional equatorial disk. Should describe the same hole as the metric.
+    pub disk: Option<ThinDisk>,
+    /// Sky seen by escaping rays.
+    pub background: Background,
+    /// Disk colouring.
+    pub shading: Shading,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            integration: IntegrationOptions::default(),
+            disk: None,
+            background: Background::Black,
+            shading: Shading::Blackbody,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Add a disk to the scene.
+    pub fn with_disk(mut self, disk: ThinDisk) -> Self {
+        self.disk = Some(disk);
+        self
+    }
+
+    /// Replace the background.
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Replace the disk shading.
+    pub fn with_shading(mut self, shading: Shading) -> Self {
+        self.shading = shading;
+        self
+    }
+
+    /// Replace the integrator settings.
+    pub fn with_integration(mut self, integration: IntegrationOptions) -> Self {
+        self.integration = integration;
+        self
+    }
+}
+
+/// Where a traced ray ended up.
+#[derive(Debug, Clone, Copy)]
+pub enum RayHit {
+    /// Fell into the black hole.
+    Horizon,
+    /// Crossed the equatorial plane on the disk.
+    Disk {
+        /// Equatorial radius of the crossing.
+        radius: f64,
+        /// Ray state interpolated to the crossing.
+        state: GeodesicState,
+    },
+    /// Escaped towards the asymptotic direction (theta, phi).
+    Sky { theta: f64, phi: f64 },
+    /// Ran out of steps.
+    Lost,
+}
+
+impl RayHit {
+    /// The matching integration termination reason.
+    pub fn termination(&self) -> TerminationReason {
+        match self {
+            RayHit::Horizon => TerminationReason::Horizon,
+            RayHit::Disk { .. } => TerminationReason::DiskCrossing,
+            RayHit::Sky { .. } => TerminationReason::Escape,
+            RayHit::Lost => TerminationReason::MaxSteps,
+        }
+    }
+}
+
+/// A traced ray: where it ended and how often it crossed the equator.
+#[derive(Debug, Clone, Copy)]
+pub struct TracedRay {
+    /// Where the ray ended.
+    pub hit: RayHit,
+    /// Equatorial-plane crossings, including a final disk hit. Rays with
+    /// n crossings before reaching the sky or disk form the order-n image.
+    pub crossings: u32,
+}
+
+/// Trace a backward ray until it hits the horizon, the disk, or the sky.
+pub fn trace_ray<M: Metric>(
+    ray: &GeodesicState,
+    metric: &M,
+    options: &IntegrationOptions,
+    disk: Option<&ThinDisk>,
+) -> TracedRay {
+    let mut state = *ray;
+    renormalize_null(&mut state, metric);
+
+    let horizon = metric.event_horizon();
+    let mut stepper = ray_stepper(options);
+    let mut h = options.initial_step;
+    let mut crossings = 0;
+    let traced = |hit, crossings| TracedRay { hit, crossings };
+
+    for step in 0..options.max_steps {
+        let r = state.x[1];
+        if r < horizon * 1.001 {
+            return traced(RayHit::Horizon, crossings);
+        }
+        if r > options.escape_radius {
+            let (theta, phi) = asymptotic_direction(&state, metric);
+            return traced(RayHit::Sky { theta, phi }, crossings);
+        }
+
+        let prev = state;
+        advance(&mut state, metric, options, &mut stepper, &mut h, step);
+
+        let (c0, c1) = (prev.x[2].cos(), state.x[2].cos());
+        if c0 * c1 < 0.0 {
+            crossings += 1;
+            if let Some(disk) = disk {
+                let crossing = lerp(&prev, &state, c0 / (c0 - c1));
+                if disk.contains(crossing.x[1]) {
+                    let hit = RayHit::Disk {
+                        radius: crossing.x[1],
+                        state: crossing,
+                    };
+                    return traced(hit, crossings);
+                }
+            }
+        }
+    }
+    traced(RayHit::Lost, crossings)
+}
+
+/// Integrate radiative transfer through `medium` along a backward ray that
+/// left a camera with 4-velocity `u_camera`, until the ray falls into the
+/// hole, escapes, or becomes opaque.
+///
+/// Returns where the ray ended (`Lost` if it became opaque) and the
+/// accumulated intensity and optical depth; light from beyond, such as the
+/// sky, should be added attenuated by the transmittance.
+pub fn trace_transfer<M: Metric, R: RadiativeMedium + ?Sized>(
+    ray: &GeodesicState,
+    u_camera: &[f64; 4],
+    metric: &M,
+    options: &IntegrationOptions,
+    medium: &R,
+    settings: TransferSettings,
+) -> (RayHit, TransferResult) {
+    let mut state = *ray;
+    renormalize_null(&mut state, metric);
+
+    let mut transfer = RadiativeTransfer::new(metric, medium, settings, &state, u_camera);
+    let horizon = metric.event_horizon();
+    let mut stepper = ray_stepper(options);
+    let mut h = options.initial_step;
+
+    for step in 0..options.max_steps {
+        let r = state.x[1];
+        if r < horizon * 1.001 {
+            return (RayHit::Horizon, transfer.result());
+        }
+        if r > options.escape_radius {
+            let (theta, phi) = asymptotic_direction(&state, metric);
+            return (RayHit::Sky { theta, phi }, transfer.result());
+        }
+        if transfer.is_opaque() {
+            break;
+        }
+        let prev = state;
+        advance(&mut state, metric, options, &mut stepper, &mut h, step);
+        transfer.add_segment(&prev, &state);
+    }
+    (RayHit::Lost, transfer.result())
+}
+
+/// Trace `ray` until it falls into the hole, escapes to `escape_radius` or
+/// runs out of steps, recording every state from the camera onwards.
+///
+/// Disk crossings are not checked; the path is for media that fill space,
+/// as in [`polarized_transfer_along_path`](crate::physics::polarization::polarized_transfer_along_path).
+pub fn trace_path<M: Metric>(
+    ray: &GeodesicState,
+    metric: &M,
+    options: &IntegrationOptions,
+) -> (RayHit, Vec<GeodesicState>) {
+    let mut state = *ray;
+    renormalize_null(&mut state, metric);
+    let mut path = vec![state];
+    let horizon = metric.event_horizon();
+    let mut stepper = ray_stepper(options);
+    let mut h = options.initial_step;
+
+    for step in 0..options.max_steps {
+        let r = state.x[1];
+        if r < horizon * 1.001 {
+            return (RayHit::Horizon, path);
+        }
+        if r > options.escape_radius {
+            let (theta, phi) = asymptotic_direction(&state, metric);
+            return (RayHit::Sky { theta, phi }, path);
+        }
+        advance(&mut state, metric, options, &mut stepper, &mut h, step);
+        path.push(state);
+    }
+    (RayHit::Lost, path)
+}
+
+/// Adaptive stepper for rays. Steps are capped relative to r in
+/// [`advance`]; a fixed cap would make distant escape radii cost thousands
+/// of steps.
+fn ray_stepper(options: &IntegrationOptions) -> AdaptiveStepper {
+    let mut stepper = AdaptiveStepper::new(options.tolerance);
+    stepper.max_step = f64::INFINITY;
+    stepper
+}
+
+/// Take step number `step` of a ray, at most `MAX_STEP_FRACTION * r` long.
+fn advance<M: Metric>(
+    state: &mut GeodesicState,
+    metric: &M,
+    options: &IntegrationOptions,
+    stepper: &mut AdaptiveStepper,
+    h: &mut f64,
+    step: usize,
+) {
+    let cap = MAX_STEP_FRACTION * state.x[1];
+    match options.method {
+        IntegrationMethod::AdaptiveRKF45 => {
+            *h = stepper.step(state, metric, h.min(cap));
+        }
+        IntegrationMethod::RK4 { step_size } => {
+            step_rk4(state, metric, step_size.min(cap));
+        }
+        IntegrationMethod::Symplectic { step_size } => {
+            step_symplectic(state, metric, step_size.min(cap));
+        }
+    }
+    if step.is_multiple_of(options.renormalize_interval) {
+        renormalize_null(state, metric);
+    }
+}
+
+/// Renders images for one camera, metric and scene.
+///
+/// Holds the camera tetrad and colour normalization so that individual
+/// samples can be requested in any order.
+pub struct Renderer<'a, M: Metric> {
+    camera: &'a Camera,
+    metric: &'a M,
+    settings: &'a RenderSettings,
+    tetrad: Tetrad,
+    /// Luminance of the unshifted peak-temperature blackbody.
+    blackbody_norm: f64,
+}
+
+impl<'a, M: Metric> Renderer<'a, M> {
+    /// Prepare to render `camera`'s view of the scene.
+    pub fn new(camera: &'a Camera, metric: &'a M, settings: &'a RenderSettings) -> Self {
+        let blackbody_norm = settings
+            .disk
+            .as_ref()
+            .map(|disk| integrate_planck_xyz(disk.peak_temperature())[1])
+            .filter(|&y| y > 0.0)
+            .unwrap_or(1.0);
+        Self {
+            camera,
+            metric,
+            settings,
+            tetrad: camera.tetrad(metric),
+            blackbody_norm,
+        }
+    }
+
+    /// Radiance along the ray through continuous image coordinate (x, y).
+    pub fn sample(&self, x: f64, y: f64) -> [f32; 3] {
+        self.trace(x, y).rgb
+    }
+
+    /// Trace the ray through continuous image coordinate (x, y), keeping
+    /// what it hit alongside the radiance.
+    pub fn trace(&self, x: f64, y: f64) -> Sample {
+        let Some(ray) = self.camera.ray(&self.tetrad, x, y) else {
+            return Sample {
+                rgb: [0.0; 3],
+                termination: TerminationReason::None,
+                crossings: 0,
+                shift: f64::NAN,
+            };
+        };
+        let traced = trace_ray(
+            &ray,
+            self.metric,
+            &self.settings.integration,
+            self.settings.disk.as_ref(),
+        );
+        let shift = match traced.hit {
+            RayHit::Disk { radius, state } => self.disk_shift(&ray, radius, &state),
+            _ => None,
+        };
+        Sample {
+            rgb: self.shade(&ray, &traced.hit),
+            termination: traced.hit.termination(),
+            crossings: traced.crossings,
+            shift: shift.unwrap_or(f64::NAN),
+        }
+    }
+
+    /// Radiance through the centre of pixel (i, j).
+    pub fn pixel(&self, i: u32, j: u32) -> [f32; 3] {
+        self.sample(i as f64 + 0.5, j as f64 + 0.5)
+    }
+
+    /// Render the full image.
+    pub fn render(&self) -> Image {
+        let mut image = Image::new(self.camera.width, self.camera.height);
+        for j in 0..image.height {
+            for i in 0..image.width {
+                image.set(i, j, self.pixel(i, j));
+            }
+        }
+        self.add_stars(&mut image);
+        image
+    }
+
+    /// Trace the samples of `tile` into `image`, filling each sample's
+    /// `stride x stride` block so coarse passes give a complete preview.
+    pub fn render_tile(&self, tile: &Tile, image: &mut Image) {
+        for (i, j) in tile.samples() {
+            image.fill_block(i, j, tile.stride, self.pixel(i, j));
+        }
+    }
+
+    /// Render every tile `scheduler` hands out, calling `on_tile` with the
+    /// partial image after each one.
+    pub fn render_progressive(
+        &self,
+        scheduler: &mut TileScheduler,
+        mut on_tile: impl FnMut(&Tile, &Image),
+    ) -> Image {
+        let mut image = Image::new(self.camera.width, self.camera.height);
+        while let Some(tile) = scheduler.pop_tile() {
+            self.render_tile(&tile, &mut image);
+            on_tile(&tile, &image);
+        }
+        self.add_stars(&mut image);
+        image
+    }
+
+    /// Frequency ratio g = nu_camera / nu_emit for a ray that left the camera
+    /// as `ray` and met the disk gas at `radius` with momentum `state.p`.
+    fn disk_shift(&self, ray: &GeodesicState, radius: f64, state: &GeodesicState) -> Option<f64> {
+        let disk = self.settings.disk.as_ref()?;
+        let u_emit = disk.emitter_velocity(self.metric, radius)?;
+        Some(contract(&ray.p, &self.camera.velocity) / contract(&state.p, &u_emit))
+    }
+
+    /// Colour for a traced ray that started at the camera as `ray`.
+    pub fn shade(&self, ray: &GeodesicState, hit: &RayHit) -> [f32; 3] {
+        match *hit {
+            RayHit::Horizon | RayHit::Lost => [0.0; 3],
+            RayHit::Sky { theta, phi } => {
+                let rgb = self.settings.background.sample(theta, phi);
+                match &self.settings.background {
+                    Background::Sphere(sky) if sky.doppler => {
+                        let boost = self.camera.sky_frequency_shift(ray).powi(4) as f32;
+                        rgb.map(|c| c * boost)
+                    }
+                    _ => rgb,
+                }
+            }
+            RayHit::Disk { radius, state } => {
+                let (Some(disk), Some(g)) = (
+                    self.settings.disk.as_ref(),
+                    self.disk_shift(ray, radius, &state),
+                ) else {
+                    return [0.0; 3];
+                };
+                match self.settings.shading {
+                    Shading::Blackbody => {
+                        let xyz = integrate_planck_xyz(g * disk.temperature(radius));
+                        let n = self.blackbody_norm;
+                        xyz_to_linear_rgb(xyz[0] / n, xyz[1] / n, xyz[2] / n)
+                    }
+                    Shading::Intensity => {
+                        let i = (g.powi(4) * disk.flux(radius)) as f32;
+                        [i; 3]
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render `camera`'s view of the scene described by `settings`.
+pub fn render<M: Metric>(camera: &Camera, metric: &M, settings: &RenderSettings) -> Image {
+    Renderer::new(camera, metric, settings).render()
+}
+
+/// Image of the radiation from `medium` seen by `camera`: every channel of
+/// each pixel holds the observed specific intensity I_nu at
+/// `settings.frequency`, in the units of the medium's coefficients (SI for
+/// [`MatterMedium`](crate::physics::matter::MatterMedium)).
+///
+/// Light from beyond the medium, such as the sky, is not included.
+pub fn render_intensity<M: Metric, R: RadiativeMedium + ?Sized>(
+    camera: &Camera,
+    metric: &M,
+    options: &IntegrationOptions,
+    medium: &R,
+    settings: TransferSettings,
+) -> Image {
+    let tetrad = camera.tetrad(metric);
+    let mut image = Image::new(camera.width, camera.height);
+    for j in 0..image.height {
+        for i in 0..image.width {
+            let Some(ray) = camera.ray(&tetrad, i as f64 + 0.5, j as f64 + 0.5) else {
+                continue;
+            };
+            let (_, result) =
+                trace_transfer(&ray, &camera.velocity, metric, options, medium, settings);
+            image.set(i, j, [result.intensity as f32; 3]);
+        }
+    }
+    image
+}
+
+/// Small deterministic generator (xorshift64*), uniform in [0, 1).
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    pub(crate) fn next(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Linear interpolation between two states.
+fn lerp(a: &GeodesicState, b: &GeodesicState, f: f64) -> GeodesicState {
+    let mut out = *a;
+    for i in 0..4 {
+        out.x[i] += (b.x[i] - a.x[i]) * f;
+        out.p[i] += (b.p[i] - a.p[i]) * f;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Kerr;
+
+    /// Static camera: the same observer in Boyer-Lindquist and Kerr-Schild.
+    fn disk_camera<M: Metric>(metric: &M) -> Camera {
+        Camera::static_observer(metric, [0.0, 40.0, 80f64.to_radians(), 0.0], 1.0, 32, 32).unwrap()
+    }
+
+    #[test]
+    fn test_shadow_is_black_and_disk_is_lit() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            let settings = RenderSettings::default()
+                .with_disk(ThinDisk::novikov_thorne(&bh, 20.0))
+                .with_background(Background::Uniform([0.1; 3]));
+            let image = render(&disk_camera(&bh), &bh, &settings);
+
+            // Centre looks into the shadow, just above the near side of the disk.
+            assert_eq!(image.get(16, 13), [0.0; 3]);
+            assert!(image.max_luminance() > 0.1);
+            assert!(image.pixels.iter().all(|c| c.iter().all(|v| v.is_finite())));
+        }
+    }
+
+    #[test]
+    fn test_boyer_lindquist_and_kerr_schild_agree() {
+        let render_with = |bh: Kerr| {
+            let settings = RenderSettings::default()
+                .with_disk(ThinDisk::novikov_thorne(&bh, 20.0))
+                .with_shading(Shading::Intensity);
+            render(&disk_camera(&bh), &bh, &settings)
+        };
+        let bl = render_with(Kerr::new(1.0, 0.7));
+        let ks = render_with(Kerr::kerr_schild(1.0, 0.7));
+        let total = |img: &Image| img.intensity().iter().sum::<f32>();
+        let (a, b) = (total(&bl), total(&ks));
+        assert!(a > 0.0 && ((a - b) / a).abs() < 0.02, "BL {a}, KS {b}");
+    }
+
+    #[test]
+    fn test_approaching_side_is_brighter() {
+        // Gas orbits in +phi. Seen from phi = 0, +e_phi (image right) is the
+        // receding side, so the left half of the image is Doppler boosted.
+        let bh = Kerr::new(1.0, 0.0);
+        let settings = RenderSettings::default()
+            .with_disk(ThinDisk::novikov_thorne(&bh, 20.0))
+            .with_shading(Shading::Intensity);
+        let image = render(&disk_camera(&bh), &bh, &settings);
+        let half = |range: std::ops::Range<u32>| -> f32 {
+            range
+                .flat_map(|i| (0..32).map(move |j| (i, j)))
+                .map(|(i, j)| image.luminance(i, j))
+                .sum()
+        };
+        assert!(half(0..16) > 1.5 * half(16..32));
+    }
+
+    #[test]
+    fn test_progressive_render_matches_direct_render() {
+        let bh = Kerr::kerr_schild(1.0, 0.6);
+        let cam = Camera::zamo(&bh, [0.0, 30.0, 1.3, 0.0], 0.9, 20, 14);
+        let settings = RenderSettings::default()
+            .with_disk(ThinDisk::novikov_thorne(&bh, 15.0))
+            .with_background(Background::Uniform([0.2; 3]));
+        let renderer = Renderer::new(&cam, &bh, &settings);
+
+        let mut scheduler = TileScheduler::new(20, 14, 8)
+            .with_order(TileOrder::Spiral)
+            .with_passes(3);
+        let mut coarse = None;
+        let progressive = renderer.render_progressive(&mut scheduler, |tile, image| {
+            if tile.pass == 0 {
+                coarse = Some(image.clone());
+            }
+        });
+        assert_eq!(progressive, renderer.render());
+
+        // After the first pass every pixel shows its 4x4 block's sample.
+        let coarse = coarse.unwrap();
+        for j in 0..14 {
+            for i in 0..20 {
+                assert_eq!(coarse.get(i, j), progressive.get(i - i % 4, j - j % 4));
+            }
+        }
+    }
+
+    #[test]
+    fn test_schwarzschild_shadow_size_and_symmetry() {
+        // Static observer at r: sin(alpha) = 3 sqrt(3) M / r * sqrt(1 - 2M / r).
+        let bh = Kerr::new(1.0, 0.0);
+        let r = 100.0;
+        let n = 41;
+        let fov = 0.2;
+        let cam = Camera::static_observer(&bh, [0.0, r, 1.0, 0.0], fov, n, n).unwrap();
+        let settings = RenderSettings::default().with_background(Background::Uniform([1.0; 3]));
+        let image = render(&cam, &bh, &settings);
+
+        let alpha = (27f64.sqrt() / r * (1.0 - 2.0 / r).sqrt()).asin();
+        let radius_px = alpha.tan() / (0.5 * fov).tan() * 0.5 * n as f64;
+        let expected = std::f64::consts::PI * radius_px * radius_px;
+        let dark = image.pixels.iter().filter(|c| c[0] == 0.0).count() as f64;
+        assert!(
+            (dark - expected).abs() / expected < 0.05,
+            "{dark} dark pixels, expected {expected}"
+        );
+
+        for j in 0..n {
+            for i in 0..n {
+                assert_eq!(image.get(i, j), image.get(n - 1 - i, j));
+            }
+        }
+    }
+
+    #[test]
+    fn test_transfer_through_emitting_ball() {
+        use crate::metric::Minkowski;
+        use crate::physics::transfer::Coefficients;
+
+        /// Static ball of radius 5 with a grey source function j / alpha = 0.2.
+        struct Ball(f64);
+        impl RadiativeMedium for Ball {
+            fn fluid_velocity(&self, x: &[f64; 4]) -> Option<[f64; 4]> {
+                (x[1] < 5.0).then_some([1.0, 0.0, 0.0, 0.0])
+            }
+            fn coefficients(&self, _x: &[f64; 4], _k: &[f64; 4], _nu: f64) -> Coefficients {
+                Coefficients {
+                    emissivity: 0.2 * self.0,
+                    absorptivity: self.0,
+                }
+            }
+        }
+
+        let u = [1.0, 0.0, 0.0, 0.0];
+        let cam = Camera::new([0.0, 20.0, 1.0, 0.0], u, 1.0, 3, 3);
+        let tetrad = cam.tetrad(&Minkowski);
+        let options = IntegrationOptions::default();
+        let settings = TransferSettings::default();
+
+        // Straight at the centre: optically thick, so I = j / alpha.
+        let centre = cam.ray(&tetrad, 1.5, 1.5).unwrap();
+        let (hit, thick) = trace_transfer(&centre, &u, &Minkowski, &options, &Ball(10.0), settings);
+        assert!(matches!(hit, RayHit::Lost));
+        assert!((thick.intensity - 0.2).abs() < 1e-6);
+
+        // The image corner misses the ball.
+        let corner = cam.ray(&tetrad, 0.0, 0.0).unwrap();
+        let (hit, missed) =
+            trace_transfer(&corner, &u, &Minkowski, &options, &Ball(10.0), settings);
+        assert!(matches!(hit, RayHit::Sky { .. }));
+        assert_eq!(missed, TransferResult::default());
+    }
+}
@@ -0,0 +1,500 @@
+//! Image file output: PFM, PNG and FITS, written without external crates.
+//!
+//! - **PFM** keeps the linear floating-point data losslessly.
+//! - **PNG** is tone-mapped and sRGB-encoded at 8 or 16 bits per channel. The
+//!   pixel data is stored in uncompressed deflate blocks, which every PNG
+//!   reader accepts.
+//! - **FITS** holds the single-channel luminance as 32-bit floats, with the
+//!   black hole parameters and angular scale in the header.
+
+use std::io::{self, Write};
+
+use crate::camera::Camera;
+use crate::constants::{MICROARCSEC_PER_RAD, SI_JANSKY, SI_PARSEC, SI_SOLAR_MASS, SI_TO_GEOM_MASS};
+
+use super::image::{luminance, Image};
+
+/// Size of a FITS header or data block in bytes.
+const FITS_BLOCK: usize = 2880;
+
+/// Largest payload of an uncompressed deflate block.
+const STORED_BLOCK: usize = 65_535;
+
+/// Write `image` as a colour portable float map.
+///
+/// Rows are stored bottom to top as little-endian 32-bit floats, as the
+/// format requires.
+pub fn write_pfm<W: Write>(image: &Image, mut writer: W) -> io::Result<()> {
+    write!(writer, "PF\n{} {}\n-1.0\n", image.width, image.height)?;
+    for j in (0..image.height).rev() {
+        for i in 0..image.width {
+            for c in image.get(i, j) {
+                writer.write_all(&c.to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Curve that maps linear radiance (after exposure) into [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMap {
+    /// Clip values above 1.
+    #[default]
+    Clamp,
+    /// c / (1 + c) per channel; keeps highlight detail.
+    Reinhard,
+}
+
+impl ToneMap {
+    /// Display value in [0, 1] for a linear value.
+    pub fn apply(self, value: f32) -> f32 {
+        let v = value.max(0.0);
+        match self {
+            ToneMap::Clamp => v.min(1.0),
+            ToneMap::Reinhard => v / (1.0 + v),
+        }
+    }
+}
+
+/// Bits per channel of a PNG file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+/// Tone mapping and encoding settings for [`write_png`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PngOptions {
+    /// Bits per channel.
+    pub bit_depth: BitDepth,
+    /// Linear scale applied before tone mapping.
+    pub exposure: f32,
+    /// Tone curve.
+    pub tone_map: ToneMap,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self {
+            bit_depth: BitDepth::Eight,
+            exposure: 1.0,
+            tone_map: ToneMap::Clamp,
+        }
+    }
+}
+
+/// sRGB transfer function for a display value in [0, 1].
+pub fn srgb_encode(linear: f32) -> f32 {
+    if linear <= 0.003_130_8 {
+        12.92 * linear
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Write `image` as a tone-mapped, sRGB-encoded RGB PNG.
+pub fn write_png<W: Write>(image: &Image, mut writer: W, options: &PngOptions) -> io::Result<()> {
+    let bytes_per_sample = match options.bit_depth {
+        BitDepth::Eight => 1,
+        BitDepth::Sixteen => 2,
+    };
+
+    // Filter type 0 (None) before every scanline.
+    let row_len = 1 + 3 * bytes_per_sample * image.width as usize;
+    let mut raw = Vec::with_capacity(row_len * image.height as usize);
+    for j in 0..image.height {
+        raw.push(0);
+        for i in 0..image.width {
+            for c in image.get(i, j) {
+                let v = srgb_encode(options.tone_map.apply(c * options.exposure));
+                match options.bit_depth {
+                    BitDepth::Eight => raw.push((v * 255.0).round() as u8),
+                    BitDepth::Sixteen => {
+                        raw.extend_from_slice(&((v * 65535.0).round() as u16).to_be_bytes())
+                    }
+                }
+            }
+        }
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&image.width.to_be_bytes());
+    ihdr.extend_from_slice(&image.height.to_be_bytes());
+    // Bit depth, colour type 2 (RGB), deflate, adaptive filtering, no interlace.
+    ihdr.extend_from_slice(&[8 * bytes_per_sample as u8, 2, 0, 0, 0]);
+
+    writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+    write_png_chunk(&mut writer, b"IHDR", &ihdr)?;
+    // Perceptual rendering intent.
+    write_png_chunk(&mut writer, b"sRGB", &[0])?;
+    write_png_chunk(&mut writer, b"IDAT", &zlib_stored(&raw))?;
+    write_png_chunk(&mut writer, b"IEND", &[])
+}
+
+/// Black hole and angular-scale metadata for [`write_fits`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitsHeader {
+    /// OBJECT keyword.
+    pub object: String,
+    /// Black hole mass in solar masses.
+    pub mass_solar: f64,
+    /// Dimensionless spin a/M.
+    pub spin: f64,
+    /// Observer inclination from the spin axis, in degrees.
+    pub inclination: f64,
+    /// Vertical field of view in microarcseconds.
+    pub fov: f64,
+    /// Distance in parsecs, if known.
+    pub distance_pc: Option<f64>,
+}
+
+impl FitsHeader {
+    /// Header for a render of a hole of `mass_solar` solar masses and `spin`
+    /// seen at `inclination` degrees, with a vertical field of view of `fov`
+    /// µas.
+    pub fn new(mass_solar: f64, spin: f64, inclination: f64, fov: f64) -> Self {
+        Self {
+            object: "BLACK HOLE".to_string(),
+            mass_solar,
+            spin,
+            inclination,
+            fov,
+            distance_pc: None,
+        }
+    }
+
+    /// Record the distance to the hole in parsecs.
+    pub fn with_distance(mut self, distance_pc: f64) -> Self {
+        self.distance_pc = Some(distance_pc);
+        self
+    }
+
+    /// Replace the OBJECT keyword.
+    pub fn with_object(mut self, object: impl Into<String>) -> Self {
+        self.object = object.into();
+        self
+    }
+}
+
+/// Angle subtended by one gravitational radius GM/c^2 of a black hole of
+/// `mass_solar` solar masses at `distance_pc` parsecs, in microarcseconds.
+///
+/// For M87* (6.5e9 Msun at 16.8 Mpc) this is about 3.8 µas.
+pub fn gravitational_radius_angle(mass_solar: f64, distance_pc: f64) -> f64 {
+    mass_solar * SI_SOLAR_MASS * SI_TO_GEOM_MASS / (distance_pc * SI_PARSEC) * MICROARCSEC_PER_RAD
+}
+
+/// Solid angle in steradians that one pixel of a distant pinhole `camera`
+/// covers on the sky of an observer at `distance_pc`, for a hole of
+/// `mass_solar` solar masses.
+///
+/// The pixel spans r 2 tan(fov / 2) / height in impact parameter, with the
+/// camera radius r in units of M, and each M subtends
+/// [`gravitational_radius_angle`].
+pub fn pixel_solid_angle(camera: &Camera, mass_solar: f64, distance_pc: f64) -> f64 {
+    let impact = camera.position[1] * 2.0 * (0.5 * camera.fov).tan() / camera.height.max(1) as f64;
+    let angle = impact * gravitational_radius_angle(mass_solar, distance_pc) / MICROARCSEC_PER_RAD;
+    angle * angle
+}
+
+/// Total flux density in Jy of an image of specific intensity in
+/// W m^-2 Hz^-1 sr^-1 (see [`render_intensity`](super::render_intensity)),
+/// with pixels of `pixel_solid_angle` steradians.
+pub fn flux_density(image: &Image, pixel_solid_angle: f64) -> f64 {
+    let total: f64 = image.pixels.iter().map(|p| p[0] as f64).sum();
+    total * pixel_solid_angle / SI_JANSKY
+}
+
+/// Write the luminance of `image` as a two-dimensional FITS image.
+///
+/// Data are big-endian IEEE floats (BITPIX = -32) with the first row at the
+/// bottom, following the FITS convention. The header carries MASS, SPIN,
+/// INCLIN, FOV and, if known, DISTANCE, plus a linear pixel scale in µas
+/// centred on the image.
+pub fn write_fits<W: Write>(image: &Image, mut writer: W, header: &FitsHeader) -> io::Result<()> {
+    let scale = header.fov / image.height.max(1) as f64;
+    let object = header.object.replace('\'', "''");
+    let mut cards = vec![
+        fits_card("SIMPLE", "T", "conforms to the FITS standard"),
+        fits_card("BITPIX", "-32", "IEEE single precision"),
+        fits_card("NAXIS", "2", "image"),
+        fits_card("NAXIS1", &image.width.to_string(), "width in pixels"),
+        fits_card("NAXIS2", &image.height.to_string(), "height in pixels"),
+        fits_card("OBJECT", &format!("'{object:<8}'"), ""),
+        fits_card("ORIGIN", "'gravitas'", "CPU reference renderer"),
+        fits_card("BUNIT", "'relative'", "linear luminance"),
+        fits_card(
+            "MASS",
+            &fits_float(header.mass_solar),
+            "black hole mass [Msun]",
+        ),
+        fits_card("SPIN", &fits_float(header.spin), "dimensionless spin a/M"),
+        fits_card(
+            "INCLIN",
+            &fits_float(header.inclination),
+            "inclination [deg]",
+        ),
+        fits_card(
+            "FOV",
+            &fits_float(header.fov),
+            "vertical field of view [uas]",
+        ),
+    ];
+    if let Some(distance) = header.distance_pc {
+        cards.push(fits_card(
+            "DISTANCE",
+            &fits_float(distance),
+            "distance [pc]",
+        ));
+    }
+    cards.extend([
+        fits_card("CTYPE1", "'X'", ""),
+        fits_card("CUNIT1", "'uas'", ""),
+        fits_card("CDELT1", &fits_float(scale), "pixel scale [uas]"),
+        fits_card("CRPIX1", &fits_float(0.5 * (image.width as f64 + 1.0)), ""),
+        fits_card("CRVAL1", "0.0", ""),
+        fits_card("CTYPE2", "'Y'", ""),
+        fits_card("CUNIT2", "'uas'", ""),
+        fits_card("CDELT2", &fits_float(scale), "pixel scale [uas]"),
+        fits_card("CRPIX2", &fits_float(0.5 * (image.height as f64 + 1.0)), ""),
+        fits_card("CRVAL2", "0.0", ""),
+        format!("{:<80}", "END"),
+    ]);
+
+    let mut bytes: Vec<u8> = cards.concat().into_bytes();
+    pad_to_block(&mut bytes, b' ');
+    for j in (0..image.height).rev() {
+        for i in 0..image.width {
+            bytes.extend_from_slice(&luminance(image.get(i, j)).to_be_bytes());
+        }
+    }
+    pad_to_block(&mut bytes, 0);
+    writer.write_all(&bytes)
+}
+
+/// One 80-character header card with the value right-aligned in column 30
+/// (strings start in column 11).
+fn fits_card(keyword: &str, value: &str, comment: &str) -> String {
+    let value = if value.starts_with('\'') {
+        format!("{value:<20}")
+    } else {
+        format!("{value:>20}")
+    };
+    let card = if comment.is_empty() {
+        format!("{keyword:<8}= {value}")
+    } else {
+        format!("{keyword:<8}= {value} / {comment}")
+    };
+    format!("{:<80.80}", card)
+}
+
+/// Floating-point value in a form FITS readers parse.
+fn fits_float(value: f64) -> String {
+    format!("{value:.10E}")
+}
+
+fn pad_to_block(bytes: &mut Vec<u8>, fill: u8) {
+    let len = bytes.len().div_ceil(FITS_BLOCK) * FITS_BLOCK;
+    bytes.resize(len, fill);
+}
+
+fn write_png_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = crc32(&[kind.as_slice(), data].concat());
+    writer.write_all(&crc.to_be_bytes())
+}
+
+/// zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let blocks = data.len().div_ceil(STORED_BLOCK).max(1);
+    let mut out = Vec::with_capacity(data.len() + 5 * blocks + 6);
+    // CMF/FLG: deflate, 32K window, no preset dictionary, check bits.
+    out.extend_from_slice(&[0x78, 0x01]);
+    for b in 0..blocks {
+        let chunk =
+            &data[(b * STORED_BLOCK).min(data.len())..((b + 1) * STORED_BLOCK).min(data.len())];
+        let len = chunk.len() as u16;
+        out.push(u8::from(b + 1 == blocks));
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65_521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+/// CRC-32 (IEEE 802.3), as used by PNG chunks.
+fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut n = 0;
+        while n < 256 {
+            let mut c = n as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 {
+                    0xEDB8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            table[n] = c;
+            n += 1;
+        }
+        table
+    };
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc = TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xFFFF_FFFF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32) -> Image {
+        let mut image = Image::new(width, height);
+        for j in 0..height {
+            for i in 0..width {
+                image.set(i, j, [i as f32, j as f32, 0.25]);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn test_pfm_layout() {
+        let image = gradient(3, 2);
+        let mut bytes = Vec::new();
+        write_pfm(&image, &mut bytes).unwrap();
+        let header = b"PF\n3 2\n-1.0\n";
+        assert_eq!(&bytes[..header.len()], header);
+        assert_eq!(bytes.len(), header.len() + 3 * 2 * 3 * 4);
+        // First stored pixel is the bottom-left one.
+        let first = f32::from_le_bytes(
+            bytes[header.len() + 4..header.len() + 8]
+                .try_into()
+                .unwrap(),
+        );
+        assert_eq!(first, 1.0);
+    }
+
+    #[test]
+    fn test_png_chunks_and_payload() {
+        let image = gradient(200, 120);
+        for bit_depth in [BitDepth::Eight, BitDepth::Sixteen] {
+            let options = PngOptions {
+                bit_depth,
+                exposure: 0.01,
+                tone_map: ToneMap::Reinhard,
+            };
+            let mut bytes = Vec::new();
+            write_png(&image, &mut bytes, &options).unwrap();
+            assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+            // Walk the chunks, checking every CRC and inflating the stored IDAT.
+            let mut pos = 8;
+            let mut idat = Vec::new();
+            let mut kinds = Vec::new();
+            while pos < bytes.len() {
+                let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+                let body = &bytes[pos + 4..pos + 8 + len];
+                let crc =
+                    u32::from_be_bytes(bytes[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+                assert_eq!(crc32(body), crc);
+                kinds.push(body[..4].to_vec());
+                if &body[..4] == b"IDAT" {
+                    idat.extend_from_slice(&body[4..]);
+                }
+                pos += 12 + len;
+            }
+            assert_eq!(kinds.first().unwrap(), b"IHDR");
+            assert_eq!(kinds.last().unwrap(), b"IEND");
+
+            let mut raw = Vec::new();
+            let mut p = 2;
+            loop {
+                let last = idat[p] & 1 == 1;
+                let len = u16::from_le_bytes([idat[p + 1], idat[p + 2]]) as usize;
+                raw.extend_from_slice(&idat[p + 5..p + 5 + len]);
+                p += 5 + len;
+                if last {
+                    break;
+                }
+            }
+            assert_eq!(
+                u32::from_be_bytes(idat[p..p + 4].try_into().unwrap()),
+                adler32(&raw)
+            );
+            let samples = if bit_depth == BitDepth::Eight { 1 } else { 2 };
+            assert_eq!(raw.len(), 120 * (1 + 200 * 3 * samples));
+        }
+    }
+
+    #[test]
+    fn test_fits_blocks_and_keywords() {
+        let image = gradient(5, 4);
+        let header = FitsHeader::new(6.5e9, 0.94, 17.0, 160.0)
+            .with_object("M87*")
+            .with_distance(16.8e6);
+        let mut bytes = Vec::new();
+        write_fits(&image, &mut bytes, &header).unwrap();
+        assert_eq!(bytes.len(), 2 * FITS_BLOCK);
+
+        let text = String::from_utf8_lossy(&bytes[..FITS_BLOCK]);
+        let cards: Vec<&str> = (0..36).map(|k| &text[80 * k..80 * (k + 1)]).collect();
+        assert!(cards[0].starts_with("SIMPLE  =                    T"));
+        assert!(cards
+            .iter()
+            .any(|c| c.starts_with("SPIN    =") && c.contains("9.4")));
+        assert!(cards
+            .iter()
+            .any(|c| c.starts_with("FOV     =") && c.contains("1.6")));
+        assert!(cards
+            .iter()
+            .any(|c| c.starts_with("MASS    =") && c.contains("6.5") && c.contains("Msun")));
+        assert!(cards
+            .iter()
+            .any(|c| c.starts_with("DISTANCE=") && c.contains("1.68")));
+        assert!(cards.iter().any(|c| c.starts_with("OBJECT  = 'M87*")));
+        assert!(cards.iter().any(|c| c.trim_end() == "END"));
+
+        // First data value is the bottom-left pixel.
+        let first = f32::from_be_bytes(bytes[FITS_BLOCK..FITS_BLOCK + 4].try_into().unwrap());
+        assert!((first - luminance([0.0, 3.0, 0.25])).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_m87_angular_scale() {
+        let uas = gravitational_radius_angle(6.5e9, 16.8e6);
+        assert!((uas - 3.8).abs() < 0.05, "{uas}");
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+}
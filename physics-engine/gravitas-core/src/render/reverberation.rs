@@ -0,0 +1,602 @@
+//! Lamppost illumination and reverberation transfer functions.
+//!
+//! In the lamppost geometry a compact corona sits on the spin axis at
+//! height h and shines on the disk, which reprocesses the light into a
+//! reflection spectrum with a fluorescent iron line. [`Lamppost::illuminate`]
+//! shoots photons isotropically in the frame of a static source and records
+//! where they land, giving the emissivity
+//!
+//!   epsilon(r) = N(r) g_ls^Gamma / (A(r) gamma_disk)
+//!
+//! for N(r) photons landing on an annulus of proper area A(r), the disk
+//! gas's Lorentz factor gamma_disk relative to the ZAMOs and the energy
+//! shift g_ls = E_disk / E_source of a power-law continuum of photon index
+//! Gamma. Light bending focuses the photons of low sources onto the inner
+//! disk, steepening the profile there; far out it tends to r^-3.
+//!
+//! Each photon's coordinate travel time is recorded too. Adding the travel
+//! time from the disk to a distant observer, from a [`DiskImage`], and
+//! subtracting that of the direct continuum gives every patch of the image a
+//! delay; histogramming the reflected line flux in redshift and delay gives
+//! the 2D [`TransferFunction`] whose moments are the lag-energy spectrum
+//! and the impulse response. Times are in units of GM/c^3.
+//!
+//! # References
+//!
+//! - Reynolds, C. S. et al. (1999). "X-ray iron line reverberation from
+//!   black hole accretion disks"
+//! - Dauser, T. et al. (2013). "Irradiation of an accretion disc by a jet:
+//!   general properties and implications for spin measurements of black
+//!   holes"
+//! - Cackett, E. M. et al. (2014). "Modelling the broad Fe K alpha
+//!   reverberation in the AGN NGC 4151"
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::geodesic::{GeodesicState, IntegrationOptions};
+use crate::metric::{contract, Kerr, Metric};
+use crate::tetrad::{static_velocity, Tetrad};
+
+use super::disk_image::{DiskImage, LimbDarkening};
+use super::line_profile::{interpolate, Emissivity, LineProfile};
+use super::scene::ThinDisk;
+use super::{lerp, trace_path, trace_ray, RayHit};
+
+/// Polar angle of the source; the coordinates are singular on the axis.
+const AXIS_THETA: f64 = 1e-4;
+
+/// Position-angle samples when searching for the direct ray.
+const DIRECT_SCAN: usize = 64;
+
+/// Bisection steps for the direct ray.
+const DIRECT_BISECTIONS: usize = 40;
+
+/// An isotropic point source on the spin axis.
+#[derive(Debug, Clone)]
+pub struct Lamppost {
+    /// Height above the disk in units of M.
+    pub height: f64,
+    /// Photon index Gamma of the source's power-law continuum.
+    pub photon_index: f64,
+    /// Number of photons shot per illumination profile.
+    pub photons: usize,
+    /// Integrator settings; `escape_radius` must exceed the observer
+    /// radius passed to [`Lamppost::direct_time`].
+    pub integration: IntegrationOptions,
+}
+
+impl Lamppost {
+    /// Source at `height` with Gamma = 2.
+    pub fn new(height: f64) -> Self {
+        Self {
+            height,
+            photon_index: 2.0,
+            photons: 4096,
+            integration: IntegrationOptions {
+                escape_radius: 2000.0,
+                ..IntegrationOptions::default()
+            },
+        }
+    }
+
+    /// Set the continuum's photon index.
+    pub fn with_photon_index(mut self, photon_index: f64) -> Self {
+        self.photon_index = photon_index;
+        self
+    }
+
+    /// Shoot `photons` photons per profile.
+    pub fn with_photons(mut self, photons: usize) -> Self {
+        self.photons = photons;
+        self
+    }
+
+    /// Illumination of the disk between `inner_radius` and `outer_radius`
+    /// around `bh`, in `bins` logarithmic annuli.
+    ///
+    /// A quarter of the photons sample the upper hemisphere, densest near
+    /// the horizontal where bent photons reach the outer disk; the rest are
+    /// aimed at logarithmically spaced radii of the disk, as they would land
+    /// in flat space, so that every annulus receives some.
+    /// Returns `None` if the source is inside the horizon.
+    pub fn illuminate(
+        &self,
+        bh: &Kerr,
+        inner_radius: f64,
+        outer_radius: f64,
+        bins: usize,
+    ) -> Option<Illumination> {
+        let u_source = static_velocity(bh, self.height, AXIS_THETA)?;
+        let mut disk = ThinDisk::novikov_thorne(bh, outer_radius);
+        disk.inner_radius = inner_radius;
+        let h = self.height;
+
+        // Edges in cos(chi), chi measured from the outward axis.
+        let up = (self.photons / 4).max(1);
+        let down = self.photons.saturating_sub(up).max(2);
+        let mut edges: Vec<f64> = (0..=up)
+            .map(|k| (1.0 - k as f64 / up as f64).powi(2))
+            .collect();
+        let (near, far) = (1e-2 * h, 10.0 * outer_radius);
+        for k in 0..down - 1 {
+            let landing = far * (near / far).powf(k as f64 / (down - 2).max(1) as f64);
+            edges.push(-h / (h * h + landing * landing).sqrt());
+        }
+        edges.push(-1.0);
+
+        let log_span = (outer_radius / inner_radius).ln();
+        let edge = |k: usize| inner_radius * (log_span * k as f64 / bins as f64).exp();
+        let (mut on_disk, mut captured) = (0.0, 0.0);
+        // Landing (position in bins, g_ls, travel time) of each photon, at
+        // the middle of its band.
+        let mut centres = Vec::with_capacity(edges.len() - 1);
+        let mut landings = Vec::with_capacity(edges.len() - 1);
+        for band in edges.windows(2) {
+            let weight = 0.5 * (band[0] - band[1]);
+            let centre = 0.5 * (band[0] + band[1]);
+            let photon = self.launch(bh, &u_source, centre.acos());
+            let mut landing = None;
+            match trace_ray(&photon, bh, &self.integration, Some(&disk)).hit {
+                RayHit::Disk { radius, state } => {
+                    on_disk += weight;
+                    landing = disk.emitter_velocity(bh, radius).map(|u_disk| {
+                        let position = (radius / inner_radius).ln() / log_span * bins as f64;
+                        let shift = -contract(&state.p, &u_disk);
+                        (position, shift, state.x[0] - photon.x[0])
+                    });
+                }
+                RayHit::Horizon => captured += weight,
+                _ => {}
+            }
+            centres.push(centre);
+            landings.push(landing);
+        }
+
+        // Each pair of neighbouring photons bounds a bundle; spread its solid
+        // angle evenly in ln(r) between their landings.
+        let mut hits = vec![0.0; bins];
+        let mut weighted = vec![0.0; bins];
+        let mut times = vec![0.0; bins];
+        let mut shifts = vec![0.0; bins];
+        for k in 1..landings.len() {
+            let weight = 0.5 * (centres[k - 1] - centres[k]);
+            // A bundle straddling the disk edge or the escape boundary is
+            // credited by halves to the photon that lands.
+            let (a, b, weight) = match (landings[k - 1], landings[k]) {
+                (Some(a), Some(b)) => (a, b, weight),
+                (Some(a), None) | (None, Some(a)) => (a, a, 0.5 * weight),
+                (None, None) => continue,
+            };
+            let shift = 0.5 * (a.1 + b.1);
+            let time = 0.5 * (a.2 + b.2);
+            let (lo, hi) = (a.0.min(b.0), a.0.max(b.0));
+            let first = (lo.floor().max(0.0) as usize).min(bins - 1);
+            let last = (hi.floor().max(0.0) as usize).min(bins - 1);
+            for bin in first..=last {
+                let share = if hi > lo {
+                    (hi.min(bin as f64 + 1.0) - lo.max(bin as f64)).max(0.0) / (hi - lo)
+                } else {
+                    1.0
+                };
+                hits[bin] += weight * share;
+                weighted[bin] += weight * share * shift.powf(self.photon_index);
+                times[bin] += weight * share * time;
+                shifts[bin] += weight * share * shift;
+            }
+        }
+
+        // Annulus areas and disk Lorentz factors, from the Boyer-Lindquist
+        // form of the hole.
+        let bl = Kerr::new(bh.mass(), bh.spin());
+        let mut radii = Vec::with_capacity(bins);
+        let mut emissivity = Vec::with_capacity(bins);
+        for k in 0..bins {
+            let (r0, r1) = (edge(k), edge(k + 1));
+            let r = (r0 * r1).sqrt();
+            let g = bl.covariant(r, FRAC_PI_2);
+            let area = TAU * (g.get(1, 1) * g.get(3, 3)).sqrt() * (r1 - r0);
+            let lapse = 1.0 / (-bl.contravariant(r, FRAC_PI_2).get(0, 0)).sqrt();
+            let gamma = disk
+                .emitter_velocity(&bl, r)
+                .map_or(f64::NAN, |u| lapse * u[0]);
+            radii.push(r);
+            emissivity.push(weighted[k] / (area * gamma));
+            if hits[k] > 0.0 {
+                times[k] /= hits[k];
+                shifts[k] /= hits[k];
+            } else {
+                times[k] = f64::NAN;
+                shifts[k] = f64::NAN;
+            }
+        }
+        fill_gaps(&radii, &mut times);
+        fill_gaps(&radii, &mut shifts);
+
+        Some(Illumination {
+            height: h,
+            radii,
+            emissivity,
+            travel_time: times,
+            redshift: shifts,
+            disk_fraction: on_disk,
+            horizon_fraction: captured,
+        })
+    }
+
+    /// Coordinate time for the source's own light to reach `observer_radius`
+    /// at `inclination`, found by bisecting on the emission angle. Delays
+    /// are measured from it.
+    ///
+    /// Returns `None` if the source is inside the horizon or no escaping
+    /// photon reaches that inclination.
+    pub fn direct_time(&self, bh: &Kerr, inclination: f64, observer_radius: f64) -> Option<f64> {
+        let u_source = static_velocity(bh, self.height, AXIS_THETA)?;
+        let arrive = |chi: f64| self.arrival(bh, &u_source, chi, observer_radius);
+        let target = inclination.clamp(AXIS_THETA, PI - AXIS_THETA);
+
+        let mut previous: Option<(f64, f64, f64)> = None;
+        for k in 0..DIRECT_SCAN {
+            let chi = PI * (k as f64 + 0.5) / DIRECT_SCAN as f64;
+            let Some((theta, time)) = arrive(chi) else {
+                previous = None;
+                continue;
+            };
+            if let Some((chi_a, theta_a, time_a)) = previous {
+                if (theta_a - target) * (theta - target) <= 0.0 {
+                    let (mut lo, mut hi) = ((chi_a, theta_a, time_a), (chi, theta, time));
+                    for _ in 0..DIRECT_BISECTIONS {
+                        let mid = 0.5 * (lo.0 + hi.0);
+                        let Some((theta_m, time_m)) = arrive(mid) else {
+                            break;
+                        };
+                        if (lo.1 - target) * (theta_m - target) <= 0.0 {
+                            hi = (mid, theta_m, time_m);
+                        } else {
+                            lo = (mid, theta_m, time_m);
+                        }
+                    }
+                    let f = if hi.1 == lo.1 {
+                        0.5
+                    } else {
+                        (target - lo.1) / (hi.1 - lo.1)
+                    };
+                    return Some(lo.2 + (hi.2 - lo.2) * f);
+                }
+            }
+            previous = Some((chi, theta, time));
+        }
+        None
+    }
+
+    /// Photon of unit energy leaving the source at angle `chi` from the
+    /// outward axis, at coordinate time 0.
+    fn launch(&self, bh: &Kerr, u_source: &[f64; 4], chi: f64) -> GeodesicState {
+        let tetrad = Tetrad::adapted(bh, self.height, AXIS_THETA, *u_source);
+        GeodesicState {
+            x: [0.0, self.height, AXIS_THETA, 0.0],
+            p: tetrad.null_momentum(1.0, [chi.cos(), chi.sin(), 0.0]),
+        }
+    }
+
+    /// (theta, t) where the photon at `chi` crosses `radius`, if it does.
+    fn arrival(&self, bh: &Kerr, u_source: &[f64; 4], chi: f64, radius: f64) -> Option<(f64, f64)> {
+        let photon = self.launch(bh, u_source, chi);
+        let (hit, path) = trace_path(&photon, bh, &self.integration);
+        if !matches!(hit, RayHit::Sky { .. }) {
+            return None;
+        }
+        let k = path.iter().position(|s| s.x[1] >= radius)?;
+        let crossing = if k == 0 {
+            path[0]
+        } else {
+            let (a, b) = (&path[k - 1], &path[k]);
+            lerp(a, b, (radius - a.x[1]) / (b.x[1] - a.x[1]))
+        };
+        Some((crossing.x[2], crossing.x[0] - photon.x[0]))
+    }
+}
+
+/// How a [`Lamppost`] lights the disk, per photon it emits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Illumination {
+    /// Source height.
+    pub height: f64,
+    /// Geometric centres of the logarithmic annuli.
+    pub radii: Vec<f64>,
+    /// Emissivity epsilon(r) of each annulus.
+    pub emissivity: Vec<f64>,
+    /// Mean coordinate time from the source to each annulus.
+    pub travel_time: Vec<f64>,
+    /// Mean energy shift g_ls = E_disk / E_source of the photons landing
+    /// on each annulus.
+    pub redshift: Vec<f64>,
+    /// Fraction of the photons that land on the disk.
+    pub disk_fraction: f64,
+    /// Fraction of the photons that fall into the hole.
+    pub horizon_fraction: f64,
+}
+
+impl Illumination {
+    /// The profile as an [`Emissivity`] for line profiles.
+    pub fn emissivity_law(&self) -> Emissivity {
+        Emissivity::Tabulated {
+            radii: self.radii.clone(),
+            values: self.emissivity.clone(),
+        }
+    }
+
+    /// Travel time from the source to radius r, if r is on the disk.
+    pub fn travel_time_at(&self, r: f64) -> Option<f64> {
+        let (first, last) = (*self.radii.first()?, *self.radii.last()?);
+        interpolate(&self.radii, &self.travel_time, r.clamp(first, last))
+    }
+
+    /// Mean emissivity index -d ln(epsilon) / d ln(r) between r0 and r1,
+    /// fitted by least squares over the annuli in between.
+    pub fn index(&self, r0: f64, r1: f64) -> f64 {
+        let points: Vec<(f64, f64)> = self
+            .radii
+            .iter()
+            .zip(&self.emissivity)
+            .filter(|(r, e)| **r >= r0 && **r <= r1 && **e > 0.0)
+            .map(|(r, e)| (r.ln(), e.ln()))
+            .collect();
+        let n = points.len() as f64;
+        let (sx, sy) = points
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        let (mx, my) = (sx / n, sy / n);
+        let (sxy, sxx) = points.iter().fold((0.0, 0.0), |(sxy, sxx), (x, y)| {
+            (sxy + (x - mx) * (y - my), sxx + (x - mx).powi(2))
+        });
+        -sxy / sxx
+    }
+}
+
+/// Reflected line flux of a lamppost-lit disk per unit redshift and delay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferFunction {
+    /// Bin centres in E_obs / E_emit.
+    pub energies: Vec<f64>,
+    /// Bin centres in delay after the direct continuum, in GM/c^3.
+    pub delays: Vec<f64>,
+    /// Energy flux density, delay-major, normalized to unit integral.
+    pub response: Vec<f64>,
+}
+
+impl TransferFunction {
+    /// Histogram the patches of `image` in redshift and in delay relative
+    /// to the direct light, arriving `direct_time` after emission, with
+    /// `bins = (energy bins, delay bins)` over the given ranges. Patches are
+    /// weighted as in [`LineProfile::from_image`], with the lamppost
+    /// emissivity.
+    pub fn from_image(
+        image: &DiskImage,
+        illumination: &Illumination,
+        direct_time: f64,
+        limb: LimbDarkening,
+        energy_range: (f64, f64),
+        delay_range: (f64, f64),
+        bins: (usize, usize),
+    ) -> Self {
+        let emissivity = illumination.emissivity_law();
+        let (ne, nd) = bins;
+        let de = (energy_range.1 - energy_range.0) / ne as f64;
+        let dd = (delay_range.1 - delay_range.0) / nd as f64;
+        let mut response = vec![0.0; ne * nd];
+        for patch in &image.patches {
+            let Some(to_disk) = illumination.travel_time_at(patch.radius) else {
+                continue;
+            };
+            let delay = to_disk + patch.travel_time - direct_time;
+            let i = ((patch.redshift - energy_range.0) / de).floor();
+            let j = ((delay - delay_range.0) / dd).floor();
+            if i < 0.0 || j < 0.0 || i >= ne as f64 || j >= nd as f64 {
+                continue;
+            }
+            response[j as usize * ne + i as usize] += emissivity.at(patch.radius)
+                * limb.weight(patch.cos_emission)
+                * patch.redshift.powi(4)
+                * patch.area;
+        }
+        let total: f64 = response.iter().sum::<f64>() * de * dd;
+        if total > 0.0 {
+            response.iter_mut().for_each(|v| *v /= total);
+        }
+        Self {
+            energies: (0..ne)
+                .map(|i| energy_range.0 + (i as f64 + 0.5) * de)
+                .collect(),
+            delays: (0..nd)
+                .map(|j| delay_range.0 + (j as f64 + 0.5) * dd)
+                .collect(),
+            response,
+        }
+    }
+
+    /// Response at energy bin i and delay bin j.
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        self.response[j * self.energies.len() + i]
+    }
+
+    /// The time-averaged line: the response summed over delays.
+    pub fn line_profile(&self) -> LineProfile {
+        let dd = bin_width(&self.delays);
+        let flux = (0..self.energies.len())
+            .map(|i| (0..self.delays.len()).map(|j| self.get(i, j)).sum::<f64>() * dd)
+            .collect();
+        LineProfile {
+            energies: self.energies.clone(),
+            flux,
+        }
+    }
+
+    /// The impulse response: line flux per unit delay, summed over energy.
+    pub fn impulse_response(&self) -> Vec<f64> {
+        let de = bin_width(&self.energies);
+        (0..self.delays.len())
+            .map(|j| {
+                (0..self.energies.len())
+                    .map(|i| self.get(i, j))
+                    .sum::<f64>()
+                    * de
+            })
+            .collect()
+    }
+
+    /// Mean delay in each energy bin, the lag-energy spectrum of the line;
+    /// NaN for empty bins.
+    pub fn lag_energy(&self) -> Vec<f64> {
+        (0..self.energies.len())
+            .map(|i| {
+                let (mut sum, mut weight) = (0.0, 0.0);
+                for (j, delay) in self.delays.iter().enumerate() {
+                    sum += delay * self.get(i, j);
+                    weight += self.get(i, j);
+                }
+                if weight > 0.0 {
+                    sum / weight
+                } else {
+                    f64::NAN
+                }
+            })
+            .collect()
+    }
+
+    /// Mean delay of the whole line.
+    pub fn mean_delay(&self) -> f64 {
+        let dd = bin_width(&self.delays);
+        self.impulse_response()
+            .iter()
+            .zip(&self.delays)
+            .map(|(r, t)| r * t * dd)
+            .sum()
+    }
+}
+
+/// Spacing of uniformly spaced bin centres.
+fn bin_width(centres: &[f64]) -> f64 {
+    match centres {
+        [first, second, ..] => second - first,
+        _ => 0.0,
+    }
+}
+
+/// Replace NaN entries of `values` by linear interpolation in `xs` between
+/// their finite neighbours, or by the nearest finite value at the ends.
+fn fill_gaps(xs: &[f64], values: &mut [f64]) {
+    let known: Vec<usize> = (0..values.len())
+        .filter(|&k| values[k].is_finite())
+        .collect();
+    let (Some(&first), Some(&last)) = (known.first(), known.last()) else {
+        return;
+    };
+    for k in 0..values.len() {
+        if values[k].is_finite() {
+            continue;
+        }
+        values[k] = if k < first {
+            values[first]
+        } else if k > last {
+            values[last]
+        } else {
+            let b = known[known.partition_point(|&i| i < k)];
+            let a = known[known.partition_point(|&i| i < k) - 1];
+            let f = (xs[k] - xs[a]) / (xs[b] - xs[a]);
+            values[a] * (1.0 - f) + values[b] * f
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Orbit;
+    use crate::render::disk_image::DiskImageSpec;
+
+    #[test]
+    fn test_lamppost_emissivity_profile() {
+        let bh = Kerr::new(1.0, 0.998);
+        let r_in = bh.isco(Orbit::Prograde);
+        let low = Lamppost::new(3.0)
+            .with_photons(2048)
+            .illuminate(&bh, r_in, 200.0, 48)
+            .unwrap();
+        let high = Lamppost::new(10.0)
+            .with_photons(2048)
+            .illuminate(&bh, r_in, 200.0, 48)
+            .unwrap();
+
+        // Light bending focuses a low source's photons onto the inner disk.
+        assert!(low.index(r_in, 3.0) > 2.8, "{}", low.index(r_in, 3.0));
+        assert!(high.index(r_in, 3.0) < 2.5, "{}", high.index(r_in, 3.0));
+        assert!(low.index(3.0, 10.0) > 3.2, "{}", low.index(3.0, 10.0));
+        assert!(high.index(3.0, 10.0) < 2.0, "{}", high.index(3.0, 10.0));
+        assert!(low.disk_fraction > high.disk_fraction);
+        assert!(low.horizon_fraction > high.horizon_fraction);
+        assert!(low.disk_fraction + low.horizon_fraction <= 1.0);
+
+        // Far out, r^-3 and nearly flat-space travel times.
+        let outer = high.index(50.0, 200.0);
+        assert!((outer - 3.0).abs() < 0.3, "{outer}");
+        let t = high.travel_time_at(100.0).unwrap();
+        let flat = (100.0f64.powi(2) + 100.0).sqrt();
+        assert!(t > flat && t < 1.1 * flat, "{t} vs {flat}");
+        // The inner disk sees the source blueshifted.
+        assert!(low.redshift[0] > 1.0);
+    }
+
+    #[test]
+    fn test_reverberation_transfer_function() {
+        let bh = Kerr::new(1.0, 0.998);
+        let spec = DiskImageSpec::new(30f64.to_radians(), 100.0).with_resolution(48, 48);
+        let image = DiskImage::trace(&bh, &spec);
+        let limb = LimbDarkening::Isotropic;
+        let transfer = |height: f64| {
+            let lamp = Lamppost::new(height).with_photons(1024);
+            let illumination = lamp
+                .illuminate(&bh, image.inner_radius, spec.outer_radius, 48)
+                .unwrap();
+            let direct = lamp
+                .direct_time(&bh, spec.inclination, spec.observer_radius)
+                .unwrap();
+            // Flat space: the direct path is shorter by h cos(i), plus a
+            // logarithmic Shapiro delay.
+            let flat = spec.observer_radius - height * spec.inclination.cos();
+            assert!(direct > flat && direct < flat + 20.0, "{direct} vs {flat}");
+            let tf = TransferFunction::from_image(
+                &image,
+                &illumination,
+                direct,
+                limb,
+                (0.0, 1.6),
+                (-20.0, 400.0),
+                (32, 84),
+            );
+            (tf, illumination)
+        };
+
+        let (low, illumination) = transfer(3.0);
+        let profile = low.line_profile();
+        let area: f64 = profile.flux.iter().sum::<f64>() * profile.bin_width();
+        assert!((area - 1.0).abs() < 1e-9);
+        // With the whole delay range covered, summing over delays gives the
+        // line profile of the same emissivity.
+        let direct =
+            LineProfile::from_image(&image, &illumination.emissivity_law(), limb, 32, (0.0, 1.6));
+        for (a, b) in profile.flux.iter().zip(&direct.flux) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        // Reflection arrives after the continuum, and the red wing from
+        // the inner disk responds before the line core.
+        assert!(low.mean_delay() > 0.0);
+        let lags = low.lag_energy();
+        let (wing, core) = (lags[10], lags[19]);
+        assert!(wing < core, "{lags:?}");
+
+        let (high, _) = transfer(10.0);
+        assert!(high.mean_delay() > low.mean_delay());
+        assert_eq!(high.impulse_response().len(), 84);
+    }
+}
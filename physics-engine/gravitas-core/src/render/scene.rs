@@ -0,0 +1,148 @@
+//! Scene content for the reference renderer: the thin disk and the sky.
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::metric::{Kerr, Metric, Orbit};
+use crate::physics::disk;
+use crate::tetrad::circular_velocity;
+
+use super::sky::CelestialSphere;
+
+/// Number of radial samples in the disk emission table.
+const TABLE_SIZE: usize = 512;
+
+/// Geometrically thin, optically thick Novikov-Thorne disk in the
+/// equatorial plane, with gas on prograde Keplerian orbits.
+///
+/// The Page-Thorne flux is tabulated once at construction, so per-ray
+/// lookups are cheap.
+#[derive(Debug, Clone)]
+pub struct ThinDisk {
+    /// Inner edge (the prograde ISCO).
+    pub inner_radius: f64,
+    /// Outer edge.
+    pub outer_radius: f64,
+    mass: f64,
+    a: f64,
+    flux: Vec<f64>,
+    temperature: Vec<f64>,
+    peak_flux: f64,
+    peak_temperature: f64,
+}
+
+impl ThinDisk {
+    /// Novikov-Thorne disk around `bh` from the ISCO to `outer_radius`, at
+    /// unit accretion rate.
+    pub fn novikov_thorne(bh: &Kerr, outer_radius: f64) -> Self {
+        let inner_radius = bh.isco(Orbit::Prograde);
+        let radius = |i: usize| {
+            inner_radius + (outer_radius - inner_radius) * i as f64 / (TABLE_SIZE - 1) as f64
+        };
+        let flux: Vec<f64> = (0..TABLE_SIZE)
+            .map(|i| disk::page_thorne_flux(radius(i), bh, 1.0))
+            .collect();
+        let temperature: Vec<f64> = (0..TABLE_SIZE)
+            .map(|i| disk::temperature(radius(i), bh, 1.0))
+            .collect();
+        let peak_flux = flux.iter().copied().fold(0.0, f64::max);
+        let peak_temperature = temperature.iter().copied().fold(0.0, f64::max);
+
+        Self {
+            inner_radius,
+            outer_radius,
+            mass: bh.mass(),
+            a: bh.a(),
+            flux,
+            temperature,
+            peak_flux,
+            peak_temperature,
+        }
+    }
+
+    /// Whether the equatorial radius r lies on the disk.
+    #[inline]
+    pub fn contains(&self, r: f64) -> bool {
+        r >= self.inner_radius && r <= self.outer_radius
+    }
+
+    /// Emitted flux at r, normalized to 1 at the flux peak.
+    pub fn flux(&self, r: f64) -> f64 {
+        if self.peak_flux > 0.0 {
+            self.lookup(&self.flux, r) / self.peak_flux
+        } else {
+            0.0
+        }
+    }
+
+    /// Effective temperature at r in Kelvin (see [`disk::temperature`]).
+    pub fn temperature(&self, r: f64) -> f64 {
+        self.lookup(&self.temperature, r)
+    }
+
+    /// Hottest temperature on the disk.
+    #[inline]
+    pub fn peak_temperature(&self) -> f64 {
+        self.peak_temperature
+    }
+
+    /// Keplerian angular velocity Omega = sqrt(M) / (r^{3/2} + a sqrt(M)).
+    #[inline]
+    pub fn angular_velocity(&self, r: f64) -> f64 {
+        let sqrt_m = self.mass.sqrt();
+        sqrt_m / (r.powf(1.5) + self.a * sqrt_m)
+    }
+
+    /// 4-velocity of the disk gas at radius r in `metric`'s coordinates.
+    ///
+    /// Omega is the same in Boyer-Lindquist and Kerr-Schild coordinates, so
+    /// either form of the hole the disk was built for may be passed.
+    pub fn emitter_velocity<M: Metric>(&self, metric: &M, r: f64) -> Option<[f64; 4]> {
+        circular_velocity(metric, r, FRAC_PI_2, self.angular_velocity(r))
+    }
+
+    /// Linear interpolation in a table sampled uniformly over [r_in, r_out].
+    fn lookup(&self, table: &[f64], r: f64) -> f64 {
+        if !self.contains(r) {
+            return 0.0;
+        }
+        let x = (r - self.inner_radius) / (self.outer_radius - self.inner_radius)
+            * (TABLE_SIZE - 1) as f64;
+        let i = (x.floor() as usize).min(TABLE_SIZE - 2);
+        let f = x - i as f64;
+        table[i] * (1.0 - f) + table[i + 1] * f
+    }
+}
+
+/// What escaping rays see.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    /// Empty black sky.
+    Black,
+    /// Constant colour in every direction.
+    Uniform([f32; 3]),
+    /// Checkerboard on the celestial sphere, `divisions` cells per pi
+    /// radians in both theta and phi. Shows lensing distortions clearly.
+    Checker {
+        divisions: u32,
+        colors: [[f32; 3]; 2],
+    },
+    /// Sky texture and point stars, with lensing magnification.
+    Sphere(CelestialSphere),
+}
+
+impl Background {
+    /// Colour of the sky at asymptotic direction (theta, phi).
+    pub fn sample(&self, theta: f64, phi: f64) -> [f32; 3] {
+        match self {
+            Background::Black => [0.0; 3],
+            Background::Uniform(rgb) => *rgb,
+            Background::Checker { divisions, colors } => {
+                let cell = PI / (*divisions).max(1) as f64;
+                let u = (theta / cell).floor() as i64;
+                let v = (phi.rem_euclid(2.0 * PI) / cell).floor() as i64;
+                colors[((u + v) & 1) as usize]
+            }
+            Background::Sphere(sky) => sky.sample(theta, phi),
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! Lensed celestial sphere: an equirectangular sky texture plus point stars.
+//!
+//! Escaping rays look up the texture at their asymptotic direction. Point
+//! stars cannot be found by sampling, so they are splatted instead: the
+//! four corner rays of each pixel map the pixel onto a quadrilateral of the
+//! unlensed sky, and a star inside that footprint lands in the pixel with
+//! magnification
+//!
+//!   mu = (solid angle of the pixel at the camera) / (solid angle of its footprint).
+//!
+//! Every image of a star (primary, secondary, Einstein ring segments) is
+//! found this way, with the correct relative brightness. With `doppler` on,
+//! texture radiance and star flux are also scaled by g^4, where g is the
+//! frequency ratio between the camera and a static source at infinity.
+
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec3;
+
+use crate::geodesic::{GeodesicState, TerminationReason, Trajectory};
+use crate::metric::Metric;
+
+use super::image::Image;
+use super::{trace_ray, RayHit, Renderer, Rng};
+
+/// A point source on the celestial sphere.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Star {
+    /// Polar angle from the +z (spin) axis.
+    pub theta: f64,
+    /// Azimuth.
+    pub phi: f64,
+    /// Unlensed flux, in units of pixel value x pixel.
+    pub flux: f32,
+    /// Colour multiplying the flux.
+    pub color: [f32; 3],
+}
+
+impl Star {
+    /// Unit direction of the star.
+    pub fn direction(&self) -> DVec3 {
+        spherical_to_unit(self.theta, self.phi)
+    }
+}
+
+/// Background sky made of an optional texture and a star catalog.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CelestialSphere {
+    /// Equirectangular map: columns span phi in [0, 2 pi), rows span theta
+    /// from 0 (top) to pi (bottom).
+    pub texture: Option<Image>,
+    /// Point stars.
+    pub stars: Vec<Star>,
+    /// Scale radiance and flux by g^4 for the camera's motion and depth in
+    /// the potential.
+    pub doppler: bool,
+}
+
+impl CelestialSphere {
+    /// Empty black sky.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the sky texture.
+    pub fn with_texture(mut self, texture: Image) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    /// Replace the star catalog.
+    pub fn with_stars(mut self, stars: Vec<Star>) -> Self {
+        self.stars = stars;
+        self
+    }
+
+    /// Turn g^4 boosting on or off.
+    pub fn with_doppler(mut self, doppler: bool) -> Self {
+        self.doppler = doppler;
+        self
+    }
+
+    /// `count` stars spread uniformly over the sphere with a power-law
+    /// brightness distribution (many faint, few bright), reproducible from
+    /// `seed`.
+    pub fn with_random_stars(mut self, count: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut next = || rng.next();
+        self.stars = (0..count)
+            .map(|_| {
+                let theta = (1.0 - 2.0 * next()).acos();
+                let phi = TAU * next();
+                let flux = (0.05 / next().max(1e-3)).min(20.0) as f32;
+                let warmth = next() as f32;
+                let color = [0.8 + 0.2 * warmth, 0.85, 1.0 - 0.25 * warmth];
+                Star {
+                    theta,
+                    phi,
+                    flux,
+                    color,
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Texture radiance in direction (theta, phi), bilinearly filtered.
+    #[allow(clippy::needless_range_loop)]
+    pub fn sample(&self, theta: f64, phi: f64) -> [f32; 3] {
+        let Some(texture) = &self.texture else {
+            return [0.0; 3];
+        };
+        let (w, h) = (texture.width as f64, texture.height as f64);
+        let x = phi.rem_euclid(TAU) / TAU * w - 0.5;
+        let y = (theta.clamp(0.0, PI) / PI * h - 0.5).clamp(0.0, h - 1.0);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = ((x - x0) as f32, (y - y0) as f32);
+        let column = |x: f64| (x.rem_euclid(w) as u32).min(texture.width - 1);
+        let (i0, i1) = (column(x0), column(x0 + 1.0));
+        let j0 = y0 as u32;
+        let j1 = (j0 + 1).min(texture.height - 1);
+
+        let mut rgb = [0.0; 3];
+        for c in 0..3 {
+            let top = texture.get(i0, j0)[c] * (1.0 - fx) + texture.get(i1, j0)[c] * fx;
+            let bottom = texture.get(i0, j1)[c] * (1.0 - fx) + texture.get(i1, j1)[c] * fx;
+            rgb[c] = top * (1.0 - fy) + bottom * fy;
+        }
+        rgb
+    }
+
+    /// Texture radiance seen along an integrated trajectory, or `None` if
+    /// the ray did not escape.
+    pub fn sample_trajectory<M: Metric>(
+        &self,
+        trajectory: &Trajectory,
+        metric: &M,
+    ) -> Option<[f32; 3]> {
+        if trajectory.termination != TerminationReason::Escape {
+            return None;
+        }
+        let (theta, phi) = asymptotic_direction(&trajectory.final_state, metric);
+        Some(self.sample(theta, phi))
+    }
+}
+
+/// Direction (theta, phi) on the celestial sphere towards which an escaping
+/// ray is heading.
+///
+/// Uses the spatial velocity dx^i/dlambda = g^{i nu} p_nu in the flat
+/// asymptotic frame, which is more accurate than the ray's angular
+/// position at a finite escape radius.
+pub fn asymptotic_direction<M: Metric>(state: &GeodesicState, metric: &M) -> (f64, f64) {
+    let [_, r, theta, phi] = state.x;
+    let v = metric.contravariant(r, theta).raise_index(&state.p);
+    let (st, ct) = theta.sin_cos();
+    let (sp, cp) = phi.sin_cos();
+    let r_hat = DVec3::new(st * cp, st * sp, ct);
+    let theta_hat = DVec3::new(ct * cp, ct * sp, -st);
+    let phi_hat = DVec3::new(-sp, cp, 0.0);
+    let dir = (v[1] * r_hat + r * v[2] * theta_hat + r * st * v[3] * phi_hat).normalize();
+    (
+        dir.z.clamp(-1.0, 1.0).acos(),
+        dir.y.atan2(dir.x).rem_euclid(TAU),
+    )
+}
+
+/// Unit vector for spherical angles (theta, phi).
+fn spherical_to_unit(theta: f64, phi: f64) -> DVec3 {
+    let (st, ct) = theta.sin_cos();
+    let (sp, cp) = phi.sin_cos();
+    DVec3::new(st * cp, st * sp, ct)
+}
+
+/// Solid angle of the spherical triangle (a, b, c), signed by orientation
+/// (Van Oosterom & Strackee 1983).
+fn triangle_solid_angle(a: DVec3, b: DVec3, c: DVec3) -> f64 {
+    let numerator = a.dot(b.cross(c));
+    let denominator = 1.0 + a.dot(b) + b.dot(c) + c.dot(a);
+    2.0 * numerator.atan2(denominator)
+}
+
+/// Signed solid angle of the spherical quadrilateral (a, b, c, d).
+fn quad_solid_angle(q: &[DVec3; 4]) -> f64 {
+    triangle_solid_angle(q[0], q[1], q[2]) + triangle_solid_angle(q[0], q[2], q[3])
+}
+
+/// Whether `s` lies inside the spherical quadrilateral `q`, of either
+/// orientation.
+fn quad_contains(q: &[DVec3; 4], s: DVec3) -> bool {
+    let centre = q[0] + q[1] + q[2] + q[3];
+    if s.dot(centre) <= 0.0 {
+        return false;
+    }
+    let sides = [0, 1, 2, 3].map(|k| s.dot(q[k].cross(q[(k + 1) % 4])));
+    sides.iter().all(|&x| x >= 0.0) || sides.iter().all(|&x| x <= 0.0)
+}
+
+impl<M: Metric> Renderer<'_, M> {
+    /// Splat the stars of a [`CelestialSphere`] background into `image`
+    /// with their lensing magnification. Does nothing for other
+    /// backgrounds or an empty catalog.
+    ///
+    /// Traces one extra ray per pixel corner, against the disk as well, so a
+    /// pixel gets no starlight unless all four corner rays escape.
+    #[allow(clippy::needless_range_loop)]
+    pub fn add_stars(&self, image: &mut Image) {
+        let super::Background::Sphere(sky) = &self.settings.background else {
+            return;
+        };
+        if sky.stars.is_empty() {
+            return;
+        }
+        let (width, height) = (image.width, image.height);
+        let stars: Vec<(DVec3, &Star)> = sky.stars.iter().map(|s| (s.direction(), s)).collect();
+
+        // Camera-frame and asymptotic sky directions at every pixel corner,
+        // with the g^4 boost of the corner ray.
+        let mut corners = Vec::with_capacity(((width + 1) * (height + 1)) as usize);
+        for j in 0..=height {
+            for i in 0..=width {
+                corners.push(self.corner(i as f64, j as f64, sky.doppler));
+            }
+        }
+        let corner = |i: u32, j: u32| &corners[(j * (width + 1) + i) as usize];
+
+        for j in 0..height {
+            for i in 0..width {
+                let quad = [
+                    corner(i, j),
+                    corner(i + 1, j),
+                    corner(i + 1, j + 1),
+                    corner(i, j + 1),
+                ];
+                let Some(quad) = quad
+                    .iter()
+                    .map(|c| c.as_ref())
+                    .collect::<Option<Vec<&(DVec3, DVec3, f64)>>>()
+                else {
+                    continue;
+                };
+                let view = [quad[0].0, quad[1].0, quad[2].0, quad[3].0];
+                let footprint = [quad[0].1, quad[1].1, quad[2].1, quad[3].1];
+                let sky_area = quad_solid_angle(&footprint).abs();
+                if sky_area <= 0.0 {
+                    continue;
+                }
+                let magnification = quad_solid_angle(&view).abs() / sky_area;
+                let boost = 0.25 * quad.iter().map(|c| c.2).sum::<f64>();
+
+                // Quick reject: stars farther than the footprint's largest
+                // corner distance from its centre.
+                let centre =
+                    (footprint[0] + footprint[1] + footprint[2] + footprint[3]).normalize();
+                let reach = footprint.iter().map(|f| f.dot(centre)).fold(1.0, f64::min);
+
+                for (dir, star) in &stars {
+                    if dir.dot(centre) < reach || !quad_contains(&footprint, *dir) {
+                        continue;
+                    }
+                    let flux = star.flux * (magnification * boost) as f32;
+                    let mut rgb = image.get(i, j);
+                    for c in 0..3 {
+                        rgb[c] += flux * star.color[c];
+                    }
+                    image.set(i, j, rgb);
+                }
+            }
+        }
+    }
+
+    /// Camera-frame direction, asymptotic sky direction and g^4 for the
+    /// ray through image point (x, y), or `None` if it hits the disk or the
+    /// horizon instead of escaping.
+    fn corner(&self, x: f64, y: f64, doppler: bool) -> Option<(DVec3, DVec3, f64)> {
+        let n = self.camera.direction(x, y)?;
+        let ray = self.camera.ray(&self.tetrad, x, y)?;
+        let traced = trace_ray(
+            &ray,
+            self.metric,
+            &self.settings.integration,
+            self.settings.disk.as_ref(),
+        );
+        let RayHit::Sky { theta, phi } = traced.hit else {
+            return None;
+        };
+        let boost = if doppler {
+            self.camera.sky_frequency_shift(&ray).powi(4)
+        } else {
+            1.0
+        };
+        Some((DVec3::from_array(n), spherical_to_unit(theta, phi), boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Camera;
+    use crate::geodesic::IntegrationOptions;
+    use crate::metric::{Kerr, Minkowski};
+    use crate::render::{Background, RenderSettings, ThinDisk};
+
+    fn star(theta: f64, phi: f64) -> Star {
+        Star {
+            theta,
+            phi,
+            flux: 1.0,
+            color: [1.0; 3],
+        }
+    }
+
+    fn total_flux(image: &Image) -> f32 {
+        image.pixels.iter().map(|c| c[0]).sum()
+    }
+
+    #[test]
+    fn test_flat_space_star_is_unmagnified() {
+        let cam = Camera::new([0.0, 50.0, 1.2, 0.3], [1.0, 0.0, 0.0, 0.0], 0.6, 32, 32);
+        // A star straight ahead of the camera (which looks towards the origin).
+        let ahead = -spherical_to_unit(1.2, 0.3);
+        let (theta, phi) = (ahead.z.acos(), ahead.y.atan2(ahead.x));
+        let sky = CelestialSphere::new().with_stars(vec![star(theta + 0.05, phi - 0.08)]);
+        let settings = RenderSettings::default().with_background(Background::Sphere(sky));
+        let image = super::super::render(&cam, &Minkowski, &settings);
+        assert!((total_flux(&image) - 1.0).abs() < 1e-3);
+        assert_eq!(image.pixels.iter().filter(|c| c[0] > 0.0).count(), 1);
+    }
+
+    #[test]
+    fn test_point_lens_magnification() {
+        // Far camera, star behind the hole offset by half the Einstein angle.
+        // Weak-field point lens: mu = (u^2 + 2) / (u sqrt(u^2 + 4)), u = 0.5.
+        let r: f64 = 500.0;
+        let bh = Kerr::new(1.0, 0.0);
+        let theta_e = (4.0 / r).sqrt();
+        let cam = Camera::static_observer(&bh, [0.0, r, 1.3, 0.0], 3.0 * theta_e, 96, 96).unwrap();
+        let sky = CelestialSphere::new()
+            .with_stars(vec![star(std::f64::consts::PI - 1.3 + 0.5 * theta_e, PI)]);
+        let settings = RenderSettings::default()
+            .with_background(Background::Sphere(sky))
+            .with_integration(IntegrationOptions {
+                escape_radius: 1e5,
+                ..IntegrationOptions::default()
+            });
+        let image = super::super::render(&cam, &bh, &settings);
+
+        let u: f64 = 0.5;
+        let expected = (u * u + 2.0) / (u * (u * u + 4.0).sqrt());
+        let lit = image.pixels.iter().filter(|c| c[0] > 0.0).count();
+        assert_eq!(lit, 2, "primary and secondary images");
+        let total = total_flux(&image) as f64;
+        assert!(
+            (total - expected).abs() / expected < 0.05,
+            "mu = {total}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn test_disk_hides_stars_behind_it() {
+        let bh = Kerr::new(1.0, 0.0);
+        let cam = Camera::static_observer(&bh, [0.0, 40.0, 1.35, 0.0], 1.0, 40, 30).unwrap();
+        let stars = CelestialSphere::new().with_random_stars(4000, 3);
+        let disk = ThinDisk::novikov_thorne(&bh, 25.0);
+        let render = |sky: &CelestialSphere, disk: Option<&ThinDisk>| {
+            let mut settings =
+                RenderSettings::default().with_background(Background::Sphere(sky.clone()));
+            settings.disk = disk.cloned();
+            super::super::render(&cam, &bh, &settings)
+        };
+        // Without a texture the sky is black, so lit pixels are the disk.
+        let bare = render(&CelestialSphere::new(), Some(&disk));
+        let starry = render(&stars, Some(&disk));
+        let see_through = render(&stars, None);
+
+        let on_disk: Vec<usize> = (0..bare.pixels.len())
+            .filter(|&k| bare.pixels[k][0] > 0.0)
+            .collect();
+        assert!(on_disk.len() > 100);
+        // Without the disk, stars would show through those pixels...
+        let hidden: f32 = on_disk.iter().map(|&k| see_through.pixels[k][0]).sum();
+        assert!(hidden > 0.0);
+        // ...but the disk is opaque.
+        for &k in &on_disk {
+            assert_eq!(starry.pixels[k], bare.pixels[k]);
+        }
+        assert!(total_flux(&starry) > total_flux(&bare));
+    }
+
+    #[test]
+    fn test_texture_wraps_in_phi() {
+        let mut texture = Image::new(4, 2);
+        texture.set(0, 0, [1.0; 3]);
+        texture.set(3, 0, [3.0; 3]);
+        let sky = CelestialSphere::new().with_texture(texture);
+        // phi = 0 sits between the last and first columns.
+        let rgb = sky.sample(0.0, 0.0);
+        assert!((rgb[0] - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_random_catalog_is_reproducible() {
+        let a = CelestialSphere::new().with_random_stars(100, 7);
+        let b = CelestialSphere::new().with_random_stars(100, 7);
+        assert_eq!(a, b);
+        assert!(a
+            .stars
+            .iter()
+            .all(|s| (0.0..=PI).contains(&s.theta) && s.flux > 0.0));
+    }
+}
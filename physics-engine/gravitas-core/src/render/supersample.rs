@@ -0,0 +1,284 @@
+//! Adaptive supersampling of edges, photon rings and the shadow boundary.
+//!
+//! Every pixel is first traced once through its centre. A pixel is refined
+//! when it disagrees with one of its eight neighbours in any of:
+//!
+//! - how its ray ended (horizon, disk, sky),
+//! - for disk hits, the number of equatorial crossings, which separates the
+//!   direct image from the n >= 1 photon rings,
+//! - the disk redshift g, beyond a relative threshold,
+//! - luminance, beyond a relative contrast threshold.
+//!
+//! A refined pixel is split into 2 x 2 sub-pixels, and split again while
+//! the sub-pixel samples still disagree, up to `max_depth` levels. The
+//! pixel value is the area-weighted mean of the leaves, so the thin rings
+//! converge without supersampling the whole frame. Neighbours of a pixel
+//! that turned out to straddle an edge are refined as well, which catches
+//! edges that only clip a pixel corner.
+
+use crate::geodesic::TerminationReason;
+use crate::metric::Metric;
+
+use super::image::{luminance, Image};
+use super::Renderer;
+
+/// One traced ray with the data used to detect edges.
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    /// Radiance along the ray.
+    pub rgb: [f32; 3],
+    /// How the ray ended (`None` where the projection has no ray).
+    pub termination: TerminationReason,
+    /// Equatorial-plane crossings before the ray ended.
+    pub crossings: u32,
+    /// Disk redshift g, or NaN if the ray did not end on the disk.
+    pub shift: f64,
+}
+
+/// Refinement thresholds for [`Renderer::render_adaptive`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SupersampleOptions {
+    /// Subdivision levels; a pixel receives at most 4^max_depth rays.
+    pub max_depth: u32,
+    /// Relative difference in g that triggers refinement.
+    pub shift_threshold: f64,
+    /// Relative luminance difference that triggers refinement.
+    pub contrast_threshold: f32,
+}
+
+impl Default for SupersampleOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            shift_threshold: 0.05,
+            contrast_threshold: 0.3,
+        }
+    }
+}
+
+impl SupersampleOptions {
+    /// Whether two neighbouring samples straddle an edge.
+    pub fn differs(&self, a: &Sample, b: &Sample) -> bool {
+        if a.termination != b.termination {
+            return true;
+        }
+        // Sky rays cross the equator at arbitrary radii, so only disk hits
+        // are compared by image order.
+        if a.termination == TerminationReason::DiskCrossing && a.crossings != b.crossings {
+            return true;
+        }
+        if a.shift.is_finite()
+            && b.shift.is_finite()
+            && (a.shift - b.shift).abs() > self.shift_threshold * a.shift.max(b.shift)
+        {
+            return true;
+        }
+        let (la, lb) = (luminance(a.rgb), luminance(b.rgb));
+        (la - lb).abs() > self.contrast_threshold * la.max(lb).max(1e-6)
+    }
+}
+
+/// Work done by an adaptive render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SupersampleStats {
+    /// Rays traced in total.
+    pub rays: usize,
+    /// Pixels that were subdivided.
+    pub refined_pixels: usize,
+}
+
+impl<M: Metric> Renderer<'_, M> {
+    /// Render with one ray per pixel, then supersample pixels on edges.
+    pub fn render_adaptive(&self, options: &SupersampleOptions) -> (Image, SupersampleStats) {
+        let (width, height) = (self.camera.width, self.camera.height);
+        let mut stats = SupersampleStats::default();
+
+        let mut centres = Vec::with_capacity((width * height) as usize);
+        for j in 0..height {
+            for i in 0..width {
+                centres.push(self.trace(i as f64 + 0.5, j as f64 + 0.5));
+            }
+        }
+        stats.rays += centres.len();
+
+        let mut image = Image::new(width, height);
+        for (pixel, sample) in image.pixels.iter_mut().zip(&centres) {
+            *pixel = sample.rgb;
+        }
+        if options.max_depth == 0 {
+            self.add_stars(&mut image);
+            return (image, stats);
+        }
+
+        // Seed with pixels whose centre differs from one of its eight
+        // neighbours. A refined pixel that turns out to straddle an edge
+        // queues its neighbours too, catching edges that only clip a corner.
+        let index = |i: u32, j: u32| (j * width + i) as usize;
+        let neighbours = move |i: u32, j: u32| {
+            (j.saturating_sub(1)..(j + 2).min(height)).flat_map(move |nj| {
+                (i.saturating_sub(1)..(i + 2).min(width)).map(move |ni| (ni, nj))
+            })
+        };
+        let mut queued = vec![false; centres.len()];
+        let mut queue = Vec::new();
+        for j in 0..height {
+            for i in 0..width {
+                let centre = &centres[index(i, j)];
+                if neighbours(i, j).any(|(ni, nj)| options.differs(centre, &centres[index(ni, nj)]))
+                {
+                    queued[index(i, j)] = true;
+                    queue.push((i, j));
+                }
+            }
+        }
+
+        while let Some((i, j)) = queue.pop() {
+            stats.refined_pixels += 1;
+            let (rgb, mixed) = self.refine(i as f64, j as f64, 1.0, 1, options, &mut stats.rays);
+            image.set(i, j, rgb);
+            if mixed {
+                for (ni, nj) in neighbours(i, j) {
+                    if !queued[index(ni, nj)] {
+                        queued[index(ni, nj)] = true;
+                        queue.push((ni, nj));
+                    }
+                }
+            }
+        }
+        self.add_stars(&mut image);
+        (image, stats)
+    }
+
+    /// Mean radiance over the square [x, x + size] x [y, y + size], from its
+    /// four quadrant centres, recursing while they disagree. Also returns
+    /// whether any samples in the square disagreed.
+    fn refine(
+        &self,
+        x: f64,
+        y: f64,
+        size: f64,
+        depth: u32,
+        options: &SupersampleOptions,
+        rays: &mut usize,
+    ) -> ([f32; 3], bool) {
+        let half = 0.5 * size;
+        let corners = [(x, y), (x + half, y), (x, y + half), (x + half, y + half)];
+        let samples = corners.map(|(qx, qy)| self.trace(qx + 0.5 * half, qy + 0.5 * half));
+        *rays += 4;
+
+        // An edge between any two quadrant centres may cut through every
+        // quadrant, so all four are split.
+        let mut mixed = samples[1..]
+            .iter()
+            .any(|other| options.differs(&samples[0], other));
+        let split = mixed && depth < options.max_depth;
+
+        let mut sum = [0.0f32; 3];
+        for (q, sample) in samples.iter().enumerate() {
+            let rgb = if split {
+                let (qx, qy) = corners[q];
+                let (rgb, quadrant_mixed) = self.refine(qx, qy, half, depth + 1, options, rays);
+                mixed |= quadrant_mixed;
+                rgb
+            } else {
+                sample.rgb
+            };
+            for c in 0..3 {
+                sum[c] += 0.25 * rgb[c];
+            }
+        }
+        (sum, mixed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Camera;
+    use crate::metric::Kerr;
+    use crate::render::{Background, RenderSettings};
+
+    #[test]
+    fn test_shadow_edge_converges_with_few_rays() {
+        // Unit sky behind a Schwarzschild shadow: each pixel's exact value is
+        // the fraction of its area outside the shadow circle.
+        let bh = Kerr::new(1.0, 0.0);
+        let (r, n, fov) = (100.0, 25, 0.15);
+        let cam = Camera::static_observer(&bh, [0.0, r, 1.0, 0.0], fov, n, n).unwrap();
+        let settings = RenderSettings::default().with_background(Background::Uniform([1.0; 3]));
+        let renderer = Renderer::new(&cam, &bh, &settings);
+
+        let alpha = (27f64.sqrt() / r * (1.0 - 2.0 / r).sqrt()).asin();
+        let tan_half = (0.5 * fov).tan();
+        let exact = |i: u32, j: u32| {
+            let m = 64;
+            let mut outside = 0;
+            for sj in 0..m {
+                for si in 0..m {
+                    let u = (i as f64 + (si as f64 + 0.5) / m as f64) / n as f64;
+                    let v = (j as f64 + (sj as f64 + 0.5) / m as f64) / n as f64;
+                    let (x, y) = ((2.0 * u - 1.0) * tan_half, (1.0 - 2.0 * v) * tan_half);
+                    if (x * x + y * y).sqrt().atan() > alpha {
+                        outside += 1;
+                    }
+                }
+            }
+            outside as f32 / (m * m) as f32
+        };
+
+        let single = renderer.render();
+        let (adaptive, stats) = renderer.render_adaptive(&SupersampleOptions::default());
+
+        let mut single_error = 0.0;
+        let mut adaptive_error = 0.0;
+        for j in 0..n {
+            for i in 0..n {
+                let e = exact(i, j);
+                single_error += (single.get(i, j)[0] - e).abs();
+                adaptive_error += (adaptive.get(i, j)[0] - e).abs();
+            }
+        }
+        assert!(
+            adaptive_error < 0.35 * single_error,
+            "adaptive {adaptive_error}, single {single_error}"
+        );
+        // Only the ring of edge pixels is refined.
+        assert!(stats.refined_pixels > 0 && stats.refined_pixels < (n * n / 3) as usize);
+        assert!(stats.rays < 4 * (n * n) as usize);
+    }
+
+    #[test]
+    fn test_edge_signals() {
+        let options = SupersampleOptions::default();
+        let base = Sample {
+            rgb: [1.0; 3],
+            termination: TerminationReason::DiskCrossing,
+            crossings: 1,
+            shift: 1.0,
+        };
+        assert!(!options.differs(&base, &base));
+        assert!(options.differs(
+            &base,
+            &Sample {
+                crossings: 2,
+                ..base
+            }
+        ));
+        assert!(options.differs(
+            &base,
+            &Sample {
+                termination: TerminationReason::Escape,
+                shift: f64::NAN,
+                ..base
+            }
+        ));
+        assert!(options.differs(&base, &Sample { shift: 1.2, ..base }));
+        assert!(!options.differs(
+            &base,
+            &Sample {
+                shift: 1.01,
+                ..base
+            }
+        ));
+    }
+}
@@ -0,0 +1,678 @@
+//! Neural surrogate for the deflection function.
+//!
+//! A small multilayer perceptron learns the map from a distant observer's
+//! ray (impact parameter, screen position angle, spin, inclination) to its
+//! deflection angle and time delay, so a shader can replace ray marching
+//! for distant-sky pixels with one forward pass. Ground truth comes from
+//! [`trace_deflection`], which integrates the geodesic exactly; training is
+//! plain backpropagation of the mean squared error with the Adam optimizer.
+//!
+//! Only rays that escape are learned: impact parameters start outside the
+//! largest Kerr shadow. Inputs are
+//!
+//!   (M/b, cos psi, sin psi, a, cos i)
+//!
+//! and outputs are (deflection angle in radians, time delay in M), with the
+//! same conventions as [`DeflectionLut`](super::DeflectionLut). The
+//! trained network is exported by [`Mlp::to_buffer`] in the layout below,
+//! which `src/shaders/compute/mlp.wgsl` evaluates:
+//!
+//! ```text
+//! 0           | format version (1)
+//! 1           | L, number of weight layers
+//! 2 .. 2+L    | layer widths n_0 .. n_L (input first, output last)
+//! 16          | input normalization, (offset, scale) per input:
+//!             |   x' = (x - offset) * scale
+//! 16 + 2 n_0  | output normalization, (offset, scale) per output:
+//!             |   y = y' * scale + offset
+//! then        | for each layer l: weights n_{l+1} x n_l row-major (one row
+//!             | per output unit), then n_{l+1} biases
+//! ```
+//!
+//! Hidden layers use tanh; the output layer is linear. No layer may be
+//! wider than [`MLP_MAX_WIDTH`], the shader's scratch array size.
+
+use std::f64::consts::TAU;
+
+use crate::metric::Kerr;
+
+use super::deflection::{trace_deflection, DeflectionLutSpec};
+use super::Rng;
+
+/// Format tag in the first slot of the weight buffer.
+pub const MLP_VERSION: f32 = 1.0;
+
+/// Floats reserved for the buffer header (version, depth, widths).
+pub const MLP_HEADER_LEN: usize = 16;
+
+/// Widest layer the shader can evaluate; `MAX_WIDTH` in `mlp.wgsl`.
+pub const MLP_MAX_WIDTH: usize = 32;
+
+/// Inputs of the deflection surrogate.
+pub const SURROGATE_INPUTS: usize = 5;
+
+/// Outputs of the deflection surrogate.
+pub const SURROGATE_OUTPUTS: usize = 2;
+
+/// Rays traced per requested sample before giving up on the domain.
+const ATTEMPTS_PER_SAMPLE: usize = 16;
+
+/// Fully connected network with tanh hidden layers and a linear output,
+/// plus affine input and output normalization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mlp {
+    /// Layer widths, input first.
+    pub sizes: Vec<usize>,
+    /// Weights and biases, layer by layer, in buffer order.
+    pub params: Vec<f64>,
+    /// (offset, scale) per input.
+    pub input_norm: Vec<(f64, f64)>,
+    /// (offset, scale) per output.
+    pub output_norm: Vec<(f64, f64)>,
+}
+
+impl Mlp {
+    /// Network with Xavier-uniform weights and zero biases, reproducible
+    /// from `seed`. Normalizations start as the identity.
+    ///
+    /// Panics unless every width lies in 1..=[`MLP_MAX_WIDTH`].
+    pub fn new(sizes: &[usize], seed: u64) -> Self {
+        assert!(sizes.len() >= 2, "need input and output widths");
+        assert!(sizes.len() <= MLP_HEADER_LEN - 2, "too many layers");
+        assert!(
+            sizes.iter().all(|n| (1..=MLP_MAX_WIDTH).contains(n)),
+            "layer widths must lie in 1..={MLP_MAX_WIDTH} for the shader"
+        );
+        let mut rng = Rng::new(seed);
+        let mut params = Vec::new();
+        for pair in sizes.windows(2) {
+            let (fan_in, fan_out) = (pair[0], pair[1]);
+            let limit = (6.0 / (fan_in + fan_out) as f64).sqrt();
+            params.extend((0..fan_in * fan_out).map(|_| limit * (2.0 * rng.next() - 1.0)));
+            params.extend(std::iter::repeat_n(0.0, fan_out));
+        }
+        Self {
+            sizes: sizes.to_vec(),
+            params,
+            input_norm: vec![(0.0, 1.0); sizes[0]],
+            output_norm: vec![(0.0, 1.0); sizes[sizes.len() - 1]],
+        }
+    }
+
+    /// Number of weight layers.
+    pub fn layers(&self) -> usize {
+        self.sizes.len() - 1
+    }
+
+    /// Network output for raw inputs `x`, in physical units.
+    pub fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let y = self.forward(&self.normalize(x)).pop().unwrap_or_default();
+        y.iter()
+            .zip(&self.output_norm)
+            .map(|(y, (offset, scale))| y * scale + offset)
+            .collect()
+    }
+
+    /// Inputs mapped through the input normalization.
+    fn normalize(&self, x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .zip(&self.input_norm)
+            .map(|(x, (offset, scale))| (x - offset) * scale)
+            .collect()
+    }
+
+    /// Activations of every layer for normalized input `x`, input first;
+    /// the last entry is the normalized output.
+    fn forward(&self, x: &[f64]) -> Vec<Vec<f64>> {
+        let mut activations = vec![x.to_vec()];
+        let mut offset = 0;
+        for l in 0..self.layers() {
+            let (n_in, n_out) = (self.sizes[l], self.sizes[l + 1]);
+            let (w, b) = self.params[offset..offset + n_in * n_out + n_out].split_at(n_in * n_out);
+            let input = &activations[l];
+            let last = l + 1 == self.layers();
+            let output = (0..n_out)
+                .map(|o| {
+                    let z = b[o] + dot(&w[o * n_in..(o + 1) * n_in], input);
+                    if last {
+                        z
+                    } else {
+                        z.tanh()
+                    }
+                })
+                .collect();
+            activations.push(output);
+            offset += n_in * n_out + n_out;
+        }
+        activations
+    }
+
+    /// Accumulate the gradient of 0.5 |y - target|^2 (normalized units)
+    /// into `grad`, returning the loss.
+    fn backward(&self, x: &[f64], target: &[f64], grad: &mut [f64]) -> f64 {
+        let activations = self.forward(x);
+        let output = &activations[self.layers()];
+        let mut delta: Vec<f64> = output.iter().zip(target).map(|(y, t)| y - t).collect();
+        let loss = 0.5 * delta.iter().map(|d| d * d).sum::<f64>();
+
+        let mut end = self.params.len();
+        for l in (0..self.layers()).rev() {
+            let (n_in, n_out) = (self.sizes[l], self.sizes[l + 1]);
+            let start = end - n_in * n_out - n_out;
+            let input = &activations[l];
+            let (gw, gb) = grad[start..end].split_at_mut(n_in * n_out);
+            for o in 0..n_out {
+                gb[o] += delta[o];
+                for (g, x) in gw[o * n_in..(o + 1) * n_in].iter_mut().zip(input) {
+                    *g += delta[o] * x;
+                }
+            }
+            if l > 0 {
+                // Through the weights, then the tanh of the layer below.
+                let w = &self.params[start..start + n_in * n_out];
+                delta = (0..n_in)
+                    .map(|i| {
+                        let back: f64 = (0..n_out).map(|o| w[o * n_in + i] * delta[o]).sum();
+                        back * (1.0 - input[i] * input[i])
+                    })
+                    .collect();
+            }
+            end = start;
+        }
+        loss
+    }
+
+    /// Weight buffer in the documented shader layout.
+    pub fn to_buffer(&self) -> Vec<f32> {
+        let mut buffer = vec![0.0; MLP_HEADER_LEN];
+        buffer[0] = MLP_VERSION;
+        buffer[1] = self.layers() as f32;
+        for (slot, &n) in buffer[2..].iter_mut().zip(&self.sizes) {
+            *slot = n as f32;
+        }
+        for &(offset, scale) in self.input_norm.iter().chain(&self.output_norm) {
+            buffer.push(offset as f32);
+            buffer.push(scale as f32);
+        }
+        buffer.extend(self.params.iter().map(|&p| p as f32));
+        buffer
+    }
+}
+
+/// Evaluate a weight buffer exactly as the shader does, in f32.
+///
+/// Returns `None` if the buffer is malformed or `x` has the wrong length.
+pub fn evaluate_buffer(buffer: &[f32], x: &[f32]) -> Option<Vec<f32>> {
+    if buffer.len() < MLP_HEADER_LEN || buffer[0] != MLP_VERSION {
+        return None;
+    }
+    let layers = buffer[1] as usize;
+    if layers == 0 || layers > MLP_HEADER_LEN - 3 {
+        return None;
+    }
+    let sizes: Vec<usize> = buffer[2..3 + layers].iter().map(|&n| n as usize).collect();
+    let (n_in, n_out) = (sizes[0], sizes[layers]);
+    if x.len() != n_in || sizes.iter().any(|n| !(1..=MLP_MAX_WIDTH).contains(n)) {
+        return None;
+    }
+    // Check the whole buffer is there before reading any of it.
+    let norm = MLP_HEADER_LEN;
+    let params = sizes.windows(2).try_fold(0usize, |total, pair| {
+        let layer = pair[0].checked_mul(pair[1])?.checked_add(pair[1])?;
+        total.checked_add(layer)
+    })?;
+    let len = (n_in + n_out)
+        .checked_mul(2)
+        .and_then(|n| n.checked_add(norm + params))?;
+    if buffer.len() < len {
+        return None;
+    }
+    let mut cursor = norm + 2 * (n_in + n_out);
+
+    let mut a: Vec<f32> = (0..n_in)
+        .map(|i| (x[i] - buffer[norm + 2 * i]) * buffer[norm + 2 * i + 1])
+        .collect();
+    for l in 0..layers {
+        let (rows, cols) = (sizes[l + 1], sizes[l]);
+        let (w, b) = buffer[cursor..cursor + rows * cols + rows].split_at(rows * cols);
+        a = (0..rows)
+            .map(|o| {
+                let z = b[o] + (0..cols).map(|i| w[o * cols + i] * a[i]).sum::<f32>();
+                if l + 1 == layers {
+                    z
+                } else {
+                    z.tanh()
+                }
+            })
+            .collect();
+        cursor += rows * cols + rows;
+    }
+    let out = norm + 2 * n_in;
+    Some(
+        (0..n_out)
+            .map(|o| a[o] * buffer[out + 2 * o + 1] + buffer[out + 2 * o])
+            .collect(),
+    )
+}
+
+/// Adam optimizer state (Kingma & Ba 2015).
+#[derive(Debug, Clone)]
+pub struct Adam {
+    /// Step size.
+    pub learning_rate: f64,
+    /// Decay of the first moment estimate.
+    pub beta1: f64,
+    /// Decay of the second moment estimate.
+    pub beta2: f64,
+    /// Denominator regularizer.
+    pub epsilon: f64,
+    m: Vec<f64>,
+    v: Vec<f64>,
+    t: i32,
+}
+
+impl Adam {
+    /// Optimizer for `n` parameters with the usual defaults.
+    pub fn new(n: usize, learning_rate: f64) -> Self {
+        Self {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            m: vec![0.0; n],
+            v: vec![0.0; n],
+            t: 0,
+        }
+    }
+
+    /// Apply one update to `params` with gradient `grad`.
+    pub fn step(&mut self, params: &mut [f64], grad: &[f64]) {
+        self.t += 1;
+        let c1 = 1.0 - self.beta1.powi(self.t);
+        let c2 = 1.0 - self.beta2.powi(self.t);
+        for (((p, g), m), v) in params
+            .iter_mut()
+            .zip(grad)
+            .zip(&mut self.m)
+            .zip(&mut self.v)
+        {
+            *m = self.beta1 * *m + (1.0 - self.beta1) * g;
+            *v = self.beta2 * *v + (1.0 - self.beta2) * g * g;
+            *p -= self.learning_rate * (*m / c1) / ((*v / c2).sqrt() + self.epsilon);
+        }
+    }
+}
+
+/// Sampling domain, network shape and optimizer settings.
+#[derive(Debug, Clone)]
+pub struct SurrogateSpec {
+    /// Impact parameter range in M. The lower end should lie outside the
+    /// shadow for every spin and inclination.
+    pub impact_range: (f64, f64),
+    /// Largest spin; spins are drawn from [0, max_spin].
+    pub max_spin: f64,
+    /// Inclination range in radians.
+    pub inclination_range: (f64, f64),
+    /// Hidden layer widths, each at most [`MLP_MAX_WIDTH`].
+    pub hidden: Vec<usize>,
+    /// Ground-truth rays used for training.
+    pub training_samples: usize,
+    /// Held-out rays used for validation.
+    pub validation_samples: usize,
+    /// Minibatch size.
+    pub batch_size: usize,
+    /// Adam step size.
+    pub learning_rate: f64,
+    /// Seed for sampling and initialization.
+    pub seed: u64,
+    /// Observer distance and integrator settings for the ground truth.
+    pub tracing: DeflectionLutSpec,
+}
+
+impl Default for SurrogateSpec {
+    fn default() -> Self {
+        Self {
+            impact_range: (8.0, 40.0),
+            max_spin: 0.998,
+            inclination_range: (1f64.to_radians(), 179f64.to_radians()),
+            hidden: vec![16, 16, 16],
+            training_samples: 2048,
+            validation_samples: 256,
+            batch_size: 32,
+            learning_rate: 3e-3,
+            seed: 1,
+            tracing: DeflectionLutSpec::default(),
+        }
+    }
+}
+
+/// One ground-truth ray: network inputs and physical outputs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurrogateSample {
+    /// (M/b, cos psi, sin psi, a, cos i).
+    pub input: [f64; SURROGATE_INPUTS],
+    /// (deflection, time delay).
+    pub output: [f64; SURROGATE_OUTPUTS],
+}
+
+/// Validation error in physical units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidationError {
+    /// Root-mean-square error of (deflection, time delay).
+    pub rms: [f64; SURROGATE_OUTPUTS],
+    /// Largest absolute error of (deflection, time delay).
+    pub max: [f64; SURROGATE_OUTPUTS],
+    /// RMS deflection error relative to the RMS deflection.
+    pub relative_deflection: f64,
+}
+
+/// Trains an [`Mlp`] on exact geodesics.
+#[derive(Debug, Clone)]
+pub struct SurrogateTrainer {
+    /// Settings.
+    pub spec: SurrogateSpec,
+    /// The network being trained.
+    pub mlp: Mlp,
+    optimizer: Adam,
+    training: Vec<SurrogateSample>,
+    validation: Vec<SurrogateSample>,
+    rng: Rng,
+    epoch: usize,
+    loss: f64,
+}
+
+impl SurrogateTrainer {
+    /// Trace the training and validation rays and set up the network, with
+    /// normalizations fitted to the training set.
+    ///
+    /// Each set stops short after 16 traced rays per requested
+    /// sample. Returns `None` if a set comes out empty, as when
+    /// `impact_range` lies inside the shadow and no ray escapes.
+    pub fn new(spec: SurrogateSpec) -> Option<Self> {
+        let mut rng = Rng::new(spec.seed);
+        let mut draw = |n: usize| -> Vec<SurrogateSample> {
+            let mut samples = Vec::with_capacity(n);
+            for _ in 0..n * ATTEMPTS_PER_SAMPLE {
+                if samples.len() == n {
+                    break;
+                }
+                if let Some(sample) = ground_truth(&spec, &mut rng) {
+                    samples.push(sample);
+                }
+            }
+            samples
+        };
+        let training = draw(spec.training_samples.max(1));
+        let validation = draw(spec.validation_samples.max(1));
+        if training.is_empty() || validation.is_empty() {
+            return None;
+        }
+
+        let mut sizes = vec![SURROGATE_INPUTS];
+        sizes.extend(&spec.hidden);
+        sizes.push(SURROGATE_OUTPUTS);
+        let mut mlp = Mlp::new(&sizes, spec.seed.wrapping_add(1));
+        mlp.input_norm = (0..SURROGATE_INPUTS)
+            .map(|k| range_norm(training.iter().map(|s| s.input[k])))
+            .collect();
+        mlp.output_norm = (0..SURROGATE_OUTPUTS)
+            .map(|k| standard_norm(training.iter().map(|s| s.output[k])))
+            .collect();
+
+        let optimizer = Adam::new(mlp.params.len(), spec.learning_rate);
+        Some(Self {
+            rng: Rng::new(spec.seed.wrapping_add(2)),
+            spec,
+            mlp,
+            optimizer,
+            training,
+            validation,
+            epoch: 0,
+            loss: f64::NAN,
+        })
+    }
+
+    /// Completed epochs.
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+
+    /// Mean training loss of the last epoch (normalized units).
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+
+    /// One pass over the shuffled training set in minibatches. Returns the
+    /// mean training loss.
+    pub fn train_epoch(&mut self) -> f64 {
+        let n = self.training.len();
+        // Fisher-Yates shuffle.
+        for i in (1..n).rev() {
+            let j = (self.rng.next() * (i + 1) as f64) as usize;
+            self.training.swap(i, j.min(i));
+        }
+
+        let mut grad = vec![0.0; self.mlp.params.len()];
+        let mut total = 0.0;
+        for batch in self.training.chunks(self.spec.batch_size.max(1)) {
+            grad.iter_mut().for_each(|g| *g = 0.0);
+            for sample in batch {
+                let x = self.mlp.normalize(&sample.input);
+                let target: Vec<f64> = sample
+                    .output
+                    .iter()
+                    .zip(&self.mlp.output_norm)
+                    .map(|(y, (offset, scale))| (y - offset) / scale)
+                    .collect();
+                total += self.mlp.backward(&x, &target, &mut grad);
+            }
+            let inv = 1.0 / batch.len() as f64;
+            grad.iter_mut().for_each(|g| *g *= inv);
+            self.optimizer.step(&mut self.mlp.params, &grad);
+        }
+        self.epoch += 1;
+        self.loss = total / n as f64;
+        self.loss
+    }
+
+    /// Error of the network on the held-out rays.
+    pub fn validate(&self) -> ValidationError {
+        let mut sq = [0.0; SURROGATE_OUTPUTS];
+        let mut max = [0.0f64; SURROGATE_OUTPUTS];
+        let mut deflection_sq = 0.0;
+        for sample in &self.validation {
+            let y = self.mlp.evaluate(&sample.input);
+            for k in 0..SURROGATE_OUTPUTS {
+                let e = y[k] - sample.output[k];
+                sq[k] += e * e;
+                max[k] = max[k].max(e.abs());
+            }
+            deflection_sq += sample.output[0] * sample.output[0];
+        }
+        let n = self.validation.len() as f64;
+        let rms = sq.map(|s| (s / n).sqrt());
+        ValidationError {
+            rms,
+            max,
+            relative_deflection: rms[0] / (deflection_sq / n).sqrt(),
+        }
+    }
+
+    /// The trained network in the shader buffer layout.
+    pub fn weights(&self) -> Vec<f32> {
+        self.mlp.to_buffer()
+    }
+}
+
+/// Network inputs for a ray.
+pub fn surrogate_input(impact: f64, angle: f64, spin: f64, inclination: f64) -> [f64; 5] {
+    [
+        1.0 / impact,
+        angle.cos(),
+        angle.sin(),
+        spin,
+        inclination.cos(),
+    ]
+}
+
+/// Trace one random ray of the sampling domain, or `None` if it did not
+/// escape.
+fn ground_truth(spec: &SurrogateSpec, rng: &mut Rng) -> Option<SurrogateSample> {
+    // Uniform in M/b so the strongly bent rays near the shadow are well
+    // represented.
+    let (b0, b1) = spec.impact_range;
+    let impact = 1.0 / (1.0 / b0 + (1.0 / b1 - 1.0 / b0) * rng.next());
+    let angle = TAU * rng.next();
+    let spin = spec.max_spin * rng.next();
+    let (i0, i1) = spec.inclination_range;
+    let inclination = (i0.cos() + (i1.cos() - i0.cos()) * rng.next()).acos();
+
+    let bh = Kerr::new(1.0, spin);
+    let truth = trace_deflection(&bh, None, inclination, impact, angle, &spec.tracing);
+    (truth.deflection.is_finite() && truth.time_delay.is_finite()).then(|| SurrogateSample {
+        input: surrogate_input(impact, angle, spin, inclination),
+        output: [truth.deflection, truth.time_delay],
+    })
+}
+
+/// (offset, scale) mapping the values' range onto [-1, 1].
+fn range_norm(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (lo, hi) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    let half = 0.5 * (hi - lo);
+    (0.5 * (hi + lo), if half > 0.0 { 1.0 / half } else { 1.0 })
+}
+
+/// (mean, standard deviation) of the values.
+fn standard_norm(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let values: Vec<f64> = values.collect();
+    let n = values.len().max(1) as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, if var > 0.0 { var.sqrt() } else { 1.0 })
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn test_backprop_matches_finite_differences() {
+        let mut mlp = Mlp::new(&[3, 4, 2], 3);
+        mlp.params
+            .iter_mut()
+            .enumerate()
+            .for_each(|(k, p)| *p += 0.01 * k as f64);
+        let (x, target) = ([0.3, -0.5, 0.8], [0.2, -0.1]);
+        let mut grad = vec![0.0; mlp.params.len()];
+        mlp.backward(&x, &target, &mut grad);
+
+        let h = 1e-6;
+        for k in 0..mlp.params.len() {
+            let mut plus = mlp.clone();
+            let mut minus = mlp.clone();
+            plus.params[k] += h;
+            minus.params[k] -= h;
+            let mut scratch = vec![0.0; mlp.params.len()];
+            let numeric = (plus.backward(&x, &target, &mut scratch)
+                - minus.backward(&x, &target, &mut scratch))
+                / (2.0 * h);
+            assert!((numeric - grad[k]).abs() < 1e-7, "param {k}");
+        }
+    }
+
+    #[test]
+    fn test_buffer_evaluates_like_the_network() {
+        let mut mlp = Mlp::new(&[5, 8, 8, 2], 11);
+        mlp.input_norm[0] = (0.05, 10.0);
+        mlp.output_norm[1] = (20.0, 3.0);
+        let x = [0.07, 0.6, 0.8, 0.5, 0.3];
+        let buffer = mlp.to_buffer();
+        assert_eq!(buffer.len(), MLP_HEADER_LEN + 2 * 7 + mlp.params.len());
+        assert_eq!(buffer[1..6], [3.0, 5.0, 8.0, 8.0, 2.0]);
+
+        let expected = mlp.evaluate(&x);
+        let x32 = x.map(|v| v as f32);
+        let got = evaluate_buffer(&buffer, &x32).unwrap();
+        for (a, b) in got.iter().zip(&expected) {
+            assert!((*a as f64 - b).abs() < 1e-4 * b.abs().max(1.0));
+        }
+        assert!(evaluate_buffer(&buffer, &x32[..4]).is_none());
+    }
+
+    #[test]
+    fn test_malformed_buffer_is_rejected() {
+        let buffer = Mlp::new(&[5, 8, 2], 4).to_buffer();
+        let x = [0.07, 0.6, 0.8, 0.5, 0.3];
+        assert!(evaluate_buffer(&buffer, &x).is_some());
+        for len in 0..buffer.len() {
+            assert!(evaluate_buffer(&buffer[..len], &x).is_none(), "{len}");
+        }
+        for (slot, value) in [
+            (1, 0.0),
+            (1, 14.0),
+            (1, 1e30),
+            (3, 1e30),
+            (3, -1.0),
+            (3, 33.0),
+        ] {
+            let mut corrupt = buffer.clone();
+            corrupt[slot] = value;
+            assert!(evaluate_buffer(&corrupt, &x).is_none(), "{slot} = {value}");
+        }
+    }
+
+    #[test]
+    fn test_training_learns_the_deflection() {
+        let mut trainer = SurrogateTrainer::new(SurrogateSpec {
+            hidden: vec![16, 16],
+            training_samples: 256,
+            validation_samples: 64,
+            batch_size: 16,
+            learning_rate: 1e-2,
+            ..SurrogateSpec::default()
+        })
+        .unwrap();
+        let before = trainer.validate();
+        let first = trainer.train_epoch();
+        for _ in 0..150 {
+            trainer.train_epoch();
+        }
+        let after = trainer.validate();
+        assert!(
+            trainer.loss() < 0.1 * first,
+            "loss {first} -> {}",
+            trainer.loss()
+        );
+        assert!(after.rms[0] < 0.2 * before.rms[0]);
+        assert!(
+            after.relative_deflection < 0.05,
+            "relative deflection error {}",
+            after.relative_deflection
+        );
+        assert_eq!(trainer.epoch(), 151);
+    }
+
+    #[test]
+    #[should_panic(expected = "layer widths")]
+    fn test_mlp_rejects_layers_wider_than_the_shader() {
+        Mlp::new(&[5, MLP_MAX_WIDTH + 1, 2], 1);
+    }
+
+    #[test]
+    fn test_trainer_gives_up_inside_the_shadow() {
+        let spec = SurrogateSpec {
+            impact_range: (1.0, 2.0),
+            training_samples: 8,
+            validation_samples: 4,
+            ..SurrogateSpec::default()
+        };
+        assert!(SurrogateTrainer::new(spec).is_none());
+    }
+}
@@ -0,0 +1,395 @@
+//! Progressive tiled rendering scheduler.
+//!
+//! The image is cut into square tiles and rendered in several passes, from
+//! coarse to fine. Pass k traces one ray per `stride x stride` block (the
+//! stride halves each pass, down to 1) and skips pixels already traced by
+//! earlier passes, so every pixel is traced exactly once while a blocky
+//! preview of the whole frame appears after the first pass.
+//!
+//! Within a pass, tiles are handed out in a [`TileOrder`]: row-major,
+//! spiral or centre-out from the middle of the image, or by a
+//! [`PriorityMap`] such as a gaze estimate. The order of the remaining
+//! tiles can be changed mid-render with [`TileScheduler::set_order`].
+
+use std::collections::VecDeque;
+
+/// A rectangular region of the image to trace in one pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    /// Left edge in pixels.
+    pub x: u32,
+    /// Top edge in pixels.
+    pub y: u32,
+    /// Width in pixels (clipped to the image).
+    pub width: u32,
+    /// Height in pixels (clipped to the image).
+    pub height: u32,
+    /// Scheduling priority in the tile's pass; higher runs first.
+    pub priority: f32,
+    /// Refinement pass, starting at 0.
+    pub pass: u32,
+    /// One ray is traced per `stride x stride` block in this pass.
+    pub stride: u32,
+    /// Stride of the previous pass, or 0 in the first pass.
+    pub previous_stride: u32,
+}
+
+impl Tile {
+    /// Pixels traced in this pass, as absolute (column, row) coordinates.
+    ///
+    /// Each sample stands for the `stride x stride` block whose top-left
+    /// corner it is. Pixels already traced in the previous pass are skipped.
+    pub fn samples(&self) -> impl Iterator<Item = (u32, u32)> {
+        let (s, prev) = (self.stride, self.previous_stride);
+        let i0 = self.x.div_ceil(s) * s;
+        let j0 = self.y.div_ceil(s) * s;
+        let (i1, j1) = (self.x + self.width, self.y + self.height);
+        (j0..j1)
+            .step_by(s as usize)
+            .flat_map(move |j| (i0..i1).step_by(s as usize).map(move |i| (i, j)))
+            .filter(move |&(i, j)| prev == 0 || i % prev != 0 || j % prev != 0)
+    }
+}
+
+/// Coarse per-region priorities over the image, sampled at tile centres.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityMap {
+    /// Map width in cells.
+    pub width: u32,
+    /// Map height in cells.
+    pub height: u32,
+    /// Row-major priorities; higher is rendered sooner.
+    pub values: Vec<f32>,
+}
+
+impl PriorityMap {
+    /// Map from row-major `values`.
+    pub fn new(width: u32, height: u32, values: Vec<f32>) -> Self {
+        assert_eq!(values.len(), (width * height) as usize);
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// Map filled by `f(u, v)` at cell centres, with u, v in [0, 1].
+    pub fn from_fn(width: u32, height: u32, f: impl Fn(f32, f32) -> f32) -> Self {
+        let values = (0..height)
+            .flat_map(|j| (0..width).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                f(
+                    (i as f32 + 0.5) / width as f32,
+                    (j as f32 + 0.5) / height as f32,
+                )
+            })
+            .collect();
+        Self::new(width, height, values)
+    }
+
+    /// Gaussian fall-off around a gaze point (u, v) with width `sigma`, all
+    /// in normalized image coordinates.
+    pub fn gaze(u: f32, v: f32, sigma: f32) -> Self {
+        Self::from_fn(64, 64, |x, y| {
+            let d2 = (x - u).powi(2) + (y - v).powi(2);
+            (-0.5 * d2 / (sigma * sigma)).exp()
+        })
+    }
+
+    /// Nearest-cell priority at normalized image coordinate (u, v).
+    pub fn sample(&self, u: f32, v: f32) -> f32 {
+        let i = ((u * self.width as f32) as u32).min(self.width.saturating_sub(1));
+        let j = ((v * self.height as f32) as u32).min(self.height.saturating_sub(1));
+        self.values[(j * self.width + i) as usize]
+    }
+}
+
+/// Order in which tiles of one pass are handed out.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TileOrder {
+    /// Left to right, top to bottom.
+    #[default]
+    RowMajor,
+    /// Square spiral outward from the centre tile.
+    Spiral,
+    /// By distance of the tile centre from the image centre.
+    CenterOut,
+    /// Highest map priority first.
+    Priority(PriorityMap),
+}
+
+/// Hands out tiles pass by pass, coarse to fine.
+#[derive(Debug, Clone)]
+pub struct TileScheduler {
+    width: u32,
+    height: u32,
+    tile_size: u32,
+    passes: u32,
+    order: TileOrder,
+    queue: VecDeque<Tile>,
+    total: usize,
+}
+
+impl TileScheduler {
+    /// Single-pass, row-major scheduler over a `width x height` image.
+    pub fn new(width: u32, height: u32, tile_size: u32) -> Self {
+        let mut scheduler = Self {
+            width,
+            height,
+            tile_size: tile_size.max(1),
+            passes: 1,
+            order: TileOrder::RowMajor,
+            queue: VecDeque::new(),
+            total: 0,
+        };
+        scheduler.reset();
+        scheduler
+    }
+
+    /// Replace the tile order and restart.
+    pub fn with_order(mut self, order: TileOrder) -> Self {
+        self.order = order;
+        self.reset();
+        self
+    }
+
+    /// Use `passes` refinement passes (strides 2^(passes-1), ..., 2, 1) and restart.
+    pub fn with_passes(mut self, passes: u32) -> Self {
+        self.passes = passes.clamp(1, 16);
+        self.reset();
+        self
+    }
+
+    /// Number of refinement passes.
+    #[inline]
+    pub fn passes(&self) -> u32 {
+        self.passes
+    }
+
+    /// Stride traced in pass `pass`.
+    #[inline]
+    pub fn stride(&self, pass: u32) -> u32 {
+        1 << (self.passes - 1 - pass.min(self.passes - 1))
+    }
+
+    /// Next tile to render, or `None` when the image is complete.
+    pub fn pop_tile(&mut self) -> Option<Tile> {
+        self.queue.pop_front()
+    }
+
+    /// Tiles not yet handed out.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Tiles in all passes.
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Fraction of tiles handed out, in [0, 1].
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            1.0 - self.remaining() as f32 / self.total as f32
+        }
+    }
+
+    /// Refill the queue with every tile of every pass.
+    pub fn reset(&mut self) {
+        let cols = self.width.div_ceil(self.tile_size);
+        let rows = self.height.div_ceil(self.tile_size);
+        let priorities = self.grid_priorities(cols, rows);
+
+        let mut cells: Vec<usize> = (0..(cols * rows) as usize).collect();
+        cells.sort_by(|&a, &b| priorities[b].total_cmp(&priorities[a]));
+
+        self.queue.clear();
+        for pass in 0..self.passes {
+            let stride = self.stride(pass);
+            let previous_stride = if pass == 0 { 0 } else { self.stride(pass - 1) };
+            for &cell in &cells {
+                let (col, row) = (cell as u32 % cols, cell as u32 / cols);
+                let (x, y) = (col * self.tile_size, row * self.tile_size);
+                self.queue.push_back(Tile {
+                    x,
+                    y,
+                    width: self.tile_size.min(self.width - x),
+                    height: self.tile_size.min(self.height - y),
+                    priority: priorities[cell],
+                    pass,
+                    stride,
+                    previous_stride,
+                });
+            }
+        }
+        self.total = self.queue.len();
+    }
+
+    /// Change the order of the tiles still queued, e.g. to follow a moving
+    /// gaze point. Passes still complete in coarse-to-fine order.
+    pub fn set_order(&mut self, order: TileOrder) {
+        self.order = order;
+        let cols = self.width.div_ceil(self.tile_size);
+        let rows = self.height.div_ceil(self.tile_size);
+        let priorities = self.grid_priorities(cols, rows);
+
+        let mut pending: Vec<Tile> = self.queue.drain(..).collect();
+        for tile in &mut pending {
+            let cell = tile.y / self.tile_size * cols + tile.x / self.tile_size;
+            tile.priority = priorities[cell as usize];
+        }
+        pending.sort_by(|a, b| a.pass.cmp(&b.pass).then(b.priority.total_cmp(&a.priority)));
+        self.queue.extend(pending);
+    }
+
+    /// Priority of every grid cell, row-major, in (0, 1] for the built-in orders.
+    fn grid_priorities(&self, cols: u32, rows: u32) -> Vec<f32> {
+        let n = (cols * rows) as usize;
+        let rank = |index: usize| 1.0 - index as f32 / n as f32;
+        match &self.order {
+            TileOrder::RowMajor => (0..n).map(rank).collect(),
+            TileOrder::Spiral => {
+                let mut priorities = vec![0.0; n];
+                for (index, (col, row)) in spiral(cols, rows).enumerate() {
+                    priorities[(row * cols + col) as usize] = rank(index);
+                }
+                priorities
+            }
+            TileOrder::CenterOut => {
+                let (cx, cy) = (0.5 * self.width as f32, 0.5 * self.height as f32);
+                (0..n)
+                    .map(|cell| {
+                        let (x, y) = self.cell_centre(cell as u32 % cols, cell as u32 / cols);
+                        let d = ((x - cx).powi(2) + (y - cy).powi(2)).sqrt();
+                        1.0 / (1.0 + d / self.tile_size as f32)
+                    })
+                    .collect()
+            }
+            TileOrder::Priority(map) => (0..n)
+                .map(|cell| {
+                    let (x, y) = self.cell_centre(cell as u32 % cols, cell as u32 / cols);
+                    map.sample(x / self.width as f32, y / self.height as f32)
+                })
+                .collect(),
+        }
+    }
+
+    /// Pixel centre of grid cell (col, row), clipped to the image.
+    fn cell_centre(&self, col: u32, row: u32) -> (f32, f32) {
+        let (x, y) = (col * self.tile_size, row * self.tile_size);
+        let w = self.tile_size.min(self.width - x);
+        let h = self.tile_size.min(self.height - y);
+        (x as f32 + 0.5 * w as f32, y as f32 + 0.5 * h as f32)
+    }
+}
+
+impl Iterator for TileScheduler {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Tile> {
+        self.pop_tile()
+    }
+}
+
+/// Grid cells in a square spiral (right, down, left, up, ...) from the
+/// centre cell, skipping cells outside the grid.
+fn spiral(cols: u32, rows: u32) -> impl Iterator<Item = (u32, u32)> {
+    let total = (cols * rows) as usize;
+    let (mut col, mut row) = (((cols as i64) - 1) / 2, ((rows as i64) - 1) / 2);
+    let mut out = Vec::with_capacity(total);
+    let mut leg = 1;
+    let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+    let mut d = 0;
+    let inside = |c: i64, r: i64| c >= 0 && r >= 0 && c < cols as i64 && r < rows as i64;
+
+    if total > 0 {
+        out.push((col as u32, row as u32));
+    }
+    while out.len() < total {
+        for _ in 0..2 {
+            let (dc, dr) = directions[d % 4];
+            for _ in 0..leg {
+                col += dc;
+                row += dr;
+                if inside(col, row) {
+                    out.push((col as u32, row as u32));
+                }
+            }
+            d += 1;
+        }
+        leg += 1;
+    }
+    out.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passes_cover_every_pixel_once() {
+        let (w, h) = (37, 23);
+        for order in [TileOrder::RowMajor, TileOrder::Spiral, TileOrder::CenterOut] {
+            let scheduler = TileScheduler::new(w, h, 8).with_order(order).with_passes(3);
+            let mut count = vec![0u32; (w * h) as usize];
+            let mut last_pass = 0;
+            for tile in scheduler {
+                assert!(tile.pass >= last_pass, "passes must run coarse to fine");
+                last_pass = tile.pass;
+                for (i, j) in tile.samples() {
+                    assert!(i < w && j < h);
+                    count[(j * w + i) as usize] += 1;
+                }
+            }
+            assert!(count.iter().all(|&c| c == 1));
+        }
+    }
+
+    #[test]
+    fn test_spiral_and_centre_out_start_in_the_middle() {
+        for order in [TileOrder::Spiral, TileOrder::CenterOut] {
+            let mut scheduler = TileScheduler::new(80, 48, 16).with_order(order);
+            let first = scheduler.pop_tile().unwrap();
+            assert_eq!((first.x, first.y), (32, 16));
+        }
+
+        let tiles: Vec<Tile> = TileScheduler::new(100, 100, 10)
+            .with_order(TileOrder::CenterOut)
+            .collect();
+        let dist = |t: &Tile| {
+            let (x, y) = (t.x as f32 + 5.0 - 50.0, t.y as f32 + 5.0 - 50.0);
+            (x * x + y * y).sqrt()
+        };
+        assert!(tiles.windows(2).all(|w| dist(&w[0]) <= dist(&w[1]) + 1e-4));
+    }
+
+    #[test]
+    fn test_spiral_visits_every_cell() {
+        let mut cells: Vec<(u32, u32)> = spiral(5, 3).collect();
+        assert_eq!(cells[0], (2, 1));
+        assert_eq!(cells[1], (3, 1));
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), 15);
+    }
+
+    #[test]
+    fn test_gaze_priority_and_reordering() {
+        let mut scheduler = TileScheduler::new(64, 64, 16).with_passes(2);
+        scheduler.set_order(TileOrder::Priority(PriorityMap::gaze(0.9, 0.1, 0.1)));
+        let first = scheduler.pop_tile().unwrap();
+        assert_eq!((first.x, first.y, first.pass), (48, 0, 0));
+
+        // Move the gaze mid-render; the coarse pass still finishes first.
+        scheduler.set_order(TileOrder::Priority(PriorityMap::gaze(0.1, 0.9, 0.1)));
+        let next = scheduler.pop_tile().unwrap();
+        assert_eq!((next.x, next.y, next.pass), (0, 48, 0));
+        let passes: Vec<u32> = scheduler.map(|t| t.pass).collect();
+        assert_eq!(passes.len(), 30);
+        assert!(passes.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
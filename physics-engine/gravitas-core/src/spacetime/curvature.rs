@@ -1,7 +1,25 @@
 //! Curvature invariants for spacetime visualization.
 //!
 //! Curvature invariants are coordinate-independent measures of how strongly
-//! spacetime is curved at a given point.
+//! spacetime is curved at a given point. The generic functions here work for
+//! any [`Metric`] by contracting the numerical Riemann tensor from
+//! [`riemann_from_metric`]; the closed-form Kerr expressions are kept as
+//! fast paths and as references for testing.
+//!
+//! # References
+//!
+//! - Cherubini, Bini, Capozziello & Ruffini (2002). "Second order scalar
+//!   invariants of the Riemann tensor: applications to black hole spacetimes"
+//! - Stephani et al. (2003). "Exact Solutions of Einstein's Field Equations", ch. 4
+
+use crate::metric::Metric;
+use crate::tensor::{
+    levi_civita, raise_all, riemann_from_metric, MetricTensor4, Rank4, RiemannTensor,
+};
+use num_complex::Complex64;
+
+/// Relative finite-difference step used for the Riemann tensor.
+const DERIVATIVE_STEP: f64 = 1e-3;
 
 /// Kretschner scalar for the Kerr metric.
 ///
@@ -42,27 +60,345 @@ pub fn kretschner_schwarzschild(r: f64, mass: f64) -> f64 {
     48.0 * mass * mass / r.powi(6)
 }
 
-/// Generate a scalar field of curvature values for visualization.
+/// Selects a single scalar out of [`CurvatureInvariants`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invariant {
+    /// K = R_{abcd} R^{abcd}.
+    Kretschmann,
+    /// P = *R_{abcd} R^{abcd}.
+    ChernPontryagin,
+    /// R = g^{ab} R_{ab}.
+    RicciScalar,
+    /// Re(I).
+    WeylIReal,
+    /// Im(I).
+    WeylIImag,
+    /// Re(J).
+    WeylJReal,
+    /// Im(J).
+    WeylJImag,
+}
+
+/// The standard second- and third-order curvature scalars at a point.
+#[derive(Debug, Clone, Copy)]
+pub struct CurvatureInvariants {
+    /// Kretschmann scalar K = R_{abcd} R^{abcd}.
+    pub kretschmann: f64,
+    /// Chern-Pontryagin scalar P = *R_{abcd} R^{abcd}, with the left dual
+    /// *R_{abcd} = 1/2 epsilon_{abef} R^{ef}_{cd}. Odd under parity, so it
+    /// vanishes for static spacetimes.
+    pub chern_pontryagin: f64,
+    /// Ricci scalar R (zero in vacuum).
+    pub ricci_scalar: f64,
+    /// Weyl invariant I = (C_{abcd} C^{abcd} - i *C_{abcd} C^{abcd}) / 16.
+    ///
+    /// In Newman-Penrose form I = Psi0 Psi4 - 4 Psi1 Psi3 + 3 Psi2^2.
+    pub weyl_i: Complex64,
+    /// Weyl invariant J = (C_{ab}^{cd} C_{cd}^{ef} C_{ef}^{ab}
+    ///                     - i C_{ab}^{cd} C_{cd}^{ef} *C_{ef}^{ab}) / 96.
+    ///
+    /// In Newman-Penrose form J = det[[Psi4, Psi3, Psi2], [Psi3, Psi2, Psi1], [Psi2, Psi1, Psi0]].
+    pub weyl_j: Complex64,
+}
+
+impl CurvatureInvariants {
+    /// Return the selected invariant as a real number.
+    pub fn get(&self, which: Invariant) -> f64 {
+        match which {
+            Invariant::Kretschmann => self.kretschmann,
+            Invariant::ChernPontryagin => self.chern_pontryagin,
+            Invariant::RicciScalar => self.ricci_scalar,
+            Invariant::WeylIReal => self.weyl_i.re,
+            Invariant::WeylIImag => self.weyl_i.im,
+            Invariant::WeylJReal => self.weyl_j.re,
+            Invariant::WeylJImag => self.weyl_j.im,
+        }
+    }
+}
+
+/// Compute all curvature invariants of an arbitrary metric at (r, theta).
+///
+/// # Example
+///
+/// ```
+/// use gravitas::metric::Kerr;
+/// use gravitas::spacetime::curvature::{curvature_invariants, kretschner_kerr};
+///
+/// let bh = Kerr::new(1.0, 0.9);
+/// let inv = curvature_invariants(&bh, 4.0, 1.0);
+/// let exact = kretschner_kerr(4.0, 1.0, 1.0, 0.9);
+/// assert!((inv.kretschmann - exact).abs() < 1e-6 * exact.abs());
+/// ```
+pub fn curvature_invariants<M: Metric>(metric: &M, r: f64, theta: f64) -> CurvatureInvariants {
+    let riemann = riemann_from_metric(metric, r, theta, DERIVATIVE_STEP);
+    invariants_from_riemann(&riemann)
+}
+
+/// Contract a precomputed Riemann tensor into its curvature invariants.
+pub fn invariants_from_riemann(riemann: &RiemannTensor) -> CurvatureInvariants {
+    let g = &riemann.metric;
+    let g_inv = &riemann.inverse;
+    let eps = levi_civita(g);
+
+    let r_up = riemann.raised();
+    let r_dual = left_dual(&riemann.components, &eps, g_inv);
+
+    let kretschmann = full_contraction(&riemann.components, &r_up);
+    let chern_pontryagin = full_contraction(&r_dual, &r_up);
+
+    let weyl = riemann.weyl();
+    let weyl_up = raise_all(&weyl, g_inv);
+    let weyl_dual = left_dual(&weyl, &eps, g_inv);
+
+    let weyl_sq = full_contraction(&weyl, &weyl_up);
+    let weyl_dual_sq = full_contraction(&weyl_dual, &weyl_up);
+    let weyl_i = Complex64::new(weyl_sq, -weyl_dual_sq) / 16.0;
+
+    // Treat C_{ab}^{cd} as a 6x6 bivector map (here a 16x16 matrix over index pairs)
+    let c = bivector_matrix(&weyl, g_inv);
+    let c_dual = bivector_matrix(&weyl_dual, g_inv);
+    let c2 = mat16_mul(&c, &c);
+    let weyl_j = Complex64::new(
+        mat16_trace_product(&c2, &c),
+        -mat16_trace_product(&c2, &c_dual),
+    ) / 96.0;
+
+    CurvatureInvariants {
+        kretschmann,
+        chern_pontryagin,
+        ricci_scalar: riemann.ricci_scalar(),
+        weyl_i,
+        weyl_j,
+    }
+}
+
+/// Generate a scalar field of one curvature invariant for visualization.
 ///
-/// Returns Vec<(r, theta, K)> tuples.
-pub fn curvature_field(
-    mass: f64,
-    spin: f64,
+/// Works for any metric, so new spacetimes get curvature plots for free.
+/// Returns Vec<(r, theta, value)> tuples.
+pub fn curvature_field<M: Metric>(
+    metric: &M,
+    invariant: Invariant,
     r_min: f64,
     r_max: f64,
     n_radial: usize,
     n_polar: usize,
 ) -> Vec<(f64, f64, f64)> {
+    invariants_field(metric, r_min, r_max, n_radial, n_polar)
+        .into_iter()
+        .map(|(r, theta, inv)| (r, theta, inv.get(invariant)))
+        .collect()
+}
+
+/// Generate a field of all curvature invariants on an (r, theta) grid.
+///
+/// Returns Vec<(r, theta, invariants)> tuples.
+pub fn invariants_field<M: Metric>(
+    metric: &M,
+    r_min: f64,
+    r_max: f64,
+    n_radial: usize,
+    n_polar: usize,
+) -> Vec<(f64, f64, CurvatureInvariants)> {
     let mut field = Vec::with_capacity(n_radial * n_polar);
 
     for i in 0..n_radial {
-        let r = r_min + (r_max - r_min) * i as f64 / (n_radial - 1) as f64;
+        let r = r_min + (r_max - r_min) * i as f64 / (n_radial - 1).max(1) as f64;
         for j in 0..n_polar {
-            let theta = 0.1 + (std::f64::consts::PI - 0.2) * j as f64 / (n_polar - 1) as f64;
-            let k = kretschner_kerr(r, theta, mass, spin);
-            field.push((r, theta, k));
+            let theta = 0.1 + (std::f64::consts::PI - 0.2) * j as f64 / (n_polar - 1).max(1) as f64;
+            field.push((r, theta, curvature_invariants(metric, r, theta)));
         }
     }
 
     field
 }
+
+/// Left dual *T_{abcd} = 1/2 epsilon_{abef} T^{ef}_{cd}.
+#[allow(clippy::needless_range_loop)]
+fn left_dual(t: &Rank4, eps: &Rank4, g_inv: &MetricTensor4) -> Rank4 {
+    // T^{ef}_{cd}
+    let mut t_mixed = [[[[0.0; 4]; 4]; 4]; 4];
+    for e in 0..4 {
+        for f in 0..4 {
+            for c in 0..4 {
+                for d in 0..4 {
+                    let mut sum = 0.0;
+                    for a in 0..4 {
+                        for b in 0..4 {
+                            sum += g_inv.get(e, a) * g_inv.get(f, b) * t[a][b][c][d];
+                        }
+                    }
+                    t_mixed[e][f][c][d] = sum;
+                }
+            }
+        }
+    }
+
+    let mut dual = [[[[0.0; 4]; 4]; 4]; 4];
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                for d in 0..4 {
+                    let mut sum = 0.0;
+                    for e in 0..4 {
+                        for f in 0..4 {
+                            sum += eps[a][b][e][f] * t_mixed[e][f][c][d];
+                        }
+                    }
+                    dual[a][b][c][d] = 0.5 * sum;
+                }
+            }
+        }
+    }
+    dual
+}
+
+/// Sum over all indices of A_{abcd} B^{abcd}.
+fn full_contraction(lower: &Rank4, upper: &Rank4) -> f64 {
+    let mut sum = 0.0;
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                for d in 0..4 {
+                    sum += lower[a][b][c][d] * upper[a][b][c][d];
+                }
+            }
+        }
+    }
+    sum
+}
+
+/// Mixed tensor T_{ab}^{cd} as a matrix M[(a,b)][(c,d)].
+#[allow(clippy::needless_range_loop)]
+fn bivector_matrix(t: &Rank4, g_inv: &MetricTensor4) -> [[f64; 16]; 16] {
+    let mut m = [[0.0; 16]; 16];
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                for d in 0..4 {
+                    let mut sum = 0.0;
+                    for e in 0..4 {
+                        for f in 0..4 {
+                            sum += t[a][b][e][f] * g_inv.get(e, c) * g_inv.get(f, d);
+                        }
+                    }
+                    m[a * 4 + b][c * 4 + d] = sum;
+                }
+            }
+        }
+    }
+    m
+}
+
+fn mat16_mul(x: &[[f64; 16]; 16], y: &[[f64; 16]; 16]) -> [[f64; 16]; 16] {
+    let mut out = [[0.0; 16]; 16];
+    for i in 0..16 {
+        for k in 0..16 {
+            let xik = x[i][k];
+            if xik == 0.0 {
+                continue;
+            }
+            for j in 0..16 {
+                out[i][j] += xik * y[k][j];
+            }
+        }
+    }
+    out
+}
+
+/// tr(X Y) for 16x16 matrices.
+fn mat16_trace_product(x: &[[f64; 16]; 16], y: &[[f64; 16]; 16]) -> f64 {
+    let mut sum = 0.0;
+    for i in 0..16 {
+        for k in 0..16 {
+            sum += x[i][k] * y[k][i];
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Kerr, Minkowski, Schwarzschild};
+
+    /// Kinnersley-tetrad Psi2 = -M / (r - i a cos(theta))^3 for Kerr.
+    fn kerr_psi2(r: f64, theta: f64, mass: f64, a: f64) -> Complex64 {
+        -mass / Complex64::new(r, -a * theta.cos()).powi(3)
+    }
+
+    #[test]
+    fn test_generic_kretschmann_matches_closed_form() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            for &(r, theta) in &[(2.5, 0.4), (4.0, 1.2), (10.0, 2.0)] {
+                let inv = curvature_invariants(&bh, r, theta);
+                let exact = kretschner_kerr(r, theta, 1.0, 0.9);
+                assert!(
+                    (inv.kretschmann - exact).abs() < 1e-6 * exact.abs(),
+                    "K at (r={}, theta={}): generic={}, exact={}",
+                    r,
+                    theta,
+                    inv.kretschmann,
+                    exact
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_chern_pontryagin_kerr() {
+        // With epsilon_{t r theta phi} = +sqrt(-g):
+        // P = -96 M^2 a r cos(theta) (3r^2 - a^2 cos^2)(r^2 - 3 a^2 cos^2) / Sigma^6
+        let (m, a) = (1.0, 0.8);
+        let bh = Kerr::new(m, a);
+        let (r, theta) = (3.0_f64, 0.7_f64);
+        let x = a * theta.cos();
+        let sigma = r * r + x * x;
+        let exact =
+            -96.0 * m * m * r * x * (3.0 * r * r - x * x) * (r * r - 3.0 * x * x) / sigma.powi(6);
+        let inv = curvature_invariants(&bh, r, theta);
+        assert!(
+            (inv.chern_pontryagin - exact).abs() < 1e-6 * exact.abs(),
+            "P: generic={}, exact={}",
+            inv.chern_pontryagin,
+            exact
+        );
+
+        let static_inv = curvature_invariants(&Schwarzschild::new(1.0), r, theta);
+        assert!(static_inv.chern_pontryagin.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weyl_invariants_type_d() {
+        let (m, a) = (1.0, 0.7);
+        let bh = Kerr::new(m, a);
+        let (r, theta) = (3.5, 1.0);
+        let inv = curvature_invariants(&bh, r, theta);
+        let psi2 = kerr_psi2(r, theta, m, a);
+
+        let i_exact = 3.0 * psi2 * psi2;
+        let j_exact = -psi2 * psi2 * psi2;
+        assert!(
+            (inv.weyl_i - i_exact).norm() < 1e-6 * i_exact.norm(),
+            "I: generic={}, exact={}",
+            inv.weyl_i,
+            i_exact
+        );
+        assert!(
+            (inv.weyl_j - j_exact).norm() < 1e-6 * j_exact.norm(),
+            "J: generic={}, exact={}",
+            inv.weyl_j,
+            j_exact
+        );
+        assert!(inv.ricci_scalar.abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_flat_space_field_is_zero() {
+        let field = curvature_field(&Minkowski, Invariant::Kretschmann, 2.0, 10.0, 4, 3);
+        assert_eq!(field.len(), 12);
+        for &(_, _, k) in &field {
+            assert!(k.abs() < 1e-10, "Flat space should have K = 0, got {}", k);
+        }
+    }
+}
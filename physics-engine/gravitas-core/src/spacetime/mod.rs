@@ -7,3 +7,4 @@ pub mod curvature;
 pub mod embedding;
 pub mod frame_drag;
 pub mod lightcone;
+pub mod newman_penrose;
@@ -0,0 +1,540 @@
+//! Newman-Penrose null tetrads, Weyl scalars, and Petrov classification.
+//!
+//! A null tetrad (l, n, m, m_bar) with l.n = -1 and m.m_bar = 1 projects the
+//! Weyl tensor onto five complex scalars Psi0..Psi4. Their pattern under
+//! tetrad rotations determines the Petrov type of the spacetime at a point:
+//! Kerr is type D everywhere, while deformed or binary spacetimes are
+//! generically type I.
+//!
+//! # References
+//!
+//! - Newman & Penrose (1962). "An approach to gravitational radiation by a
+//!   method of spin coefficients"
+//! - Kinnersley, W. (1969). "Type D vacuum metrics"
+//! - d'Inverno & Russell-Clark (1971). "Classification of the Harrison metrics"
+//! - Stephani et al. (2003). "Exact Solutions of Einstein's Field Equations", ch. 9
+
+use crate::metric::kerr::CoordinateSystem;
+use crate::metric::{Kerr, Metric};
+use crate::tensor::{riemann_from_metric, MetricTensor4, Rank4};
+use crate::tetrad::Tetrad;
+use num_complex::Complex64;
+
+/// Relative finite-difference step used for the Riemann tensor.
+const DERIVATIVE_STEP: f64 = 1e-3;
+
+/// Default relative tolerance for the algebraic tests in [`petrov_type`].
+pub const DEFAULT_PETROV_TOLERANCE: f64 = 1e-5;
+
+/// Below this value of max |Psi_k| * r^2 the numerical Weyl tensor is
+/// indistinguishable from finite-difference noise and the point is type O.
+const FLAT_WEYL_THRESHOLD: f64 = 1e-8;
+
+/// A Newman-Penrose null tetrad (contravariant components).
+///
+/// Normalization (signature -+++): l.n = -1, m.m_bar = 1, all other
+/// products zero.
+#[derive(Debug, Clone, Copy)]
+pub struct NullTetrad {
+    /// Outgoing real null vector l^mu.
+    pub l: [f64; 4],
+    /// Ingoing real null vector n^mu.
+    pub n: [f64; 4],
+    /// Complex spacelike null vector m^mu.
+    pub m: [Complex64; 4],
+}
+
+impl NullTetrad {
+    /// The Kinnersley tetrad of a Kerr black hole.
+    ///
+    /// Its real null vectors are the two principal null directions, so only
+    /// Psi2 = -M / (r - i a cos(theta))^3 is non-zero.
+    ///
+    /// In Boyer-Lindquist coordinates:
+    ///   l = ((r^2 + a^2)/Delta, 1, 0, a/Delta)
+    ///   n = (r^2 + a^2, -Delta, 0, a) / (2 Sigma)
+    ///   m = (i a sin(theta), 0, 1, i/sin(theta)) / (sqrt(2) (r + i a cos(theta)))
+    ///
+    /// For a Kerr-Schild metric the same vectors are transformed to
+    /// Kerr-Schild components.
+    pub fn kinnersley(bh: &Kerr, r: f64, theta: f64) -> Self {
+        let a = bh.a();
+        let delta = bh.delta(r);
+        let sigma = bh.sigma(r, theta);
+        let sin_theta = theta.sin();
+        let ra2 = r * r + a * a;
+
+        let l = [ra2 / delta, 1.0, 0.0, a / delta];
+        let n = [
+            ra2 / (2.0 * sigma),
+            -delta / (2.0 * sigma),
+            0.0,
+            a / (2.0 * sigma),
+        ];
+
+        let norm = 1.0 / (std::f64::consts::SQRT_2 * Complex64::new(r, a * theta.cos()));
+        let i = Complex64::i();
+        let m = [
+            i * a * sin_theta * norm,
+            Complex64::new(0.0, 0.0),
+            norm,
+            i / sin_theta * norm,
+        ];
+
+        let tetrad = Self { l, n, m };
+        match bh.coordinate_system() {
+            CoordinateSystem::BoyerLindquist => tetrad,
+            CoordinateSystem::KerrSchild => Self {
+                l: bh.vector_bl_to_ks(r, tetrad.l),
+                n: bh.vector_bl_to_ks(r, tetrad.n),
+                // m has no radial component, so it is unchanged
+                m: tetrad.m,
+            },
+        }
+    }
+
+    /// A null tetrad for an arbitrary metric, built from the orthonormal
+    /// frame of the zero-angular-momentum observer ([`Tetrad::zamo`]).
+    ///
+    /// With that frame e_0..e_3, l = (e_0 + e_1)/sqrt(2), n = (e_0 - e_1)/sqrt(2) and
+    /// m = (e_2 + i e_3)/sqrt(2).
+    ///
+    /// This tetrad is generally not aligned with the principal null
+    /// directions, so all five Weyl scalars may be non-zero even for type D.
+    pub fn from_metric<M: Metric>(metric: &M, r: f64, theta: f64) -> Self {
+        let frame = Tetrad::zamo(metric, r, theta).legs;
+
+        let s = std::f64::consts::FRAC_1_SQRT_2;
+        let mut l = [0.0; 4];
+        let mut n = [0.0; 4];
+        let mut m = [Complex64::new(0.0, 0.0); 4];
+        for mu in 0..4 {
+            l[mu] = s * (frame[0][mu] + frame[1][mu]);
+            n[mu] = s * (frame[0][mu] - frame[1][mu]);
+            m[mu] = Complex64::new(s * frame[2][mu], s * frame[3][mu]);
+        }
+
+        Self { l, n, m }
+    }
+
+    /// Exchange l <-> n and m <-> m_bar. This maps Psi_k to Psi_{4-k}.
+    pub fn swap(&self) -> Self {
+        Self {
+            l: self.n,
+            n: self.l,
+            m: self.m.map(|c| c.conj()),
+        }
+    }
+
+    /// Null rotation about l with complex parameter c:
+    ///
+    ///   l -> l,  m -> m + c l,  n -> n + conj(c) m + c m_bar + |c|^2 l
+    pub fn null_rotation_about_l(&self, c: Complex64) -> Self {
+        let mut m = self.m;
+        let mut n = self.n;
+        for mu in 0..4 {
+            m[mu] = self.m[mu] + c * self.l[mu];
+            n[mu] = self.n[mu] + 2.0 * (c.conj() * self.m[mu]).re + c.norm_sqr() * self.l[mu];
+        }
+        Self { l: self.l, n, m }
+    }
+
+    /// Largest deviation from the NP normalization conditions.
+    pub fn normalization_error(&self, g: &MetricTensor4) -> f64 {
+        let l = self.l.map(Complex64::from);
+        let n = self.n.map(Complex64::from);
+        let m = self.m;
+        let m_bar = self.m.map(|c| c.conj());
+
+        let checks = [
+            (complex_dot(g, &l, &n), -1.0),
+            (complex_dot(g, &m, &m_bar), 1.0),
+            (complex_dot(g, &l, &l), 0.0),
+            (complex_dot(g, &n, &n), 0.0),
+            (complex_dot(g, &m, &m), 0.0),
+            (complex_dot(g, &l, &m), 0.0),
+            (complex_dot(g, &n, &m), 0.0),
+        ];
+        checks
+            .iter()
+            .map(|(value, expected)| (value - expected).norm())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// The five Weyl scalars in a given null tetrad.
+#[derive(Debug, Clone, Copy)]
+pub struct WeylScalars {
+    /// Psi0..Psi4, where
+    ///   Psi0 = C(l, m, l, m),       Psi1 = C(l, n, l, m),
+    ///   Psi2 = C(l, m, m_bar, n),   Psi3 = C(l, n, m_bar, n),
+    ///   Psi4 = C(n, m_bar, n, m_bar).
+    pub psi: [Complex64; 5],
+}
+
+impl WeylScalars {
+    /// I = Psi0 Psi4 - 4 Psi1 Psi3 + 3 Psi2^2 (tetrad-invariant).
+    pub fn invariant_i(&self) -> Complex64 {
+        let p = &self.psi;
+        p[0] * p[4] - 4.0 * p[1] * p[3] + 3.0 * p[2] * p[2]
+    }
+
+    /// J = det[[Psi4, Psi3, Psi2], [Psi3, Psi2, Psi1], [Psi2, Psi1, Psi0]] (tetrad-invariant).
+    pub fn invariant_j(&self) -> Complex64 {
+        let p = &self.psi;
+        p[4] * (p[2] * p[0] - p[1] * p[1]) - p[3] * (p[3] * p[0] - p[1] * p[2])
+            + p[2] * (p[3] * p[1] - p[2] * p[2])
+    }
+
+    /// Largest |Psi_k|, used as the scale for tolerance checks.
+    pub fn magnitude(&self) -> f64 {
+        self.psi.iter().map(|p| p.norm()).fold(0.0, f64::max)
+    }
+}
+
+/// Project a Weyl tensor C_{abcd} onto a null tetrad.
+pub fn weyl_scalars(weyl: &Rank4, tetrad: &NullTetrad) -> WeylScalars {
+    let l = tetrad.l.map(Complex64::from);
+    let n = tetrad.n.map(Complex64::from);
+    let m = tetrad.m;
+    let m_bar = tetrad.m.map(|c| c.conj());
+
+    WeylScalars {
+        psi: [
+            contract4(weyl, &l, &m, &l, &m),
+            contract4(weyl, &l, &n, &l, &m),
+            contract4(weyl, &l, &m, &m_bar, &n),
+            contract4(weyl, &l, &n, &m_bar, &n),
+            contract4(weyl, &n, &m_bar, &n, &m_bar),
+        ],
+    }
+}
+
+/// Petrov classification of the Weyl tensor at a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PetrovType {
+    /// Algebraically general: four distinct principal null directions.
+    I,
+    /// One double and two simple principal null directions.
+    II,
+    /// Two double principal null directions (Kerr, Schwarzschild).
+    D,
+    /// One triple and one simple principal null direction.
+    III,
+    /// One quadruple principal null direction (plane waves).
+    N,
+    /// Conformally flat: the Weyl tensor vanishes.
+    O,
+}
+
+/// Classify the Weyl tensor at a point, using any normalized null tetrad.
+///
+/// Follows the d'Inverno & Russell-Clark decision tree. With Psi4 != 0:
+///
+///   K = Psi1 Psi4^2 - 3 Psi4 Psi3 Psi2 + 2 Psi3^3
+///   L = Psi2 Psi4 - Psi3^2
+///   N = 12 L^2 - Psi4^2 I
+///
+/// - I^3 != 27 J^2 -> I
+/// - I = J = 0: K != 0 -> III, otherwise N
+/// - otherwise: K = N = 0 -> D, otherwise II
+///
+/// If Psi4 vanishes the tetrad is first swapped or null-rotated.
+///
+/// # Arguments
+/// - `tolerance` -- Relative tolerance for "equals zero" tests. Each
+///   expression is compared against the matching power of max |Psi_k|.
+pub fn petrov_type(weyl: &Rank4, tetrad: &NullTetrad, tolerance: f64) -> PetrovType {
+    let mut psi = weyl_scalars(weyl, tetrad);
+    let scale = psi.magnitude();
+    if scale < 1e-300 {
+        return PetrovType::O;
+    }
+    let zero = |value: Complex64, power: i32| value.norm() <= tolerance * scale.powi(power);
+
+    if zero(psi.psi[4], 1) {
+        if !zero(psi.psi[0], 1) {
+            psi = weyl_scalars(weyl, &tetrad.swap());
+        } else {
+            // Psi0 = Psi4 = 0: some null rotation about l makes Psi4 non-zero.
+            let candidates = [
+                Complex64::new(1.0, 0.0),
+                Complex64::new(0.0, 1.0),
+                Complex64::new(0.7, -0.3),
+            ];
+            for c in candidates {
+                let rotated = weyl_scalars(weyl, &tetrad.null_rotation_about_l(c));
+                if !zero(rotated.psi[4], 1) {
+                    psi = rotated;
+                    break;
+                }
+            }
+        }
+    }
+
+    let scale = psi.magnitude();
+    let zero = |value: Complex64, power: i32| value.norm() <= tolerance * scale.powi(power);
+    let p = &psi.psi;
+
+    let i = psi.invariant_i();
+    let j = psi.invariant_j();
+
+    if !zero(i * i * i - 27.0 * j * j, 6) {
+        return PetrovType::I;
+    }
+
+    let k = p[1] * p[4] * p[4] - 3.0 * p[4] * p[3] * p[2] + 2.0 * p[3] * p[3] * p[3];
+    let l = p[2] * p[4] - p[3] * p[3];
+    let n = 12.0 * l * l - p[4] * p[4] * i;
+
+    if zero(i, 2) && zero(j, 3) {
+        if zero(k, 3) {
+            PetrovType::N
+        } else {
+            PetrovType::III
+        }
+    } else if zero(k, 3) && zero(n, 4) {
+        PetrovType::D
+    } else {
+        PetrovType::II
+    }
+}
+
+/// Pointwise Petrov type of an arbitrary metric at (r, theta).
+///
+/// Uses the numerical Riemann tensor and the generic tetrad from
+/// [`NullTetrad::from_metric`], so it works for any [`Metric`].
+///
+/// # Example
+///
+/// ```
+/// use gravitas::metric::Kerr;
+/// use gravitas::spacetime::newman_penrose::{petrov_type_at, PetrovType};
+///
+/// let bh = Kerr::new(1.0, 0.9);
+/// assert_eq!(petrov_type_at(&bh, 4.0, 1.0), PetrovType::D);
+/// ```
+pub fn petrov_type_at<M: Metric>(metric: &M, r: f64, theta: f64) -> PetrovType {
+    let riemann = riemann_from_metric(metric, r, theta, DERIVATIVE_STEP);
+    let weyl = riemann.weyl();
+    let tetrad = NullTetrad::from_metric(metric, r, theta);
+    if weyl_scalars(&weyl, &tetrad).magnitude() * r * r < FLAT_WEYL_THRESHOLD {
+        return PetrovType::O;
+    }
+    petrov_type(&weyl, &tetrad, DEFAULT_PETROV_TOLERANCE)
+}
+
+/// Generate a field of Petrov types on an (r, theta) grid.
+///
+/// Returns Vec<(r, theta, type)> tuples.
+pub fn petrov_field<M: Metric>(
+    metric: &M,
+    r_min: f64,
+    r_max: f64,
+    n_radial: usize,
+    n_polar: usize,
+) -> Vec<(f64, f64, PetrovType)> {
+    let mut field = Vec::with_capacity(n_radial * n_polar);
+
+    for i in 0..n_radial {
+        let r = r_min + (r_max - r_min) * i as f64 / (n_radial - 1).max(1) as f64;
+        for j in 0..n_polar {
+            let theta = 0.1 + (std::f64::consts::PI - 0.2) * j as f64 / (n_polar - 1).max(1) as f64;
+            field.push((r, theta, petrov_type_at(metric, r, theta)));
+        }
+    }
+
+    field
+}
+
+/// g_{mu nu} u^mu v^nu for complex vectors (bilinear, no conjugation).
+#[allow(clippy::needless_range_loop)]
+fn complex_dot(g: &MetricTensor4, u: &[Complex64; 4], v: &[Complex64; 4]) -> Complex64 {
+    let mut sum = Complex64::new(0.0, 0.0);
+    for mu in 0..4 {
+        for nu in 0..4 {
+            sum += g.get(mu, nu) * u[mu] * v[nu];
+        }
+    }
+    sum
+}
+
+/// C_{abcd} w^a x^b y^c z^d.
+fn contract4(
+    t: &Rank4,
+    w: &[Complex64; 4],
+    x: &[Complex64; 4],
+    y: &[Complex64; 4],
+    z: &[Complex64; 4],
+) -> Complex64 {
+    let mut sum = Complex64::new(0.0, 0.0);
+    for a in 0..4 {
+        for b in 0..4 {
+            let wx = w[a] * x[b];
+            for c in 0..4 {
+                let wxy = wx * y[c];
+                for d in 0..4 {
+                    sum += t[a][b][c][d] * wxy * z[d];
+                }
+            }
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{HamiltonianDerivatives, Minkowski};
+    use crate::spacetime::curvature::curvature_invariants;
+
+    /// Kerr (BL) with g_tt rescaled by 1 + eps M^3 sin^2(theta) / r^3.
+    /// A toy non-Kerr deformation that breaks the type D structure.
+    struct DeformedKerr {
+        kerr: Kerr,
+        eps: f64,
+    }
+
+    impl DeformedKerr {
+        fn factor(&self, r: f64, theta: f64) -> f64 {
+            1.0 + self.eps * theta.sin().powi(2) / (r * r * r)
+        }
+    }
+
+    impl Metric for DeformedKerr {
+        fn covariant(&self, r: f64, theta: f64) -> MetricTensor4 {
+            let mut g = self.kerr.covariant(r, theta);
+            g.set(0, 0, g.get(0, 0) * self.factor(r, theta));
+            g
+        }
+
+        fn contravariant(&self, r: f64, theta: f64) -> MetricTensor4 {
+            // Invert the (t, phi) block; the (r, theta) block stays diagonal.
+            let g = self.covariant(r, theta);
+            let det = g.get(0, 0) * g.get(3, 3) - g.get(0, 3) * g.get(0, 3);
+            let mut inv = MetricTensor4::diagonal(
+                g.get(3, 3) / det,
+                1.0 / g.get(1, 1),
+                1.0 / g.get(2, 2),
+                g.get(0, 0) / det,
+            );
+            inv.set(0, 3, -g.get(0, 3) / det);
+            inv.set(3, 0, -g.get(0, 3) / det);
+            inv
+        }
+
+        fn hamiltonian_derivatives(
+            &self,
+            _r: f64,
+            _theta: f64,
+            _p: [f64; 4],
+        ) -> HamiltonianDerivatives {
+            HamiltonianDerivatives {
+                dh_dr: 0.0,
+                dh_dtheta: 0.0,
+            }
+        }
+
+        fn mass(&self) -> f64 {
+            self.kerr.mass()
+        }
+
+        fn spin(&self) -> f64 {
+            self.kerr.spin()
+        }
+    }
+
+    #[test]
+    fn test_kinnersley_tetrad_is_normalized() {
+        for bh in [Kerr::new(1.0, 0.8), Kerr::kerr_schild(1.0, 0.8)] {
+            let g = bh.covariant(4.0, 1.1);
+            let tetrad = NullTetrad::kinnersley(&bh, 4.0, 1.1);
+            assert!(
+                tetrad.normalization_error(&g) < 1e-12,
+                "Kinnersley tetrad normalization error {}",
+                tetrad.normalization_error(&g)
+            );
+        }
+    }
+
+    #[test]
+    fn test_generic_tetrad_is_normalized() {
+        let bh = Kerr::kerr_schild(1.0, 0.9);
+        let g = bh.covariant(1.5, 0.6);
+        let tetrad = NullTetrad::from_metric(&bh, 1.5, 0.6);
+        assert!(tetrad.normalization_error(&g) < 1e-12);
+    }
+
+    #[test]
+    fn test_kinnersley_only_psi2() {
+        let (m, a) = (1.0, 0.7);
+        let (r, theta) = (3.5_f64, 1.0_f64);
+        for bh in [Kerr::new(m, a), Kerr::kerr_schild(m, a)] {
+            let weyl = riemann_from_metric(&bh, r, theta, DERIVATIVE_STEP).weyl();
+            let psi = weyl_scalars(&weyl, &NullTetrad::kinnersley(&bh, r, theta)).psi;
+            let expected = -m / Complex64::new(r, -a * theta.cos()).powi(3);
+
+            assert!(
+                (psi[2] - expected).norm() < 1e-7,
+                "Psi2 = {}, expected {}",
+                psi[2],
+                expected
+            );
+            for k in [0, 1, 3, 4] {
+                assert!(psi[k].norm() < 1e-7, "Psi{} = {} should vanish", k, psi[k]);
+            }
+
+            // Psi0 = Psi4 = 0 here, so classification must null-rotate first.
+            let tetrad = NullTetrad::kinnersley(&bh, r, theta);
+            assert_eq!(
+                petrov_type(&weyl, &tetrad, DEFAULT_PETROV_TOLERANCE),
+                PetrovType::D
+            );
+        }
+    }
+
+    #[test]
+    fn test_np_invariants_match_tensor_invariants() {
+        let bh = Kerr::new(1.0, 0.9);
+        let (r, theta) = (2.8, 0.5);
+        let weyl = riemann_from_metric(&bh, r, theta, DERIVATIVE_STEP).weyl();
+        let psi = weyl_scalars(&weyl, &NullTetrad::from_metric(&bh, r, theta));
+        let inv = curvature_invariants(&bh, r, theta);
+
+        assert!((psi.invariant_i() - inv.weyl_i).norm() < 1e-6 * inv.weyl_i.norm());
+        assert!((psi.invariant_j() - inv.weyl_j).norm() < 1e-6 * inv.weyl_j.norm());
+    }
+
+    #[test]
+    fn test_kerr_is_type_d_everywhere() {
+        for bh in [
+            Kerr::new(1.0, 0.0),
+            Kerr::new(1.0, 0.95),
+            Kerr::kerr_schild(1.0, 0.6),
+        ] {
+            for (r, theta, petrov) in petrov_field(&bh, 2.5, 20.0, 4, 5) {
+                assert_eq!(
+                    petrov,
+                    PetrovType::D,
+                    "Kerr should be type D at r={}, theta={}",
+                    r,
+                    theta
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_flat_space_is_type_o() {
+        assert_eq!(petrov_type_at(&Minkowski, 5.0, 1.0), PetrovType::O);
+    }
+
+    #[test]
+    fn test_deformed_kerr_is_type_i() {
+        let deformed = DeformedKerr {
+            kerr: Kerr::new(1.0, 0.5),
+            eps: 2.0,
+        };
+        assert_eq!(petrov_type_at(&deformed, 4.0, 1.0), PetrovType::I);
+    }
+}
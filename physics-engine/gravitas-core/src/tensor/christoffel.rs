@@ -14,6 +14,7 @@ use crate::metric::Metric;
 /// This is the "brute force" approach. Individual metric implementations
 /// may provide faster analytic Hamiltonian derivatives instead --
 /// see [`Metric::hamiltonian_derivatives`].
+#[allow(clippy::needless_range_loop)]
 pub fn christoffel_from_metric_derivs<M: Metric>(
     metric: &M,
     r: f64,
@@ -57,6 +58,7 @@ pub fn christoffel_from_metric_derivs<M: Metric>(
     gamma
 }
 
+#[allow(clippy::needless_range_loop)]
 fn metric_derivative_r<M: Metric>(metric: &M, r: f64, theta: f64, eps: f64) -> [f64; 16] {
     let g_plus = metric.covariant(r + eps, theta);
     let g_minus = metric.covariant(r - eps, theta);
@@ -67,6 +69,7 @@ fn metric_derivative_r<M: Metric>(metric: &M, r: f64, theta: f64, eps: f64) -> [
     dg
 }
 
+#[allow(clippy::needless_range_loop)]
 fn metric_derivative_theta<M: Metric>(metric: &M, r: f64, theta: f64, eps: f64) -> [f64; 16] {
     let g_plus = metric.covariant(r, theta + eps);
     let g_minus = metric.covariant(r, theta - eps);
@@ -60,6 +60,7 @@ impl MetricTensor4 {
     }
 
     /// Raise an index: p^mu = g^{mu nu} p_nu
+    #[allow(clippy::needless_range_loop)]
     pub fn raise_index(&self, p_lower: &[f64; 4]) -> [f64; 4] {
         let mut p_upper = [0.0; 4];
         for mu in 0..4 {
@@ -70,6 +71,11 @@ impl MetricTensor4 {
         p_upper
     }
 
+    /// Lower an index with a covariant metric: v_mu = g_{mu nu} v^nu
+    pub fn lower_index(&self, v_upper: &[f64; 4]) -> [f64; 4] {
+        self.raise_index(v_upper)
+    }
+
     /// Return the underlying array.
     pub fn as_array(&self) -> &[f64; 16] {
         &self.components
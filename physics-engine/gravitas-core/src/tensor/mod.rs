@@ -1,10 +1,13 @@
 //! Tensor algebra types for general relativity computations.
 //!
-//! Provides a [`MetricTensor4`] type for 4x4 symmetric tensors
-//! and utilities for Christoffel symbol computation.
+//! Provides a [`MetricTensor4`] type for 4x4 symmetric tensors,
+//! utilities for Christoffel symbol computation, and the Riemann
+//! curvature tensor for arbitrary metrics.
 
 mod christoffel;
 mod metric_tensor;
+mod riemann;
 
 pub use christoffel::christoffel_from_metric_derivs;
 pub use metric_tensor::MetricTensor4;
+pub use riemann::{levi_civita, raise_all, riemann_from_metric, Rank4, RiemannTensor};
@@ -0,0 +1,396 @@
+//! Riemann curvature tensor from numerical metric derivatives.
+//!
+//! Works for any [`Metric`]: the first and second derivatives of g_{mu nu}
+//! are taken with fourth-order central differences in r and theta. The
+//! coordinates t and phi are treated as cyclic (stationary, axisymmetric
+//! spacetimes), so all derivatives along them vanish.
+
+use crate::metric::Metric;
+use crate::tensor::MetricTensor4;
+
+/// A rank-4 tensor indexed as [a][b][c][d].
+pub type Rank4 = [[[[f64; 4]; 4]; 4]; 4];
+
+/// The Riemann tensor at a single spacetime point, together with the metric
+/// it was computed from (needed to raise indices).
+#[derive(Debug, Clone, Copy)]
+pub struct RiemannTensor {
+    /// Fully covariant components R_{abcd}.
+    pub components: Rank4,
+    /// Covariant metric g_{mu nu} at the evaluation point.
+    pub metric: MetricTensor4,
+    /// Contravariant metric g^{mu nu} at the evaluation point.
+    pub inverse: MetricTensor4,
+}
+
+/// Compute the Riemann tensor R_{abcd} at (r, theta).
+///
+/// Uses the MTW convention:
+///
+///   R_{abcd} = 1/2 (g_{ad,bc} + g_{bc,ad} - g_{ac,bd} - g_{bd,ac})
+///            + g_{mu nu} (Gamma^mu_{bc} Gamma^nu_{ad} - Gamma^mu_{bd} Gamma^nu_{ac})
+///
+/// # Arguments
+/// - `eps` -- Relative finite-difference step. The radial step is `eps * r`
+///   and the polar step is `eps` radians. Around 1e-3 balances truncation
+///   against round-off for the fourth-order stencils used here.
+#[allow(clippy::needless_range_loop)]
+pub fn riemann_from_metric<M: Metric>(metric: &M, r: f64, theta: f64, eps: f64) -> RiemannTensor {
+    let hr = eps * r.abs().max(1e-6);
+    let hth = eps;
+
+    let g = metric.covariant(r, theta);
+    let g_inv = metric.contravariant(r, theta);
+
+    // Stencil offsets in units of the step; index 2 is the evaluation point.
+    let offsets = [-2.0, -1.0, 0.0, 1.0, 2.0];
+    let sample = |i: usize, j: usize| -> [f64; 16] {
+        metric
+            .covariant(r + offsets[i] * hr, theta + offsets[j] * hth)
+            .components
+    };
+
+    let mut along_r = [[0.0; 16]; 5];
+    let mut along_th = [[0.0; 16]; 5];
+    for k in [0, 1, 3, 4] {
+        along_r[k] = sample(k, 2);
+        along_th[k] = sample(2, k);
+    }
+    along_r[2] = g.components;
+    along_th[2] = g.components;
+
+    // dg[k][mu*4+nu] = dg_{mu nu}/dx^k, ddg[k][l][..] = d^2 g_{mu nu}/dx^k dx^l
+    let mut dg = [[0.0; 16]; 4];
+    let mut ddg = [[[0.0; 16]; 4]; 4];
+
+    for i in 0..16 {
+        dg[1][i] = first_derivative(
+            along_r[0][i],
+            along_r[1][i],
+            along_r[3][i],
+            along_r[4][i],
+            hr,
+        );
+        dg[2][i] = first_derivative(
+            along_th[0][i],
+            along_th[1][i],
+            along_th[3][i],
+            along_th[4][i],
+            hth,
+        );
+        ddg[1][1][i] = second_derivative(
+            along_r[0][i],
+            along_r[1][i],
+            along_r[2][i],
+            along_r[3][i],
+            along_r[4][i],
+            hr,
+        );
+        ddg[2][2][i] = second_derivative(
+            along_th[0][i],
+            along_th[1][i],
+            along_th[2][i],
+            along_th[3][i],
+            along_th[4][i],
+            hth,
+        );
+    }
+
+    // Mixed derivative: the theta stencil applied to radial derivatives.
+    let mut dr_at_theta = [[0.0; 16]; 5];
+    for j in [0, 1, 3, 4] {
+        let column = [sample(0, j), sample(1, j), sample(3, j), sample(4, j)];
+        for i in 0..16 {
+            dr_at_theta[j][i] =
+                first_derivative(column[0][i], column[1][i], column[2][i], column[3][i], hr);
+        }
+    }
+    for i in 0..16 {
+        let mixed = first_derivative(
+            dr_at_theta[0][i],
+            dr_at_theta[1][i],
+            dr_at_theta[3][i],
+            dr_at_theta[4][i],
+            hth,
+        );
+        ddg[1][2][i] = mixed;
+        ddg[2][1][i] = mixed;
+    }
+
+    // Christoffel symbols of the first kind: Gamma_{a b c} = 1/2 (g_{ab,c} + g_{ac,b} - g_{bc,a})
+    let mut gamma_lower = [[[0.0; 4]; 4]; 4];
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                gamma_lower[a][b][c] =
+                    0.5 * (dg[c][a * 4 + b] + dg[b][a * 4 + c] - dg[a][b * 4 + c]);
+            }
+        }
+    }
+
+    // Second kind: Gamma^e_{bc} = g^{ea} Gamma_{abc}
+    let mut gamma = [[[0.0; 4]; 4]; 4];
+    for e in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                let mut sum = 0.0;
+                for a in 0..4 {
+                    sum += g_inv.get(e, a) * gamma_lower[a][b][c];
+                }
+                gamma[e][b][c] = sum;
+            }
+        }
+    }
+
+    let mut components = [[[[0.0; 4]; 4]; 4]; 4];
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                for d in 0..4 {
+                    let second = 0.5
+                        * (ddg[b][c][a * 4 + d] + ddg[a][d][b * 4 + c]
+                            - ddg[a][c][b * 4 + d]
+                            - ddg[b][d][a * 4 + c]);
+
+                    // g_{mu nu} Gamma^mu_{bc} Gamma^nu_{ad} = Gamma_{nu bc} Gamma^nu_{ad}
+                    let mut quadratic = 0.0;
+                    for n in 0..4 {
+                        quadratic += gamma_lower[n][b][c] * gamma[n][a][d]
+                            - gamma_lower[n][b][d] * gamma[n][a][c];
+                    }
+
+                    components[a][b][c][d] = second + quadratic;
+                }
+            }
+        }
+    }
+
+    RiemannTensor {
+        components,
+        metric: g,
+        inverse: g_inv,
+    }
+}
+
+impl RiemannTensor {
+    /// Get component R_{abcd}.
+    #[inline]
+    pub fn get(&self, a: usize, b: usize, c: usize, d: usize) -> f64 {
+        self.components[a][b][c][d]
+    }
+
+    /// Fully contravariant components R^{abcd}.
+    pub fn raised(&self) -> Rank4 {
+        raise_all(&self.components, &self.inverse)
+    }
+
+    /// Ricci tensor R_{bd} = g^{ac} R_{abcd}.
+    pub fn ricci(&self) -> MetricTensor4 {
+        let mut ricci = MetricTensor4::from_array([0.0; 16]);
+        for b in 0..4 {
+            for d in 0..4 {
+                let mut sum = 0.0;
+                for a in 0..4 {
+                    for c in 0..4 {
+                        sum += self.inverse.get(a, c) * self.components[a][b][c][d];
+                    }
+                }
+                ricci.set(b, d, sum);
+            }
+        }
+        ricci
+    }
+
+    /// Ricci scalar R = g^{bd} R_{bd}.
+    pub fn ricci_scalar(&self) -> f64 {
+        let ricci = self.ricci();
+        let mut sum = 0.0;
+        for b in 0..4 {
+            for d in 0..4 {
+                sum += self.inverse.get(b, d) * ricci.get(b, d);
+            }
+        }
+        sum
+    }
+
+    /// Weyl (conformal) tensor C_{abcd}, the trace-free part of R_{abcd}.
+    ///
+    /// C_{abcd} = R_{abcd} - 1/2 (g_{ac} R_{bd} - g_{ad} R_{bc} - g_{bc} R_{ad} + g_{bd} R_{ac})
+    ///          + R/6 (g_{ac} g_{bd} - g_{ad} g_{bc})
+    ///
+    /// In vacuum the Weyl and Riemann tensors coincide.
+    #[allow(clippy::needless_range_loop)]
+    pub fn weyl(&self) -> Rank4 {
+        let g = &self.metric;
+        let ricci = self.ricci();
+        let scalar = self.ricci_scalar();
+
+        let mut weyl = [[[[0.0; 4]; 4]; 4]; 4];
+        for a in 0..4 {
+            for b in 0..4 {
+                for c in 0..4 {
+                    for d in 0..4 {
+                        weyl[a][b][c][d] = self.components[a][b][c][d]
+                            - 0.5
+                                * (g.get(a, c) * ricci.get(b, d)
+                                    - g.get(a, d) * ricci.get(b, c)
+                                    - g.get(b, c) * ricci.get(a, d)
+                                    + g.get(b, d) * ricci.get(a, c))
+                            + scalar / 6.0
+                                * (g.get(a, c) * g.get(b, d) - g.get(a, d) * g.get(b, c));
+                    }
+                }
+            }
+        }
+        weyl
+    }
+}
+
+/// Raise all four indices of a covariant rank-4 tensor.
+#[allow(clippy::needless_range_loop)]
+pub fn raise_all(t: &Rank4, g_inv: &MetricTensor4) -> Rank4 {
+    let mut current = *t;
+    for slot in 0..4 {
+        let mut next = [[[[0.0; 4]; 4]; 4]; 4];
+        for a in 0..4 {
+            for b in 0..4 {
+                for c in 0..4 {
+                    for d in 0..4 {
+                        let idx = [a, b, c, d];
+                        let mut sum = 0.0;
+                        for s in 0..4 {
+                            let mut src = idx;
+                            src[slot] = s;
+                            sum +=
+                                g_inv.get(idx[slot], s) * current[src[0]][src[1]][src[2]][src[3]];
+                        }
+                        next[a][b][c][d] = sum;
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// Covariant Levi-Civita tensor epsilon_{abcd} = sqrt(-g) [abcd].
+///
+/// Orientation: epsilon_{t r theta phi} = +sqrt(-g).
+#[allow(clippy::needless_range_loop)]
+pub fn levi_civita(g: &MetricTensor4) -> Rank4 {
+    let sqrt_neg_g = (-g.determinant()).abs().sqrt();
+    let mut eps = [[[[0.0; 4]; 4]; 4]; 4];
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                for d in 0..4 {
+                    eps[a][b][c][d] = permutation_sign([a, b, c, d]) * sqrt_neg_g;
+                }
+            }
+        }
+    }
+    eps
+}
+
+/// Sign of a permutation of (0, 1, 2, 3); zero if any index repeats.
+fn permutation_sign(idx: [usize; 4]) -> f64 {
+    let mut sign = 1.0;
+    for i in 0..4 {
+        for j in (i + 1)..4 {
+            if idx[i] == idx[j] {
+                return 0.0;
+            }
+            if idx[i] > idx[j] {
+                sign = -sign;
+            }
+        }
+    }
+    sign
+}
+
+/// Fourth-order central first derivative from samples at -2h, -h, +h, +2h.
+#[inline]
+fn first_derivative(fm2: f64, fm1: f64, fp1: f64, fp2: f64, h: f64) -> f64 {
+    (fm2 - 8.0 * fm1 + 8.0 * fp1 - fp2) / (12.0 * h)
+}
+
+/// Fourth-order central second derivative from samples at -2h..+2h.
+#[inline]
+fn second_derivative(fm2: f64, fm1: f64, f0: f64, fp1: f64, fp2: f64, h: f64) -> f64 {
+    (-fm2 + 16.0 * fm1 - 30.0 * f0 + 16.0 * fp1 - fp2) / (12.0 * h * h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Kerr, Minkowski, Schwarzschild};
+
+    #[test]
+    fn test_flat_space_has_no_curvature() {
+        let riemann = riemann_from_metric(&Minkowski, 5.0, 1.0, 1e-3);
+        for a in 0..4 {
+            for b in 0..4 {
+                for c in 0..4 {
+                    for d in 0..4 {
+                        assert!(
+                            riemann.get(a, b, c, d).abs() < 1e-8,
+                            "Minkowski R_{}{}{}{} = {}",
+                            a,
+                            b,
+                            c,
+                            d,
+                            riemann.get(a, b, c, d)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_schwarzschild_trtr_component() {
+        // R_{trtr} = -2M/r^3 in Schwarzschild coordinates
+        let r = 6.0;
+        let riemann = riemann_from_metric(&Schwarzschild::new(1.0), r, 1.2, 1e-3);
+        let expected = -2.0 / (r * r * r);
+        assert!(
+            (riemann.get(0, 1, 0, 1) - expected).abs() < 1e-8,
+            "R_trtr should be {}, got {}",
+            expected,
+            riemann.get(0, 1, 0, 1)
+        );
+    }
+
+    #[test]
+    fn test_kerr_is_ricci_flat() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            let riemann = riemann_from_metric(&bh, 4.0, 0.8, 1e-3);
+            let ricci = riemann.ricci();
+            for i in 0..16 {
+                assert!(
+                    ricci.components[i].abs() < 1e-7,
+                    "Kerr should be a vacuum solution, R_mu_nu[{}] = {}",
+                    i,
+                    ricci.components[i]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_riemann_symmetries() {
+        let riemann = riemann_from_metric(&Kerr::new(1.0, 0.6), 5.0, 1.0, 1e-3);
+        for a in 0..4 {
+            for b in 0..4 {
+                for c in 0..4 {
+                    for d in 0..4 {
+                        let r_abcd = riemann.get(a, b, c, d);
+                        assert!((r_abcd + riemann.get(b, a, c, d)).abs() < 1e-9);
+                        assert!((r_abcd - riemann.get(c, d, a, b)).abs() < 1e-9);
+                    }
+                }
+            }
+        }
+    }
+}
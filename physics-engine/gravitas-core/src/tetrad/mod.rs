@@ -0,0 +1,500 @@
+//! Local observer frames (orthonormal tetrads).
+//!
+//! A tetrad e_(a)^mu is the set of four orthonormal basis vectors carried
+//! by an observer: e_(0) is the observer's 4-velocity and e_(1), e_(2),
+//! e_(3) span its local rest space. Projecting a photon momentum onto the
+//! tetrad gives the energy and direction the observer actually measures,
+//! and the reverse map turns a locally chosen direction into a geodesic
+//! momentum. Cameras, redshift factors and tidal tensors all go through
+//! this one conversion.
+//!
+//! Available observers (see [`observers`]):
+//!
+//! - Zero-angular-momentum observer (ZAMO), defined for any metric
+//! - Static observer, at rest with respect to the Killing time
+//! - Circular observer, rigidly rotating with angular velocity Omega
+//! - Keplerian orbit and radial free fall from rest at infinity
+//! - Arbitrary 4-velocity, reached by a Lorentz boost from the ZAMO
+//!
+//! # References
+//!
+//! - Bardeen, Press & Teukolsky (1972). "Rotating black holes: locally
+//!   nonrotating frames, energy extraction, and scalar synchrotron radiation"
+//! - Misner, Thorne & Wheeler (1973). "Gravitation", section 13.6
+
+pub mod observers;
+
+pub use observers::{
+    boosted_velocity, circular_velocity, keplerian_velocity, radial_infall_velocity,
+    static_velocity, zamo_velocity,
+};
+
+use crate::metric::{contract, Metric};
+use crate::tensor::{MetricTensor4, RiemannTensor};
+
+/// Minkowski signature of the orthonormal frame, eta_(a)(a).
+const ETA: [f64; 4] = [-1.0, 1.0, 1.0, 1.0];
+
+/// An orthonormal frame at a spacetime point.
+///
+/// The spatial legs follow the coordinate directions where possible:
+/// e_(1) is radial, e_(2) polar and e_(3) azimuthal, forming a right-handed
+/// triad.
+#[derive(Debug, Clone, Copy)]
+pub struct Tetrad {
+    /// Contravariant legs e_(a)^mu, indexed as [a][mu].
+    pub legs: [[f64; 4]; 4],
+    /// Covariant legs e_(a)mu = g_{mu nu} e_(a)^nu, indexed as [a][mu].
+    pub legs_lower: [[f64; 4]; 4],
+}
+
+/// A 4-momentum as measured in an observer's local frame.
+#[derive(Debug, Clone, Copy)]
+pub struct LocalMomentum {
+    /// Locally measured energy E = -p_mu e_(0)^mu.
+    pub energy: f64,
+    /// Spatial momentum components p^(i) in the frame (e_(1), e_(2), e_(3)).
+    pub momentum: [f64; 3],
+}
+
+impl LocalMomentum {
+    /// Unit vector along the spatial momentum (the photon's direction of travel).
+    pub fn direction(&self) -> [f64; 3] {
+        let p = self.momentum;
+        let norm = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+        if norm < 1e-300 {
+            return [0.0; 3];
+        }
+        [p[0] / norm, p[1] / norm, p[2] / norm]
+    }
+}
+
+impl Tetrad {
+    /// Build a tetrad from contravariant legs, lowering them with `g`.
+    #[allow(clippy::needless_range_loop)]
+    pub fn from_legs(legs: [[f64; 4]; 4], g: &MetricTensor4) -> Self {
+        let mut legs_lower = [[0.0; 4]; 4];
+        for a in 0..4 {
+            for mu in 0..4 {
+                for nu in 0..4 {
+                    legs_lower[a][mu] += g.get(mu, nu) * legs[a][nu];
+                }
+            }
+        }
+        Self { legs, legs_lower }
+    }
+
+    /// The observer's 4-velocity u^mu = e_(0)^mu.
+    #[inline]
+    pub fn velocity(&self) -> [f64; 4] {
+        self.legs[0]
+    }
+
+    /// Frame components p_(a) = e_(a)^mu p_mu of a covariant vector.
+    #[allow(clippy::needless_range_loop)]
+    pub fn project_covector(&self, p: &[f64; 4]) -> [f64; 4] {
+        let mut out = [0.0; 4];
+        for a in 0..4 {
+            for mu in 0..4 {
+                out[a] += self.legs[a][mu] * p[mu];
+            }
+        }
+        out
+    }
+
+    /// Frame components v^(a) = eta^(a)(a) e_(a)mu v^mu of a contravariant vector.
+    #[allow(clippy::needless_range_loop)]
+    pub fn project_vector(&self, v: &[f64; 4]) -> [f64; 4] {
+        let mut out = [0.0; 4];
+        for a in 0..4 {
+            for mu in 0..4 {
+                out[a] += self.legs_lower[a][mu] * v[mu];
+            }
+            out[a] *= ETA[a];
+        }
+        out
+    }
+
+    /// Coordinate components V^mu = v^(a) e_(a)^mu of a frame vector.
+    #[allow(clippy::needless_range_loop)]
+    pub fn vector_from_frame(&self, v: &[f64; 4]) -> [f64; 4] {
+        let mut out = [0.0; 4];
+        for a in 0..4 {
+            for mu in 0..4 {
+                out[mu] += v[a] * self.legs[a][mu];
+            }
+        }
+        out
+    }
+
+    /// Covariant components p_mu = p^(a) e_(a)mu of a frame vector.
+    #[allow(clippy::needless_range_loop)]
+    pub fn covector_from_frame(&self, v: &[f64; 4]) -> [f64; 4] {
+        let mut out = [0.0; 4];
+        for a in 0..4 {
+            for mu in 0..4 {
+                out[mu] += v[a] * self.legs_lower[a][mu];
+            }
+        }
+        out
+    }
+
+    /// Energy and spatial momentum of a covariant momentum p_mu as measured
+    /// by this observer.
+    pub fn measure(&self, p: &[f64; 4]) -> LocalMomentum {
+        let p_frame = self.project_covector(p);
+        LocalMomentum {
+            energy: -p_frame[0],
+            momentum: [p_frame[1], p_frame[2], p_frame[3]],
+        }
+    }
+
+    /// Covariant photon momentum p_mu with local energy `energy` travelling
+    /// along the unit frame direction `direction`.
+    ///
+    /// The result is exactly null: p^(a) = E (1, n).
+    pub fn null_momentum(&self, energy: f64, direction: [f64; 3]) -> [f64; 4] {
+        let norm = (direction[0].powi(2) + direction[1].powi(2) + direction[2].powi(2))
+            .sqrt()
+            .max(1e-300);
+        self.covector_from_frame(&[
+            energy,
+            energy * direction[0] / norm,
+            energy * direction[1] / norm,
+            energy * direction[2] / norm,
+        ])
+    }
+
+    /// 3-velocity of an observer with 4-velocity `u` as measured in this frame.
+    pub fn relative_velocity(&self, u: &[f64; 4]) -> [f64; 3] {
+        let u_frame = self.project_vector(u);
+        let gamma = u_frame[0];
+        [u_frame[1] / gamma, u_frame[2] / gamma, u_frame[3] / gamma]
+    }
+
+    /// The frame of an observer moving with 3-velocity `v` (|v| < 1)
+    /// relative to this one, related by a pure Lorentz boost.
+    ///
+    ///   e'_(0) = gamma (e_(0) + v^i e_(i))
+    ///   e'_(i) = e_(i) + (gamma - 1) v_i v^j e_(j) / v^2 + gamma v_i e_(0)
+    #[allow(clippy::needless_range_loop)]
+    pub fn boost(&self, v: [f64; 3]) -> Self {
+        let v2 = v[0] * v[0] + v[1] * v[1] + v[2] * v[2];
+        if v2 < 1e-30 {
+            return *self;
+        }
+        let gamma = 1.0 / (1.0 - v2).max(1e-30).sqrt();
+
+        // Lambda[a][b]: e'_(a) = Lambda[a][b] e_(b)
+        let mut lambda = [[0.0; 4]; 4];
+        lambda[0][0] = gamma;
+        for i in 0..3 {
+            lambda[0][i + 1] = gamma * v[i];
+            lambda[i + 1][0] = gamma * v[i];
+            for j in 0..3 {
+                let kronecker = if i == j { 1.0 } else { 0.0 };
+                lambda[i + 1][j + 1] = kronecker + (gamma - 1.0) * v[i] * v[j] / v2;
+            }
+        }
+
+        let mut legs = [[0.0; 4]; 4];
+        let mut legs_lower = [[0.0; 4]; 4];
+        for a in 0..4 {
+            for b in 0..4 {
+                for mu in 0..4 {
+                    legs[a][mu] += lambda[a][b] * self.legs[b][mu];
+                    legs_lower[a][mu] += lambda[a][b] * self.legs_lower[b][mu];
+                }
+            }
+        }
+        Self { legs, legs_lower }
+    }
+
+    /// Boost this frame to the observer with 4-velocity `u`.
+    pub fn boost_to(&self, u: &[f64; 4]) -> Self {
+        self.boost(self.relative_velocity(u))
+    }
+
+    /// Tidal tensor E_(i)(j) = R_(0)(i)(0)(j) measured by this observer.
+    ///
+    /// The relative acceleration of nearby free-falling particles separated
+    /// by xi^(j) is a^(i) = -E_(i)(j) xi^(j).
+    pub fn tidal_tensor(&self, riemann: &RiemannTensor) -> [[f64; 3]; 3] {
+        let e = &self.legs;
+        let mut tidal = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                let mut sum = 0.0;
+                for a in 0..4 {
+                    for b in 0..4 {
+                        for c in 0..4 {
+                            for d in 0..4 {
+                                sum += riemann.components[a][b][c][d]
+                                    * e[0][a]
+                                    * e[i + 1][b]
+                                    * e[0][c]
+                                    * e[j + 1][d];
+                            }
+                        }
+                    }
+                }
+                tidal[i][j] = sum;
+            }
+        }
+        tidal
+    }
+
+    /// Largest deviation of g(e_(a), e_(b)) from eta_(a)(b).
+    #[allow(clippy::needless_range_loop)]
+    pub fn orthonormality_error(&self) -> f64 {
+        let mut worst: f64 = 0.0;
+        for a in 0..4 {
+            for b in 0..4 {
+                let mut dot = 0.0;
+                for mu in 0..4 {
+                    dot += self.legs[a][mu] * self.legs_lower[b][mu];
+                }
+                let expected = if a == b { ETA[a] } else { 0.0 };
+                worst = worst.max((dot - expected).abs());
+            }
+        }
+        worst
+    }
+}
+
+/// Build the frame of 4-velocity `u` by Gram-Schmidt on the coordinate
+/// directions, taken in `order` (1 = r, 2 = theta, 3 = phi). Leg k is always
+/// built from direction k, so the triad stays (radial, polar, azimuthal).
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn gram_schmidt(g: &MetricTensor4, u: [f64; 4], order: [usize; 3]) -> Tetrad {
+    let dot = |x: &[f64; 4], y: &[f64; 4]| -> f64 {
+        let mut sum = 0.0;
+        for mu in 0..4 {
+            for nu in 0..4 {
+                sum += g.get(mu, nu) * x[mu] * y[nu];
+            }
+        }
+        sum
+    };
+
+    let mut legs = [[0.0; 4]; 4];
+    let u_norm = (-dot(&u, &u)).abs().sqrt().max(1e-300);
+    for mu in 0..4 {
+        legs[0][mu] = u[mu] / u_norm;
+    }
+
+    for (n, &k) in order.iter().enumerate() {
+        let mut v = [0.0; 4];
+        v[k] = 1.0;
+        for &j in std::iter::once(&0).chain(&order[..n]) {
+            let proj = dot(&v, &legs[j]) * ETA[j];
+            for mu in 0..4 {
+                v[mu] -= proj * legs[j][mu];
+            }
+        }
+        let norm = dot(&v, &v).abs().sqrt().max(1e-300);
+        for mu in 0..4 {
+            legs[k][mu] = v[mu] / norm;
+        }
+    }
+
+    Tetrad::from_legs(legs, g)
+}
+
+/// Ratio of the photon energies measured by two observers, E_b / E_a.
+///
+/// For a photon emitted by observer `a` and received by observer `b`
+/// (with momenta `p_a`, `p_b` at each end of the same geodesic), this is
+/// the redshift factor g = nu_obs / nu_emit.
+pub fn energy_ratio(p_a: &[f64; 4], u_a: &[f64; 4], p_b: &[f64; 4], u_b: &[f64; 4]) -> f64 {
+    let e_a = -contract(p_a, u_a);
+    let e_b = -contract(p_b, u_b);
+    if e_a.abs() < 1e-300 {
+        return 0.0;
+    }
+    e_b / e_a
+}
+
+impl Tetrad {
+    /// Frame of the zero-angular-momentum observer at (r, theta).
+    ///
+    /// See [`zamo_velocity`]. This frame exists wherever g^{tt} < 0.
+    pub fn zamo<M: Metric>(metric: &M, r: f64, theta: f64) -> Self {
+        let g = metric.covariant(r, theta);
+        gram_schmidt(&g, zamo_velocity(metric, r, theta), [1, 2, 3])
+    }
+
+    /// Frame of an observer with 4-velocity `u`, obtained by a pure boost
+    /// of the ZAMO frame (no additional spatial rotation).
+    pub fn from_velocity<M: Metric>(metric: &M, r: f64, theta: f64, u: [f64; 4]) -> Self {
+        Self::zamo(metric, r, theta).boost_to(&u)
+    }
+
+    /// Frame of an observer with 4-velocity `u` whose azimuthal leg lies along
+    /// the axial Killing vector d/dphi and whose polar leg lies along d/dtheta.
+    ///
+    /// e_(3) is d/dphi projected orthogonal to u, e_(2) is d/dtheta made
+    /// orthogonal to both, and e_(1) completes the triad pointing outward.
+    /// In Kerr, d/dtheta and d/dphi are the same vectors in Boyer-Lindquist
+    /// and Kerr-Schild coordinates, so this frame does not depend on the
+    /// coordinate system. For stationary observers in Boyer-Lindquist
+    /// coordinates it agrees with [`Tetrad::from_velocity`].
+    pub fn adapted<M: Metric>(metric: &M, r: f64, theta: f64, u: [f64; 4]) -> Self {
+        let g = metric.covariant(r, theta);
+        gram_schmidt(&g, u, [3, 2, 1])
+    }
+
+    /// Frame of the static observer (u proportional to the Killing vector d/dt).
+    ///
+    /// Returns `None` inside the ergoregion, where no static observers exist.
+    pub fn static_observer<M: Metric>(metric: &M, r: f64, theta: f64) -> Option<Self> {
+        static_velocity(metric, r, theta).map(|u| Self::from_velocity(metric, r, theta, u))
+    }
+
+    /// Frame of an observer rotating rigidly with angular velocity `omega`
+    /// (u proportional to d/dt + omega d/dphi).
+    ///
+    /// Returns `None` if that motion would be superluminal at (r, theta).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gravitas::metric::Kerr;
+    /// use gravitas::tetrad::Tetrad;
+    ///
+    /// let bh = Kerr::new(1.0, 0.9);
+    /// let r = 6.0;
+    /// let frame = Tetrad::circular(&bh, r, std::f64::consts::FRAC_PI_2, bh.keplerian_frequency(r));
+    /// assert!(frame.is_some());
+    /// ```
+    pub fn circular<M: Metric>(metric: &M, r: f64, theta: f64, omega: f64) -> Option<Self> {
+        circular_velocity(metric, r, theta, omega).map(|u| Self::from_velocity(metric, r, theta, u))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Kerr;
+    use crate::physics::redshift::kerr_g_factor;
+    use crate::tensor::riemann_from_metric;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_frames_are_orthonormal() {
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            let zamo = Tetrad::zamo(&bh, 3.0, 1.0);
+            assert!(zamo.orthonormality_error() < 1e-12);
+
+            let circ = Tetrad::circular(&bh, 6.0, FRAC_PI_2, bh.keplerian_frequency(6.0)).unwrap();
+            assert!(circ.orthonormality_error() < 1e-12);
+
+            let stat = Tetrad::static_observer(&bh, 5.0, 0.7).unwrap();
+            assert!(stat.orthonormality_error() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_isco_and_infall_velocities_are_normalized() {
+        use crate::metric::Orbit;
+
+        let norm = |bh: &Kerr, r: f64, theta: f64, u: [f64; 4]| -> f64 {
+            let g = bh.covariant(r, theta);
+            let mut sum = 0.0;
+            for mu in 0..4 {
+                for nu in 0..4 {
+                    sum += g.get(mu, nu) * u[mu] * u[nu];
+                }
+            }
+            sum
+        };
+
+        for bh in [Kerr::new(1.0, 0.9), Kerr::kerr_schild(1.0, 0.9)] {
+            for orbit in [Orbit::Prograde, Orbit::Retrograde] {
+                let r = bh.isco(orbit);
+                let u = keplerian_velocity(&bh, r, orbit).unwrap();
+                assert!((norm(&bh, r, FRAC_PI_2, u) + 1.0).abs() < 1e-12);
+            }
+
+            let u = radial_infall_velocity(&bh, 6.0, 0.8).unwrap();
+            assert!(u[1] < 0.0);
+            assert!((norm(&bh, 6.0, 0.8, u) + 1.0).abs() < 1e-12);
+        }
+
+        // Kerr-Schild raindrops keep falling through the horizon.
+        let ks = Kerr::kerr_schild(1.0, 0.9);
+        let u = radial_infall_velocity(&ks, 1.2, 0.8).unwrap();
+        assert!(u[1] < 0.0);
+        assert!((norm(&ks, 1.2, 0.8, u) + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_no_static_observer_in_ergoregion() {
+        let bh = Kerr::new(1.0, 0.9);
+        assert!(Tetrad::static_observer(&bh, 1.8, FRAC_PI_2).is_none());
+    }
+
+    #[test]
+    fn test_null_momentum_round_trip() {
+        let bh = Kerr::new(1.0, 0.7);
+        let frame = Tetrad::zamo(&bh, 8.0, 1.2).boost([0.3, -0.2, 0.5]);
+        let dir = [0.48, -0.6, 0.64];
+        let p = frame.null_momentum(2.5, dir);
+
+        let g_inv = bh.contravariant(8.0, 1.2);
+        assert!(g_inv.contract(&p).abs() < 1e-12, "momentum should be null");
+
+        let measured = frame.measure(&p);
+        assert!((measured.energy - 2.5).abs() < 1e-12);
+        let back = measured.direction();
+        for i in 0..3 {
+            assert!((back[i] - dir[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_boost_composes_with_relative_velocity() {
+        let bh = Kerr::new(1.0, 0.5);
+        let zamo = Tetrad::zamo(&bh, 10.0, 1.0);
+        let v = [0.1, 0.4, -0.3];
+        let moving = zamo.boost(v);
+        let measured = zamo.relative_velocity(&moving.velocity());
+        for i in 0..3 {
+            assert!((measured[i] - v[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_energy_ratio_matches_kerr_g_factor() {
+        // Photon from a Keplerian emitter, received at infinity.
+        let bh = Kerr::new(1.0, 0.8);
+        let r = 7.0;
+        let lambda = 3.0;
+        let p = [-1.0, 0.5, 0.0, lambda];
+        let u_emit = circular_velocity(&bh, r, FRAC_PI_2, bh.keplerian_frequency(r)).unwrap();
+        let u_inf = [1.0, 0.0, 0.0, 0.0];
+
+        let g = energy_ratio(&p, &u_emit, &p, &u_inf);
+        let expected = kerr_g_factor(r, 1.0, 0.8, lambda);
+        assert!(
+            (g - expected).abs() < 1e-12,
+            "g = {}, expected {}",
+            g,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_schwarzschild_radial_tidal_stretch() {
+        // Static observer: E_rr = -2M/r^3, E_thth = E_phph = M/r^3
+        let bh = Kerr::new(1.0, 0.0);
+        let r = 10.0;
+        let frame = Tetrad::static_observer(&bh, r, FRAC_PI_2).unwrap();
+        let riemann = riemann_from_metric(&bh, r, FRAC_PI_2, 1e-3);
+        let tidal = frame.tidal_tensor(&riemann);
+        let m_r3 = 1.0 / (r * r * r);
+        assert!((tidal[0][0] + 2.0 * m_r3).abs() < 1e-9);
+        assert!((tidal[1][1] - m_r3).abs() < 1e-9);
+        assert!((tidal[2][2] - m_r3).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,128 @@
+//! 4-velocities of the standard observers.
+//!
+//! The static and circular observers move along the Killing vectors
+//! d/dt and d/dphi. In Kerr these are the same vector fields in
+//! Boyer-Lindquist and ingoing Kerr-Schild coordinates, so the formulas
+//! below hold for either coordinate system.
+
+use crate::metric::{Kerr, Metric, Orbit};
+
+use super::Tetrad;
+
+/// 4-velocity of the zero-angular-momentum observer, u^mu = -alpha g^{mu t}
+/// with lapse alpha = 1 / sqrt(-g^{tt}).
+///
+/// This is the unit normal to the t = const slices. In Boyer-Lindquist
+/// coordinates it is the Bardeen ZAMO rotating with the frame-dragging rate
+/// omega = -g_{t phi} / g_{phi phi}. In Kerr-Schild coordinates it is the
+/// ingoing normal observer, which stays regular through the horizon.
+pub fn zamo_velocity<M: Metric>(metric: &M, r: f64, theta: f64) -> [f64; 4] {
+    let g_inv = metric.contravariant(r, theta);
+    let alpha = 1.0 / (-g_inv.get(0, 0)).max(1e-30).sqrt();
+    [
+        -alpha * g_inv.get(0, 0),
+        -alpha * g_inv.get(1, 0),
+        -alpha * g_inv.get(2, 0),
+        -alpha * g_inv.get(3, 0),
+    ]
+}
+
+/// 4-velocity of an observer moving with 3-velocity `v` = (v_r, v_theta,
+/// v_phi) relative to the ZAMO, in units of c along the ZAMO's radial, polar
+/// and azimuthal legs: u = gamma (e_(0) + v^i e_(i)).
+///
+/// Any future-directed timelike 4-velocity at (r, theta) can be reached this
+/// way. Returns `None` unless |v| < 1.
+pub fn boosted_velocity<M: Metric>(
+    metric: &M,
+    r: f64,
+    theta: f64,
+    v: [f64; 3],
+) -> Option<[f64; 4]> {
+    let v2 = v[0] * v[0] + v[1] * v[1] + v[2] * v[2];
+    if v2.is_nan() || v2 >= 1.0 {
+        return None;
+    }
+    let gamma = 1.0 / (1.0 - v2).sqrt();
+    let zamo = Tetrad::zamo(metric, r, theta);
+    Some(zamo.vector_from_frame(&[gamma, gamma * v[0], gamma * v[1], gamma * v[2]]))
+}
+
+/// 4-velocity of the static observer, u = (d/dt) / sqrt(-g_tt).
+///
+/// Returns `None` where g_tt >= 0 (inside the ergoregion).
+pub fn static_velocity<M: Metric>(metric: &M, r: f64, theta: f64) -> Option<[f64; 4]> {
+    circular_velocity(metric, r, theta, 0.0)
+}
+
+/// 4-velocity of an observer rotating with angular velocity `omega`,
+/// u = u^t (1, 0, 0, omega).
+///
+/// Returns `None` if d/dt + omega d/dphi is not timelike at (r, theta).
+/// Passing `Kerr::keplerian_frequency(r)` gives the prograde circular
+/// geodesic in the equatorial plane.
+pub fn circular_velocity<M: Metric>(
+    metric: &M,
+    r: f64,
+    theta: f64,
+    omega: f64,
+) -> Option<[f64; 4]> {
+    let g = metric.covariant(r, theta);
+    let norm = g.get(0, 0) + 2.0 * omega * g.get(0, 3) + omega * omega * g.get(3, 3);
+    if norm >= 0.0 {
+        return None;
+    }
+    let ut = 1.0 / (-norm).sqrt();
+    Some([ut, 0.0, 0.0, ut * omega])
+}
+
+/// 4-velocity of the equatorial circular geodesic at radius r in Kerr.
+///
+/// Omega = +/- sqrt(M) / (r^{3/2} +/- a sqrt(M)), upper sign prograde.
+/// Returns `None` inside the photon orbit, where no timelike circular
+/// orbit exists.
+pub fn keplerian_velocity(bh: &Kerr, r: f64, orbit: Orbit) -> Option<[f64; 4]> {
+    let sqrt_m = bh.mass().sqrt();
+    let a = bh.a();
+    let omega = match orbit {
+        Orbit::Prograde => sqrt_m / (r.powf(1.5) + a * sqrt_m),
+        Orbit::Retrograde => -sqrt_m / (r.powf(1.5) - a * sqrt_m),
+    };
+    circular_velocity(bh, r, std::f64::consts::FRAC_PI_2, omega)
+}
+
+/// 4-velocity of a freely falling observer released from rest at infinity
+/// with zero angular momentum (u_t = -1, u_phi = 0, u_theta = 0).
+///
+/// u_r is fixed by the normalization g^{mu nu} u_mu u_nu = -1, taking the
+/// ingoing root. In Kerr-Schild coordinates the observer passes smoothly
+/// through the horizon. Returns `None` where no such observer exists.
+pub fn radial_infall_velocity<M: Metric>(metric: &M, r: f64, theta: f64) -> Option<[f64; 4]> {
+    let g_inv = metric.contravariant(r, theta);
+    let u_t = -1.0;
+
+    // g^rr u_r^2 + 2 g^tr u_t u_r + (g^tt u_t^2 + 1) = 0
+    let a_quad = g_inv.get(1, 1);
+    let b_quad = 2.0 * g_inv.get(0, 1) * u_t;
+    let c_quad = g_inv.get(0, 0) * u_t * u_t + 1.0;
+    let disc = b_quad * b_quad - 4.0 * a_quad * c_quad;
+    if disc < 0.0 || a_quad.abs() < 1e-30 {
+        return None;
+    }
+    let sqrt_d = disc.sqrt();
+
+    let roots = [
+        (-b_quad - sqrt_d) / (2.0 * a_quad),
+        (-b_quad + sqrt_d) / (2.0 * a_quad),
+    ];
+    // Ingoing: the root with the most negative u^r.
+    let u_lower = roots
+        .iter()
+        .map(|&u_r| [u_t, u_r, 0.0, 0.0])
+        .min_by(|x, y| {
+            let ur_x = g_inv.get(1, 0) * x[0] + g_inv.get(1, 1) * x[1];
+            let ur_y = g_inv.get(1, 0) * y[0] + g_inv.get(1, 1) * y[1];
+            ur_x.total_cmp(&ur_y)
+        })?;
+    Some(g_inv.raise_index(&u_lower))
+}
@@ -15,13 +15,21 @@
 mod camera;
 mod sab;
 
+use gravitas::camera::{Camera, Projection};
 use gravitas::geodesic::{
-    integrate, AdaptiveStepper, GeodesicState, IntegrationMethod, IntegrationOptions,
+    integrate, AdaptiveStepper, GeodesicObserver, GeodesicState, IntegrationMethod,
+    IntegrationOptions,
 };
 use gravitas::invariants;
 use gravitas::metric::kerr::CoordinateSystem;
 use gravitas::metric::{Kerr, Metric, Orbit};
 use gravitas::physics::{disk, spectrum};
+use gravitas::render::deflection::{LUT_CHANNELS, LUT_HEADER_LEN};
+use gravitas::render::{
+    Background, DeflectionLut, DeflectionLutSpec, Image, PriorityMap, RenderSettings, Renderer,
+    SurrogateSpec, SurrogateTrainer, ThinDisk, TileOrder, TileScheduler,
+};
+use gravitas::tetrad;
 
 use js_sys::Float32Array;
 use wasm_bindgen::prelude::*;
@@ -51,6 +59,19 @@ pub struct PhysicsEngine {
     external_sab_ptr: Option<*mut f32>,
     camera: camera::CameraState,
     last_good_camera: camera::CameraState,
+    geodesic_camera: Option<GeodesicObserver>,
+    geodesic_time_scale: f64,
+    cpu_render: Option<CpuRender>,
+}
+
+/// A headless CPU render in progress, advanced a few tiles per call so the
+/// worker stays responsive.
+struct CpuRender {
+    camera: Camera,
+    settings: RenderSettings,
+    scheduler: TileScheduler,
+    image: Image,
+    use_kerr_schild: bool,
 }
 
 #[wasm_bindgen]
@@ -68,6 +89,9 @@ impl PhysicsEngine {
             external_sab_ptr: None,
             camera: camera::CameraState::new(),
             last_good_camera: camera::CameraState::new(),
+            geodesic_camera: None,
+            geodesic_time_scale: 1.0,
+            cpu_render: None,
         }
     }
 
@@ -80,6 +104,9 @@ impl PhysicsEngine {
         self.spin = spin;
         self.metric_bl = Kerr::new(mass, spin);
         self.metric_ks = Kerr::kerr_schild(mass, spin);
+        // A geodesic computed in the old spacetime is meaningless in the new one.
+        self.geodesic_camera = None;
+        self.cpu_render = None;
     }
 
     pub fn compute_horizon(&self) -> f64 {
@@ -125,6 +152,92 @@ impl PhysicsEngine {
         self.camera.auto_spin = enabled;
     }
 
+    /// Drop the camera from rest (zero angular momentum) at (r, theta, phi).
+    ///
+    /// The camera then follows its timelike geodesic in Kerr-Schild
+    /// coordinates through the event horizon, stopping at the inner horizon.
+    /// Returns false if the starting point is not outside the horizon.
+    pub fn start_freefall_camera(&mut self, r: f64, theta: f64, phi: f64) -> bool {
+        let r_stop = self.metric_ks.cauchy_horizon().max(0.1 * self.mass) * 1.01;
+        self.geodesic_camera = GeodesicObserver::released_from_rest(&self.metric_ks, r, theta, phi)
+            .map(|obs| obs.with_min_radius(r_stop));
+        self.geodesic_camera.is_some()
+    }
+
+    /// Put the camera on the equatorial circular geodesic at radius r.
+    ///
+    /// Returns false if no timelike circular orbit exists at r.
+    pub fn start_orbit_camera(&mut self, r: f64, prograde: bool) -> bool {
+        let orbit = if prograde {
+            Orbit::Prograde
+        } else {
+            Orbit::Retrograde
+        };
+        self.geodesic_camera = GeodesicObserver::circular(&self.metric_ks, r, orbit, 0.0);
+        self.geodesic_camera.is_some()
+    }
+
+    /// Return the camera to interactive (mouse-driven) mode.
+    pub fn stop_geodesic_camera(&mut self) {
+        self.geodesic_camera = None;
+    }
+
+    /// Proper time (in units of M) advanced per second of wall-clock time.
+    pub fn set_geodesic_time_scale(&mut self, scale: f64) {
+        self.geodesic_time_scale = scale.max(0.0);
+    }
+
+    /// Trace exact deflection, time delay and disk-crossing tables over
+    /// (impact parameter, position angle, spin, inclination) and write them
+    /// into the SAB LUT block (layout in `sab.rs`).
+    ///
+    /// Returns the number of f32 values written. Throws, without tracing
+    /// anything, if the table does not fit in the shared buffer.
+    ///
+    /// Without an external SAB the table goes into the internal buffer,
+    /// which grows to fit and may move: fetch `get_sab_ptr` again after
+    /// this call.
+    pub fn write_deflection_lut(
+        &mut self,
+        impact_samples: usize,
+        angle_samples: usize,
+        spin_samples: usize,
+        inclination_samples: usize,
+        max_impact: f64,
+    ) -> Result<usize, JsValue> {
+        let spec = DeflectionLutSpec {
+            impact_range: (1.0, max_impact.max(2.0)),
+            impact_samples: impact_samples.max(2),
+            angle_samples: angle_samples.max(1),
+            spin_samples: spin_samples.max(1),
+            inclination_samples: inclination_samples.max(1),
+            ..DeflectionLutSpec::default()
+        };
+        let len = LUT_HEADER_LEN + LUT_CHANNELS * spec.len();
+        let end = OFFSET_LUTS + len;
+        if self.external_sab_ptr.is_some() && end > sab::SAB_LEN {
+            return Err(JsValue::from_str(&format!(
+                "deflection table of {len} floats does not fit in the {} left in the shared buffer",
+                sab::SAB_LEN - OFFSET_LUTS
+            )));
+        }
+        let data = DeflectionLut::generate(&spec).to_buffer();
+
+        let sab_ptr = match self.external_sab_ptr {
+            Some(ptr) => ptr,
+            None => {
+                if self.sab_buffer.len() < end {
+                    self.sab_buffer.resize(end, 0.0);
+                }
+                self.sab_buffer.as_mut_ptr()
+            }
+        };
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), sab_ptr.add(OFFSET_LUTS), data.len());
+        }
+        Ok(data.len())
+    }
+
     pub fn generate_spectrum_lut(
         &self,
         width: usize,
@@ -215,7 +328,22 @@ impl PhysicsEngine {
         gravitas::spacetime::curvature::kretschner_kerr(r, theta, self.mass, self.spin)
     }
 
-    /// FROM curvature.rs: Generate a 2D scalar field of curvature values.
+    /// FROM curvature.rs: All curvature invariants at a point, from the full Riemann tensor.
+    /// Returns [K, P, R, Re(I), Im(I), Re(J), Im(J)].
+    pub fn compute_curvature_invariants(&self, r: f64, theta: f64) -> Vec<f64> {
+        let inv = gravitas::spacetime::curvature::curvature_invariants(&self.metric_bl, r, theta);
+        vec![
+            inv.kretschmann,
+            inv.chern_pontryagin,
+            inv.ricci_scalar,
+            inv.weyl_i.re,
+            inv.weyl_i.im,
+            inv.weyl_j.re,
+            inv.weyl_j.im,
+        ]
+    }
+
+    /// FROM curvature.rs: Generate a 2D scalar field of Kretschmann values.
     /// Returns flat array of (r, theta, K) triples.
     pub fn generate_curvature_field(
         &self,
@@ -225,7 +353,12 @@ impl PhysicsEngine {
         n_polar: usize,
     ) -> Float32Array {
         let field = gravitas::spacetime::curvature::curvature_field(
-            self.mass, self.spin, r_min, r_max, n_radial, n_polar,
+            &self.metric_bl,
+            gravitas::spacetime::curvature::Invariant::Kretschmann,
+            r_min,
+            r_max,
+            n_radial,
+            n_polar,
         );
         let flat: Vec<f32> = field
             .iter()
@@ -334,7 +467,11 @@ impl PhysicsEngine {
                 zoom_delta,
                 dt,
             };
-            camera::update_camera(&input, &mut self.camera);
+            if self.geodesic_camera.is_some() {
+                self.advance_geodesic_camera(dt);
+            } else {
+                camera::update_camera(&input, &mut self.camera);
+            }
 
             if !self.camera.validate() {
                 self.camera = self.last_good_camera;
@@ -356,6 +493,23 @@ impl PhysicsEngine {
             *sab_ptr.add(OFFSET_CAMERA + 10) = self.camera.orientation.z as f32;
             *sab_ptr.add(OFFSET_CAMERA + 11) = self.camera.orientation.w as f32;
 
+            // 3.1 GEODESIC CAMERA: mode, proper time, termination, x^mu, u^mu
+            match &self.geodesic_camera {
+                Some(obs) => {
+                    let u = obs.velocity(&self.metric_ks);
+                    *sab_ptr.add(OFFSET_CAMERA + 12) = 1.0;
+                    *sab_ptr.add(OFFSET_CAMERA + 13) = obs.proper_time as f32;
+                    *sab_ptr.add(OFFSET_CAMERA + 14) = obs.termination as u8 as f32;
+                    for (mu, (x, u)) in obs.state.x.iter().zip(u).enumerate() {
+                        *sab_ptr.add(OFFSET_CAMERA + 16 + mu) = *x as f32;
+                        *sab_ptr.add(OFFSET_CAMERA + 20 + mu) = u as f32;
+                    }
+                }
+                None => {
+                    *sab_ptr.add(OFFSET_CAMERA + 12) = 0.0;
+                }
+            }
+
             // 4. WRITE PHYSICS (computed via gravitas-core)
             *sab_ptr.add(OFFSET_PHYSICS) = self.compute_horizon() as f32;
             *sab_ptr.add(OFFSET_PHYSICS + 1) = self.compute_isco() as f32;
@@ -379,9 +533,9 @@ impl PhysicsEngine {
                 let actual_points = curve.len().min(64);
                 *sab_ptr.add(OFFSET_PHYSICS + 15) = actual_points as f32; // Store point count
 
-                for i in 0..actual_points {
-                    *sab_ptr.add(OFFSET_PHYSICS + 16 + i * 2) = curve[i].0 as f32;
-                    *sab_ptr.add(OFFSET_PHYSICS + 16 + i * 2 + 1) = curve[i].1 as f32;
+                for (i, (alpha, beta)) in curve.iter().take(actual_points).enumerate() {
+                    *sab_ptr.add(OFFSET_PHYSICS + 16 + i * 2) = *alpha as f32;
+                    *sab_ptr.add(OFFSET_PHYSICS + 16 + i * 2 + 1) = *beta as f32;
                 }
 
                 // Extents for fast bounding box checks
@@ -462,4 +616,326 @@ impl PhysicsEngine {
             s.x[0], s.x[1], s.x[2], s.x[3], s.p[0], s.p[1], s.p[2], s.p[3],
         ]
     }
+
+    /// Initial states for every pixel of a ZAMO camera at (r, theta, phi).
+    ///
+    /// `projection`: 0 = pinhole, 1 = fisheye, 2 = equirectangular, 3 = cubemap
+    /// strip. Returns 8 values per pixel (x^mu then p_mu) in row-major order,
+    /// ready to pass to `integrate_ray_relativistic`. Pixels without a ray
+    /// (outside a fisheye circle) are filled with NaN.
+    pub fn generate_camera_rays(
+        &self,
+        r: f64,
+        theta: f64,
+        phi: f64,
+        fov: f64,
+        width: u32,
+        height: u32,
+        projection: u32,
+        use_kerr_schild: bool,
+    ) -> Vec<f64> {
+        let metric = if use_kerr_schild {
+            &self.metric_ks
+        } else {
+            &self.metric_bl
+        };
+        let camera = Camera::zamo(metric, [0.0, r, theta, phi], fov, width, height)
+            .with_projection(projection_from_index(projection));
+        flatten_camera_rays(&camera, metric, false)
+    }
+
+    /// Initial states for a camera carried by a moving observer, including
+    /// relativistic aberration and Doppler shift.
+    ///
+    /// `observer`: 0 = ZAMO, 1 = static, 2 = prograde Keplerian orbit (placed
+    /// in the equatorial plane), 3 = radial free fall from rest at infinity.
+    /// Returns 9 values per pixel: x^mu, p_mu, then nu_camera / nu_emit for
+    /// light from a source at rest at infinity. Returns an empty array if the
+    /// observer cannot exist at r (e.g. static inside the ergosphere).
+    pub fn generate_observer_camera_rays(
+        &self,
+        r: f64,
+        theta: f64,
+        phi: f64,
+        observer: u32,
+        fov: f64,
+        width: u32,
+        height: u32,
+        projection: u32,
+        use_kerr_schild: bool,
+    ) -> Vec<f64> {
+        let metric = if use_kerr_schild {
+            &self.metric_ks
+        } else {
+            &self.metric_bl
+        };
+        let theta = if observer == 2 {
+            std::f64::consts::FRAC_PI_2
+        } else {
+            theta
+        };
+        let velocity = match observer {
+            1 => tetrad::static_velocity(metric, r, theta),
+            2 => tetrad::keplerian_velocity(metric, r, Orbit::Prograde),
+            3 => tetrad::radial_infall_velocity(metric, r, theta),
+            _ => Some(tetrad::zamo_velocity(metric, r, theta)),
+        };
+        let Some(velocity) = velocity else {
+            return Vec::new();
+        };
+        let camera = Camera::new([0.0, r, theta, phi], velocity, fov, width, height)
+            .with_projection(projection_from_index(projection));
+        flatten_camera_rays(&camera, metric, true)
+    }
+
+    /// Initial states for a camera carried by an arbitrary observer, moving
+    /// with 3-velocity (v_r, v_theta, v_phi) in units of c relative to the
+    /// ZAMO at (r, theta, phi).
+    ///
+    /// Same layout as `generate_observer_camera_rays`. Returns an empty
+    /// array unless |v| < 1.
+    pub fn generate_moving_camera_rays(
+        &self,
+        r: f64,
+        theta: f64,
+        phi: f64,
+        v_r: f64,
+        v_theta: f64,
+        v_phi: f64,
+        fov: f64,
+        width: u32,
+        height: u32,
+        projection: u32,
+        use_kerr_schild: bool,
+    ) -> Vec<f64> {
+        let metric = if use_kerr_schild {
+            &self.metric_ks
+        } else {
+            &self.metric_bl
+        };
+        let position = [0.0, r, theta, phi];
+        let Some(camera) =
+            Camera::moving(metric, position, [v_r, v_theta, v_phi], fov, width, height)
+        else {
+            return Vec::new();
+        };
+        let camera = camera.with_projection(projection_from_index(projection));
+        flatten_camera_rays(&camera, metric, true)
+    }
+
+    /// Start a progressive CPU render from a ZAMO camera at (r, theta, phi).
+    ///
+    /// `order`: 0 = row-major, 1 = spiral, 2 = centre-out. `passes` coarse-to-fine
+    /// refinement passes are rendered, the first tracing one ray per
+    /// 2^(passes-1) pixel block. `disk_outer` is the outer radius of a
+    /// Novikov-Thorne disk, or 0 for no disk. Call `render_cpu_tiles`
+    /// repeatedly to make progress.
+    pub fn start_cpu_render(
+        &mut self,
+        r: f64,
+        theta: f64,
+        phi: f64,
+        fov: f64,
+        width: u32,
+        height: u32,
+        tile_size: u32,
+        order: u32,
+        passes: u32,
+        disk_outer: f64,
+        use_kerr_schild: bool,
+    ) {
+        let metric = if use_kerr_schild {
+            &self.metric_ks
+        } else {
+            &self.metric_bl
+        };
+        let camera = Camera::zamo(metric, [0.0, r, theta, phi], fov, width, height);
+        let mut settings = RenderSettings::default().with_background(Background::Checker {
+            divisions: 18,
+            colors: [[0.02, 0.02, 0.03], [0.08, 0.08, 0.1]],
+        });
+        if disk_outer > 0.0 {
+            settings = settings.with_disk(ThinDisk::novikov_thorne(metric, disk_outer));
+        }
+        let order = match order {
+            1 => TileOrder::Spiral,
+            2 => TileOrder::CenterOut,
+            _ => TileOrder::RowMajor,
+        };
+        self.cpu_render = Some(CpuRender {
+            camera,
+            settings,
+            scheduler: TileScheduler::new(width, height, tile_size)
+                .with_order(order)
+                .with_passes(passes),
+            image: Image::new(width, height),
+            use_kerr_schild,
+        });
+    }
+
+    /// Render up to `max_tiles` more tiles of the current CPU render.
+    ///
+    /// Returns the fraction of tiles done, 1.0 when complete (or when no
+    /// render has been started).
+    pub fn render_cpu_tiles(&mut self, max_tiles: u32) -> f32 {
+        let Some(job) = self.cpu_render.as_mut() else {
+            return 1.0;
+        };
+        let metric = if job.use_kerr_schild {
+            &self.metric_ks
+        } else {
+            &self.metric_bl
+        };
+        let renderer = Renderer::new(&job.camera, metric, &job.settings);
+        for _ in 0..max_tiles {
+            let Some(tile) = job.scheduler.pop_tile() else {
+                break;
+            };
+            renderer.render_tile(&tile, &mut job.image);
+        }
+        job.scheduler.progress()
+    }
+
+    /// Render the remaining tiles nearest the normalized image point (u, v) first.
+    pub fn focus_cpu_render(&mut self, u: f32, v: f32) {
+        if let Some(job) = self.cpu_render.as_mut() {
+            job.scheduler
+                .set_order(TileOrder::Priority(PriorityMap::gaze(u, v, 0.15)));
+        }
+    }
+
+    /// The current CPU render as linear RGBA floats in row-major order, or
+    /// an empty array if none has been started.
+    pub fn cpu_render_pixels(&self) -> Vec<f32> {
+        let Some(job) = self.cpu_render.as_ref() else {
+            return Vec::new();
+        };
+        job.image
+            .pixels
+            .iter()
+            .flat_map(|&[r, g, b]| [r, g, b, 1.0])
+            .collect()
+    }
+}
+
+/// Neural Radiance Surrogate (NRS) trainer: fits the deflection MLP to
+/// exact geodesics a few epochs per call, so the worker stays responsive.
+///
+/// The weight buffer layout is documented in `gravitas::render::surrogate`.
+#[wasm_bindgen]
+pub struct NrsTrainer {
+    trainer: SurrogateTrainer,
+}
+
+#[wasm_bindgen]
+impl NrsTrainer {
+    /// Trace the ground-truth rays up front; this is the slow part.
+    /// Throws if none of the sampled rays escape.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        training_samples: usize,
+        validation_samples: usize,
+        seed: u32,
+    ) -> Result<NrsTrainer, JsValue> {
+        let trainer = SurrogateTrainer::new(SurrogateSpec {
+            training_samples,
+            validation_samples,
+            seed: seed as u64,
+            ..SurrogateSpec::default()
+        })
+        .ok_or_else(|| JsValue::from_str("no sampled ray escaped the hole"))?;
+        Ok(NrsTrainer { trainer })
+    }
+
+    /// Run `epochs` passes over the training set; returns the training loss.
+    pub fn step(&mut self, epochs: usize) -> f64 {
+        for _ in 0..epochs {
+            self.trainer.train_epoch();
+        }
+        self.trainer.loss()
+    }
+
+    pub fn get_loss(&self) -> f64 {
+        self.trainer.loss()
+    }
+
+    pub fn get_epoch(&self) -> usize {
+        self.trainer.epoch()
+    }
+
+    /// Validation error: [relative RMS deflection, RMS deflection (rad),
+    /// max deflection (rad), RMS time delay (M), max time delay (M)].
+    pub fn validation_error(&self) -> Vec<f64> {
+        let error = self.trainer.validate();
+        vec![
+            error.relative_deflection,
+            error.rms[0],
+            error.max[0],
+            error.rms[1],
+            error.max[1],
+        ]
+    }
+
+    /// Current weights in the shader buffer layout.
+    pub fn get_weights(&self) -> Float32Array {
+        Float32Array::from(self.trainer.weights().as_slice())
+    }
+}
+
+fn projection_from_index(index: u32) -> Projection {
+    match index {
+        1 => Projection::Fisheye,
+        2 => Projection::Equirectangular,
+        3 => Projection::Cubemap,
+        _ => Projection::Pinhole,
+    }
+}
+
+impl PhysicsEngine {
+    /// Advance the geodesic camera by dt seconds and mirror its position
+    /// into the interactive camera state.
+    fn advance_geodesic_camera(&mut self, dt: f64) {
+        let Some(obs) = self.geodesic_camera.as_mut() else {
+            return;
+        };
+        if dt > 0.0 {
+            obs.advance(&self.metric_ks, dt * self.geodesic_time_scale);
+        }
+
+        // Kerr-Schild Cartesian coordinates, with the spin axis along +Y.
+        let [_, r, theta, phi] = obs.state.x;
+        let a = self.metric_ks.a();
+        let position = glam::DVec3::new(
+            theta.sin() * (r * phi.cos() - a * phi.sin()),
+            r * theta.cos(),
+            -theta.sin() * (r * phi.sin() + a * phi.cos()),
+        );
+        if dt > 0.0 && position.is_finite() {
+            self.camera.velocity = (position - self.camera.position) / dt;
+        }
+        if position.is_finite() {
+            self.camera.position = position;
+        }
+    }
+}
+
+/// Flatten a camera's rays to [x^mu, p_mu (, sky shift)] per pixel, NaN where
+/// the projection has no ray.
+fn flatten_camera_rays(camera: &Camera, metric: &Kerr, with_shift: bool) -> Vec<f64> {
+    let stride = if with_shift { 9 } else { 8 };
+    let rays = camera.rays(metric);
+    let mut out = Vec::with_capacity(rays.len() * stride);
+    for ray in rays {
+        match ray {
+            Some(ray) => {
+                out.extend_from_slice(&ray.x);
+                out.extend_from_slice(&ray.p);
+                if with_shift {
+                    out.push(camera.sky_frequency_shift(&ray));
+                }
+            }
+            None => out.extend(std::iter::repeat_n(f64::NAN, stride)),
+        }
+    }
+    out
 }
@@ -13,10 +13,41 @@
 ///  64 - 127  | CAMERA      | 16         | pos(3), pad, vel(3), pad, quat(4), ...
 /// 128 - 255  | PHYSICS     | 16         | horizon, isco, mass, spin, ...
 /// 256 - 511  | TELEMETRY   | 64         | sequence counter, frame time, ...
-/// 2048+      | LUTS        | variable   | Disk temperature LUT, Spectrum LUT
+/// 2048+      | LUTS        | variable   | Deflection LUT
+/// ```
+///
+/// LUTS block detail (relative to `OFFSET_LUTS`), written by
+/// `PhysicsEngine::write_deflection_lut`:
+///
+/// ```text
+///  0      | format version (1)
+///  1 -  4 | samples: impact b, position angle psi, spin a, inclination i
+///  5 -  6 | b range (M)
+///  7      | max spin (spins start at 0)
+///  8 -  9 | inclination range (rad)
+/// 10      | channels per node (4)
+/// 11 - 12 | observer radius, disk outer radius (M)
+/// 16+     | nodes, b fastest, then psi, a, i; per node: deflection (rad),
+///         | time delay (M), disk-hit radius (M), disk redshift g; -1 = none
+/// ```
+///
+/// CAMERA block detail (relative to `OFFSET_CAMERA`):
+///
+/// ```text
+///  0 -  2 | position (x, y, z), spin axis along +y
+///  4 -  6 | velocity (x, y, z) per second
+///  8 - 11 | orientation quaternion (x, y, z, w)
+/// 12      | mode: 0 = interactive, 1 = riding a geodesic
+/// 13      | proper time tau of the geodesic camera (M)
+/// 14      | termination code (0 = moving, 1 = stopped at min radius)
+/// 16 - 19 | x^mu = (t, r, theta, phi), Kerr-Schild
+/// 20 - 23 | u^mu, Kerr-Schild
 /// ```
 pub const OFFSET_CONTROL: usize = 0;
 pub const OFFSET_CAMERA: usize = 64;
 pub const OFFSET_PHYSICS: usize = 128;
 pub const OFFSET_TELEMETRY: usize = 256;
 pub const OFFSET_LUTS: usize = 2048;
+
+/// Size of the SharedArrayBuffer allocated by the JS bridge, in f32 elements.
+pub const SAB_LEN: usize = 2 * 1024 * 1024 / 4;
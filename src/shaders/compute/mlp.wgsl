@@ -1,34 +1,73 @@
 @group(0) @binding(0) var<uniform> u_mass : f32;
 @group(0) @binding(1) var<uniform> u_spin : f32;
 @group(0) @binding(2) var<storage, read> u_weights : array<f32>; // MLP Weights
+@group(0) @binding(3) var<storage, read_write> u_output : array<vec2<f32>>;
 
 // Neural Radiance Surrogate (NRS)
-// Layer 1: 3 -> 16
-// Layer 2: 16 -> 16
-// Layer 3: 16 -> 16
-// Layer 4: 16 -> 3 (Deflection, Redshift, Delay)
+//
+// Evaluates the deflection MLP trained by gravitas-core
+// (render/surrogate.rs). Weight buffer layout:
+//   0            version (1)
+//   1            L, number of weight layers
+//   2 .. 2+L     layer widths n_0 .. n_L
+//   16           (offset, scale) per input:  x' = (x - offset) * scale
+//   16 + 2 n_0   (offset, scale) per output: y = y' * scale + offset
+//   then         per layer: n_{l+1} x n_l weights (row per output), n_{l+1} biases
+// Hidden layers use tanh, the output layer is linear.
+//
+// Inputs:  (M/b, cos psi, sin psi, a, cos i)
+// Outputs: (deflection angle [rad], time delay [M])
 
-// Simplified forward pass for a single ray (workgroup size 64)
+const HEADER_LEN : u32 = 16u;
+// Widest layer; matches MLP_MAX_WIDTH in render/surrogate.rs.
+const MAX_WIDTH : u32 = 32u;
+
+fn nrs_evaluate(x : array<f32, 5>) -> vec2<f32> {
+    let layers = u32(u_weights[1]);
+    let n_in = u32(u_weights[2]);
+    let n_out = u32(u_weights[2u + layers]);
+    let out_norm = HEADER_LEN + 2u * n_in;
+    var cursor = out_norm + 2u * n_out;
+
+    var a = array<f32, MAX_WIDTH>();
+    for (var i = 0u; i < n_in; i = i + 1u) {
+        a[i] = (x[i] - u_weights[HEADER_LEN + 2u * i]) * u_weights[HEADER_LEN + 2u * i + 1u];
+    }
+
+    for (var l = 0u; l < layers; l = l + 1u) {
+        let cols = u32(u_weights[2u + l]);
+        let rows = u32(u_weights[3u + l]);
+        let bias = cursor + rows * cols;
+        var next = array<f32, MAX_WIDTH>();
+        for (var o = 0u; o < rows; o = o + 1u) {
+            var z = u_weights[bias + o];
+            for (var i = 0u; i < cols; i = i + 1u) {
+                z = z + u_weights[cursor + o * cols + i] * a[i];
+            }
+            next[o] = select(tanh(z), z, l + 1u == layers);
+        }
+        a = next;
+        cursor = bias + rows;
+    }
+
+    return vec2<f32>(
+        a[0] * u_weights[out_norm + 1u] + u_weights[out_norm],
+        a[1] * u_weights[out_norm + 3u] + u_weights[out_norm + 2u],
+    );
+}
+
+// One ray per invocation along the equator-on screen axis (workgroup size 64)
 @compute @workgroup_size(64)
 fn main(@builtin(global_invocation_id) global_id : vec3<u32>) {
-    // Input: Impact Parameter b, Polar Angle theta, Spin a
-    let b = f32(global_id.x) * 0.1 + 3.0; // Map ID to b-space [3M..]
-    let theta = 3.14159 * 0.5; // Equatorial plane
+    // Impact parameter b in units of M, from 8M outwards
+    let b = f32(global_id.x) * 0.1 + 8.0;
+    let psi = 0.0;
+    let inclination = 3.14159 * 0.5; // Equatorial observer
     let a = u_spin;
 
-    // Layer 1 (Input [b, theta, a])
-    var hidden1 = array<f32, 16>();
-    for (var i = 0u; i < 16u; i = i + 1u) {
-        var sum = 0.0;
-        sum = sum + b * u_weights[i * 3u + 0u];
-        sum = sum + theta * u_weights[i * 3u + 1u];
-        sum = sum + a * u_weights[i * 3u + 2u];
-        hidden1[i] = max(0.0, sum); // ReLU
-    }
-    
-    // ... (Full MLP Logic goes here) ...
-    // This shader calculates the deflecton angle alpha for a given (b, a)
-    // allowing "One-Step" ray tracing for distant objects.
-    
-    // Output stored in texture or buffer (omitted for brevity)
+    let x = array<f32, 5>(1.0 / b, cos(psi), sin(psi), a, cos(inclination));
+    let y = nrs_evaluate(x);
+
+    // Time delay scales with M; the deflection angle is dimensionless.
+    u_output[global_id.x] = vec2<f32>(y.x, y.y * u_mass);
 }